@@ -0,0 +1,203 @@
+// the alternative hosts of the production spot api, they may perform better but are less stable than api.binance.com, see
+// https://developers.binance.com/docs/binance-spot-api-docs/rest-api/general-api-information
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ApiCluster {
+    Gcp,
+    Api1,
+    Api2,
+    Api3,
+    Api4,
+}
+
+impl ApiCluster {
+    pub fn domain(&self) -> &'static str {
+        match self {
+            ApiCluster::Gcp => "api-gcp.binance.com",
+            ApiCluster::Api1 => "api1.binance.com",
+            ApiCluster::Api2 => "api2.binance.com",
+            ApiCluster::Api3 => "api3.binance.com",
+            ApiCluster::Api4 => "api4.binance.com",
+        }
+    }
+
+    pub fn all() -> [ApiCluster; 5] {
+        [ApiCluster::Gcp, ApiCluster::Api1, ApiCluster::Api2, ApiCluster::Api3, ApiCluster::Api4]
+    }
+}
+
+// where the spot REST api, streams and websocket api are served
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum SpotEndpoint {
+    Production,
+    // the production account served by api1.binance.com - api4.binance.com, the streams stay on production
+    Cluster(ApiCluster),
+    // https://testnet.binance.vision, a separate account with its own keys
+    Testnet,
+    // data-api.binance.vision and data-stream.binance.vision, public market data only, no trading or account endpoints
+    MarketData,
+}
+
+impl SpotEndpoint {
+    pub fn rest_domain(&self) -> &'static str {
+        match self {
+            SpotEndpoint::Production => "api.binance.com",
+            SpotEndpoint::Cluster(cluster) => cluster.domain(),
+            SpotEndpoint::Testnet => "testnet.binance.vision",
+            SpotEndpoint::MarketData => "data-api.binance.vision",
+        }
+    }
+
+    // the hosts serving the same account as the rest domain, the rest domain first,
+    // the REST client fails over among them when failover is enabled in its EndpointConfig
+    pub fn equivalent_rest_domains(&self) -> Vec<&'static str> {
+        match self {
+            SpotEndpoint::Production | SpotEndpoint::Cluster(_) => {
+                let mut domains = vec![self.rest_domain()];
+                let production = std::iter::once(SpotEndpoint::Production.rest_domain());
+                let clusters = ApiCluster::all().into_iter().map(|cluster| cluster.domain());
+                domains.extend(production.chain(clusters).filter(|domain| *domain != self.rest_domain()));
+                domains
+            }
+            SpotEndpoint::Testnet | SpotEndpoint::MarketData => vec![self.rest_domain()],
+        }
+    }
+
+    // the stream host without path, /ws and /stream are appended
+    pub fn stream_base_uri(&self) -> &'static str {
+        match self {
+            SpotEndpoint::Production | SpotEndpoint::Cluster(_) => "wss://stream.binance.com:9443",
+            SpotEndpoint::Testnet => "wss://stream.testnet.binance.vision",
+            SpotEndpoint::MarketData => "wss://data-stream.binance.vision",
+        }
+    }
+
+    pub fn websocket_api_uri(&self) -> &'static str {
+        match self {
+            SpotEndpoint::Production | SpotEndpoint::Cluster(_) | SpotEndpoint::MarketData => {
+                "wss://ws-api.binance.com:443/ws-api/v3"
+            }
+            SpotEndpoint::Testnet => "wss://ws-api.testnet.binance.vision/ws-api/v3",
+        }
+    }
+}
+
+// where the USD-M futures REST api and streams are served
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum FutureEndpoint {
+    Production,
+    // https://testnet.binancefuture.com, a separate account with its own keys
+    Testnet,
+}
+
+impl FutureEndpoint {
+    pub fn rest_domain(&self) -> &'static str {
+        match self {
+            FutureEndpoint::Production => "fapi.binance.com",
+            FutureEndpoint::Testnet => "testnet.binancefuture.com",
+        }
+    }
+
+    // the stream host without path, /ws and /stream are appended
+    pub fn stream_base_uri(&self) -> &'static str {
+        match self {
+            FutureEndpoint::Production => "wss://fstream.binance.com",
+            FutureEndpoint::Testnet => "wss://fstream.binancefuture.com",
+        }
+    }
+}
+
+// Environment decides the hosts every REST and websocket client talks to, it is set on the Config of the clients.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Environment {
+    spot: SpotEndpoint,
+    future: FutureEndpoint,
+}
+
+impl Environment {
+    pub fn new(spot: SpotEndpoint, future: FutureEndpoint) -> Self {
+        Environment { spot, future }
+    }
+
+    pub fn production() -> Self {
+        Environment::new(SpotEndpoint::Production, FutureEndpoint::Production)
+    }
+
+    // the spot testnet and the futures testnet, nothing is sent to production
+    pub fn testnet() -> Self {
+        Environment::new(SpotEndpoint::Testnet, FutureEndpoint::Testnet)
+    }
+
+    pub fn cluster(cluster: ApiCluster) -> Self {
+        Environment::new(SpotEndpoint::Cluster(cluster), FutureEndpoint::Production)
+    }
+
+    pub fn market_data() -> Self {
+        Environment::new(SpotEndpoint::MarketData, FutureEndpoint::Production)
+    }
+
+    pub fn get_spot(&self) -> SpotEndpoint {
+        self.spot
+    }
+    pub fn get_future(&self) -> FutureEndpoint {
+        self.future
+    }
+
+    pub fn spot_rest_domain(&self) -> &'static str {
+        self.spot.rest_domain()
+    }
+    pub fn spot_socket_uri(&self) -> String {
+        format!("{}/ws", self.spot.stream_base_uri())
+    }
+    pub fn spot_combined_socket_uri(&self) -> String {
+        format!("{}/stream", self.spot.stream_base_uri())
+    }
+    pub fn spot_websocket_api_uri(&self) -> &'static str {
+        self.spot.websocket_api_uri()
+    }
+    pub fn future_rest_domain(&self) -> &'static str {
+        self.future.rest_domain()
+    }
+    pub fn future_socket_uri(&self) -> String {
+        format!("{}/ws", self.future.stream_base_uri())
+    }
+    pub fn future_combined_socket_uri(&self) -> String {
+        format!("{}/stream", self.future.stream_base_uri())
+    }
+}
+
+impl Default for Environment {
+    fn default() -> Self {
+        Environment::production()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_environment() {
+        let production = Environment::default();
+        assert_eq!(production.spot_rest_domain(), "api.binance.com");
+        assert_eq!(production.spot_socket_uri(), "wss://stream.binance.com:9443/ws");
+        assert_eq!(production.future_combined_socket_uri(), "wss://fstream.binance.com/stream");
+
+        let cluster = Environment::cluster(ApiCluster::Api3);
+        assert_eq!(cluster.spot_rest_domain(), "api3.binance.com");
+        assert_eq!(cluster.spot_socket_uri(), production.spot_socket_uri());
+        let domains = cluster.get_spot().equivalent_rest_domains();
+        assert_eq!(domains.len(), 6);
+        assert_eq!(domains[0], "api3.binance.com");
+        assert!(domains.contains(&"api.binance.com"));
+        assert!(domains.contains(&"api-gcp.binance.com"));
+
+        let testnet = Environment::testnet();
+        assert_eq!(testnet.spot_rest_domain(), "testnet.binance.vision");
+        assert_eq!(testnet.future_rest_domain(), "testnet.binancefuture.com");
+        assert_eq!(testnet.get_spot().equivalent_rest_domains(), vec!["testnet.binance.vision"]);
+
+        let market_data = Environment::market_data();
+        assert_eq!(market_data.spot_rest_domain(), "data-api.binance.vision");
+        assert_eq!(market_data.spot_combined_socket_uri(), "wss://data-stream.binance.vision/stream");
+    }
+}
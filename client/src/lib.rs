@@ -1,4 +1,6 @@
 #![feature(unboxed_closures)]
 
+pub mod environment;
 pub mod rest;
-pub mod stream;
\ No newline at end of file
+pub mod stream;
+pub mod wsapi;
@@ -1,11 +1,13 @@
 use bytes::Bytes;
-use http_body_util::{Empty, Full};
+use http_body_util::{BodyExt, Empty, Full};
+use hyper::{Request, Response};
 use hyper::body::{Body, Frame};
 use std::convert::Infallible;
 use std::pin::Pin;
 use std::task::{Context, Poll};
 use pin_project::pin_project;
 use serde::Deserialize;
+use tower::BoxError;
 
 
 #[derive(Debug, Deserialize, Default)]
@@ -31,3 +33,29 @@ impl Body for RequestBody {
         }
     }
 }
+
+
+// a copy of a request which is sent again, the body is still empty above the authorization layer,
+// it is built there from the extensions
+pub(crate) fn copy_request(req: &Request<RequestBody>) -> Request<RequestBody> {
+    let mut request = Request::builder()
+        .method(req.method().clone())
+        .uri(req.uri().clone())
+        .version(req.version())
+        .body(RequestBody::Empty(Empty::<Bytes>::new()))
+        .unwrap();
+    *request.headers_mut() = req.headers().clone();
+    *request.extensions_mut() = req.extensions().clone();
+    request
+}
+
+// read the whole body of a response, the error code of binance is in the body
+pub(crate) async fn buffer_response<B>(resp: Response<B>) -> Result<Response<Bytes>, BoxError>
+where
+    B: Body<Data = Bytes>,
+    B::Error: Into<BoxError>,
+{
+    let (parts, body) = resp.into_parts();
+    let body = body.collect().await.map_err(Into::into)?.to_bytes();
+    Ok(Response::from_parts(parts, body))
+}
@@ -1,6 +1,10 @@
+use crate::environment::Environment;
 use crate::rest::body::RequestBody;
-use crate::rest::config::Config;
+use crate::rest::config::{Config, RateLimitMarket, RateLimitProfile};
 use crate::rest::extension::RequestExtension;
+use crate::rest::layer::circuit::types::CircuitState;
+use crate::rest::layer::endpoint::types::HostHealth;
+use crate::rest::layer::rate::types::usage::RateUsage;
 use async_trait::async_trait;
 use bytes::Bytes;
 use general::error::SdkError;
@@ -20,6 +24,21 @@ pub trait BinanceClient {
 
 #[async_trait]
 pub trait BinanceClientAction {
+    // the environment of the config the client is built with
+    fn get_environment(&self) -> &Environment;
+
+    // the health of the spot hosts the requests are sent to
+    fn get_hosts_health(&self) -> Vec<HostHealth>;
+
+    // the rate limit usage binance reported in the latest responses, a scheduler can throttle on it before a 429
+    fn get_rate_usage(&self) -> Vec<RateUsage>;
+
+    // the host classes whose requests are stopped after a 429, a 418 or a -1003, with the time they reopen
+    fn get_circuit_state(&self) -> Vec<CircuitState>;
+
+    // replace the rate limits of a market, e.g. by the rateLimits of its exchangeInfo
+    fn set_rate_limits(&self, market: RateLimitMarket, profile: &RateLimitProfile);
+
     async fn get<I, O>(
         &self,
         request: Option<I>,
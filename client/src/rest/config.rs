@@ -1,3 +1,7 @@
+use crate::environment::Environment;
+use crate::rest::layer::rate::store::{MemoryRateLimitStore, RateLimitStore};
+use general::enums::rate_limiter::{RateLimitType, RateLimitUnit, RateLimiter};
+use std::sync::Arc;
 use std::time::Duration;
 
 #[derive(Debug, Copy, Clone)]
@@ -19,9 +23,207 @@ impl WeightWindow {
         self.window_size
     }
 }
+
+#[derive(Debug, Copy, Clone)]
+pub struct EndpointConfig {
+    failover: bool,
+    probe_interval: Duration,
+    recover_after: Duration,
+}
+
+impl EndpointConfig {
+    // failover: send the spot requests to the fastest healthy host equivalent to the host of the environment
+    // probe_interval: how often the latency of the hosts is measured with /api/v3/ping
+    // recover_after: how long a failed host is skipped before it is tried again
+    pub fn new(failover: bool, probe_interval: Duration, recover_after: Duration) -> Self {
+        EndpointConfig {
+            failover,
+            probe_interval,
+            recover_after,
+        }
+    }
+    pub fn new_default() -> Self {
+        EndpointConfig::new(false, Duration::from_secs(60), Duration::from_secs(30))
+    }
+    pub fn failover(&self) -> bool {
+        self.failover
+    }
+    pub fn probe_interval(&self) -> Duration {
+        self.probe_interval
+    }
+    pub fn recover_after(&self) -> Duration {
+        self.recover_after
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum RateLimitMarket {
+    // /api of the spot hosts
+    Spot,
+    // /fapi of the USD-M futures hosts
+    Future,
+}
+
+// the rate limits of a market as listed in the rateLimits of its exchangeInfo: REQUEST_WEIGHT and RAW_REQUESTS
+// count per ip, ORDERS per account
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateLimitProfile {
+    rate_limits: Vec<RateLimiter>,
+}
+
+impl RateLimitProfile {
+    pub fn new(rate_limits: Vec<RateLimiter>) -> Self {
+        RateLimitProfile { rate_limits }
+    }
+
+    // https://developers.binance.com/docs/binance-spot-api-docs/rest-api/limits
+    pub fn spot_default() -> Self {
+        RateLimitProfile::new(vec![
+            rate_limit(RateLimitType::RequestWeight, RateLimitUnit::MINUTE, 1, 6000),
+            rate_limit(RateLimitType::ORDERS, RateLimitUnit::SECOND, 10, 100),
+            rate_limit(RateLimitType::ORDERS, RateLimitUnit::DAY, 1, 200000),
+            rate_limit(RateLimitType::RawRequests, RateLimitUnit::MINUTE, 5, 61000),
+        ])
+    }
+
+    // https://developers.binance.com/docs/derivatives/usds-margined-futures/general-info#limits
+    pub fn future_default() -> Self {
+        RateLimitProfile::new(vec![
+            rate_limit(RateLimitType::RequestWeight, RateLimitUnit::MINUTE, 1, 2400),
+            rate_limit(RateLimitType::ORDERS, RateLimitUnit::MINUTE, 1, 1200),
+            rate_limit(RateLimitType::ORDERS, RateLimitUnit::SECOND, 10, 300),
+        ])
+    }
+
+    // replace the REQUEST_WEIGHT limits by a single one of window_weight per window_size minutes
+    pub fn with_weight_window(mut self, weight_window: WeightWindow) -> Self {
+        self.rate_limits
+            .retain(|rate_limit| rate_limit.rate_limit_type != RateLimitType::RequestWeight);
+        self.rate_limits.insert(
+            0,
+            rate_limit(
+                RateLimitType::RequestWeight,
+                RateLimitUnit::MINUTE,
+                weight_window.window_size(),
+                weight_window.window_weight() as u32,
+            ),
+        );
+        self
+    }
+
+    pub fn get_rate_limits(&self) -> &[RateLimiter] {
+        &self.rate_limits
+    }
+
+    pub fn of_type(&self, rate_limit_type: RateLimitType) -> impl Iterator<Item = &RateLimiter> {
+        self.rate_limits
+            .iter()
+            .filter(move |rate_limit| rate_limit.rate_limit_type == rate_limit_type)
+    }
+}
+
+fn rate_limit(rate_limit_type: RateLimitType, interval: RateLimitUnit, interval_num: u64, limit: u32) -> RateLimiter {
+    RateLimiter {
+        rate_limit_type,
+        interval,
+        interval_num,
+        limit,
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum RateLimitMode {
+    // a request over the rate limit fails at once
+    Reject,
+    // a request over the rate limit waits in the line of its market and account until the windows have room for it,
+    // it fails when it would wait longer than the duration, the request timeout starts once it leaves the line
+    Queue(Duration),
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct RetryConfig {
+    max_retries: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    max_retry_after: Duration,
+}
+
+impl RetryConfig {
+    // max_retries: how many times a request is sent again after the first attempt
+    // initial_backoff: the delay before the first retry, it doubles with every retry until max_backoff
+    // max_retry_after: the longest Retry-After of a 429 or 418 which is waited for, a longer one is returned to the caller
+    pub fn new(max_retries: u32, initial_backoff: Duration, max_backoff: Duration, max_retry_after: Duration) -> Self {
+        RetryConfig {
+            max_retries,
+            initial_backoff,
+            max_backoff,
+            max_retry_after,
+        }
+    }
+    pub fn new_default() -> Self {
+        RetryConfig::new(3, Duration::from_millis(200), Duration::from_secs(5), Duration::from_secs(60))
+    }
+    pub fn disabled() -> Self {
+        RetryConfig::new(0, Duration::ZERO, Duration::ZERO, Duration::ZERO)
+    }
+    pub fn max_retries(&self) -> u32 {
+        self.max_retries
+    }
+    pub fn initial_backoff(&self) -> Duration {
+        self.initial_backoff
+    }
+    pub fn max_backoff(&self) -> Duration {
+        self.max_backoff
+    }
+    pub fn max_retry_after(&self) -> Duration {
+        self.max_retry_after
+    }
+
+    // retry starts from 1
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let factor = 2u32.checked_pow(retry.saturating_sub(1)).unwrap_or(u32::MAX);
+        self.initial_backoff.saturating_mul(factor).min(self.max_backoff)
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct CircuitBreakerConfig {
+    enabled: bool,
+    default_open: Duration,
+}
+
+impl CircuitBreakerConfig {
+    // enabled: a 429, a 418 or a -1003 stops the requests to the hosts of the same class until the ban ends
+    // default_open: how long the requests are stopped when the response has no Retry-After
+    pub fn new(enabled: bool, default_open: Duration) -> Self {
+        CircuitBreakerConfig { enabled, default_open }
+    }
+    pub fn new_default() -> Self {
+        CircuitBreakerConfig::new(true, Duration::from_secs(60))
+    }
+    pub fn disabled() -> Self {
+        CircuitBreakerConfig::new(false, Duration::ZERO)
+    }
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+    pub fn default_open(&self) -> Duration {
+        self.default_open
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct Config {
     request_timeout: Duration,
     weight_window_config: WeightWindow,
+    environment: Environment,
+    endpoint_config: EndpointConfig,
+    retry_config: RetryConfig,
+    rate_limit_mode: RateLimitMode,
+    spot_rate_limits: Option<RateLimitProfile>,
+    future_rate_limits: RateLimitProfile,
+    rate_limit_store: Arc<dyn RateLimitStore>,
+    circuit_breaker_config: CircuitBreakerConfig,
 }
 
 impl Config {
@@ -29,12 +231,84 @@ impl Config {
         Config {
             request_timeout: Duration::from_secs(5),
             weight_window_config: WeightWindow::new(6000,1),
+            environment: Environment::production(),
+            endpoint_config: EndpointConfig::new_default(),
+            retry_config: RetryConfig::new_default(),
+            rate_limit_mode: RateLimitMode::Reject,
+            spot_rate_limits: None,
+            future_rate_limits: RateLimitProfile::future_default(),
+            rate_limit_store: Arc::new(MemoryRateLimitStore::new()),
+            circuit_breaker_config: CircuitBreakerConfig::new_default(),
         }
     }
+    // how long a request may take once it is sent, the wait in line of RateLimitMode::Queue does not count
+    pub fn set_request_timeout(&mut self, request_timeout: Duration) {
+        self.request_timeout = request_timeout;
+    }
+    // the hosts of every client built with this config, production by default
+    pub fn set_environment(&mut self, environment: Environment) {
+        self.environment = environment;
+    }
+    pub fn set_endpoint_config(&mut self, endpoint_config: EndpointConfig) {
+        self.endpoint_config = endpoint_config;
+    }
+    pub fn set_retry_config(&mut self, retry_config: RetryConfig) {
+        self.retry_config = retry_config;
+    }
+    pub fn set_rate_limit_mode(&mut self, rate_limit_mode: RateLimitMode) {
+        self.rate_limit_mode = rate_limit_mode;
+    }
+    // the spot request weight limit while no spot profile is set
+    pub fn set_weight_window_config(&mut self, weight_window_config: WeightWindow) {
+        self.weight_window_config = weight_window_config;
+    }
+    // e.g. the rateLimits of the spot exchangeInfo
+    pub fn set_spot_rate_limits(&mut self, spot_rate_limits: RateLimitProfile) {
+        self.spot_rate_limits = Some(spot_rate_limits);
+    }
+    pub fn set_future_rate_limits(&mut self, future_rate_limits: RateLimitProfile) {
+        self.future_rate_limits = future_rate_limits;
+    }
+    // where the rate limit windows are kept, the clients built with this config share it. A FileRateLimitStore
+    // on the same path shares the windows with the other processes of the machine
+    pub fn set_rate_limit_store(&mut self, rate_limit_store: Arc<dyn RateLimitStore>) {
+        self.rate_limit_store = rate_limit_store;
+    }
+    pub fn set_circuit_breaker_config(&mut self, circuit_breaker_config: CircuitBreakerConfig) {
+        self.circuit_breaker_config = circuit_breaker_config;
+    }
     pub fn request_timeout(&self) -> Duration {
         self.request_timeout
     }
     pub fn weight_window_config(&self) -> WeightWindow {
         self.weight_window_config
     }
+    pub fn environment(&self) -> Environment {
+        self.environment
+    }
+    pub fn endpoint_config(&self) -> EndpointConfig {
+        self.endpoint_config
+    }
+    pub fn retry_config(&self) -> RetryConfig {
+        self.retry_config
+    }
+    pub fn rate_limit_mode(&self) -> RateLimitMode {
+        self.rate_limit_mode
+    }
+    // the profile set, or the spot default with the request weight of weight_window_config
+    pub fn spot_rate_limits(&self) -> RateLimitProfile {
+        match &self.spot_rate_limits {
+            Some(spot_rate_limits) => spot_rate_limits.clone(),
+            None => RateLimitProfile::spot_default().with_weight_window(self.weight_window_config),
+        }
+    }
+    pub fn future_rate_limits(&self) -> RateLimitProfile {
+        self.future_rate_limits.clone()
+    }
+    pub fn rate_limit_store(&self) -> Arc<dyn RateLimitStore> {
+        self.rate_limit_store.clone()
+    }
+    pub fn circuit_breaker_config(&self) -> CircuitBreakerConfig {
+        self.circuit_breaker_config
+    }
 }
\ No newline at end of file
@@ -11,7 +11,7 @@ use bytes::Bytes;
 use tower::Service;
 
 pub mod layer;
-mod sign;
+pub(crate) mod sign;
 pub mod types;
 
 #[derive(Debug, Clone)]
@@ -13,7 +13,11 @@ pub(crate) fn sign(payload: String, key: &str) -> BinanceResult<String> {
 
 #[cfg(not(feature = "sign_with_hmac"))]
 pub(crate) fn sign(payload: String, key: &str) -> BinanceResult<String> {
+    Ok(sign_ed25519(&payload, key)?)
+}
 
+// the websocket api only accepts Ed25519 keys for session.logon, so it is available whatever the REST signing is
+pub(crate) fn sign_ed25519(payload: &str, key: &str) -> Result<String, ed25519_dalek::pkcs8::Error> {
     use base64::{engine::general_purpose, Engine as _};
     use ed25519_dalek::pkcs8::DecodePrivateKey;
     use ed25519_dalek::SigningKey;
@@ -0,0 +1,33 @@
+use crate::rest::config::CircuitBreakerConfig;
+use crate::rest::layer::circuit::types::Circuits;
+use crate::rest::layer::circuit::CircuitBreaker;
+use tower::Layer;
+
+#[derive(Debug)]
+pub struct CircuitBreakerLayer {
+    config: CircuitBreakerConfig,
+    circuits: Circuits,
+}
+
+impl CircuitBreakerLayer {
+    pub fn new(config: CircuitBreakerConfig) -> Self {
+        CircuitBreakerLayer {
+            config,
+            circuits: Circuits::default(),
+        }
+    }
+
+    // the circuits are shared with the layers which must not send while a circuit is open
+    pub(crate) fn with_circuits(mut self, circuits: Circuits) -> Self {
+        self.circuits = circuits;
+        self
+    }
+}
+
+impl<I> Layer<I> for CircuitBreakerLayer {
+    type Service = CircuitBreaker<I>;
+
+    fn layer(&self, inner: I) -> Self::Service {
+        CircuitBreaker::new(inner, self.config).with_circuits(self.circuits.clone())
+    }
+}
@@ -0,0 +1,159 @@
+use crate::rest::body::{buffer_response, RequestBody};
+use crate::rest::config::CircuitBreakerConfig;
+use crate::rest::layer::circuit::types::{CircuitState, Circuits, HostClass};
+use crate::rest::layer::retry::policy::{error_code, retry_after};
+use crate::rest::layer::take_ready;
+use bytes::Bytes;
+use general::error::SdkError;
+use http_body::Body;
+use hyper::{Request, Response, StatusCode};
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::SystemTime;
+use tower::{BoxError, Service};
+
+pub mod layer;
+pub mod types;
+
+// -1003 too many requests, the ip is about to be banned
+const TOO_MANY_REQUESTS: i64 = -1003;
+
+// CircuitBreaker stops sending once binance rate limits the ip: a 429, a 418 or a -1003 opens the circuit of the
+// host class of the request for the Retry-After of the response, and while it is open the requests to that class
+// fail at once with SdkError::CircuitOpen instead of escalating the ban. The response comes out with its body read.
+#[derive(Debug, Clone)]
+pub struct CircuitBreaker<S> {
+    inner: S,
+    config: CircuitBreakerConfig,
+    circuits: Circuits,
+}
+
+impl<S> CircuitBreaker<S> {
+    pub fn new(inner: S, config: CircuitBreakerConfig) -> Self {
+        CircuitBreaker {
+            inner,
+            config,
+            circuits: Circuits::default(),
+        }
+    }
+    pub(crate) fn with_circuits(mut self, circuits: Circuits) -> Self {
+        self.circuits = circuits;
+        self
+    }
+    pub fn get_inner_ref(&self) -> &S {
+        &self.inner
+    }
+
+    // the circuits which are open now
+    pub fn get_circuit_state(&self) -> Vec<CircuitState> {
+        self.circuits.snapshot()
+    }
+
+    #[cfg(test)]
+    pub(crate) fn open_circuit(&self, host_class: HostClass, reopen_at: SystemTime) {
+        self.circuits.open(host_class, 429, reopen_at);
+    }
+}
+
+impl<S, B> Service<Request<RequestBody>> for CircuitBreaker<S>
+where
+    S: Service<Request<RequestBody>, Response = Response<B>> + Clone,
+    S::Future: Send + 'static,
+    S::Error: Into<BoxError>,
+    B: Body<Data = Bytes> + Send + 'static,
+    B::Error: Into<BoxError>,
+{
+    type Response = Response<Bytes>;
+    type Error = BoxError;
+    type Future = Pin<Box<dyn Future<Output = Result<Response<Bytes>, BoxError>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, req: Request<RequestBody>) -> Self::Future {
+        let host_class = HostClass::of(req.uri().path());
+        if self.config.enabled()
+            && let Some(reopen_at) = self.circuits.reopen_at(host_class)
+        {
+            // nothing is sent, the service made ready by poll_ready is given up for the other requests
+            drop(take_ready(&mut self.inner));
+            return Box::pin(async move { Err(SdkError::CircuitOpen(host_class.to_string(), reopen_at).into()) });
+        }
+        let future = self.inner.call(req);
+        let config = self.config;
+        let circuits = self.circuits.clone();
+        Box::pin(async move {
+            let resp = buffer_response(future.await.map_err(Into::into)?).await?;
+            if config.enabled() && is_rate_limited(&resp) {
+                let reopen_at = SystemTime::now() + retry_after(&resp).unwrap_or(config.default_open());
+                log::warn!(
+                    "{} answered {}, the {} hosts are closed until {:?}",
+                    host_class,
+                    resp.status(),
+                    host_class,
+                    reopen_at
+                );
+                circuits.open(host_class, resp.status().as_u16(), reopen_at);
+            }
+            Ok(resp)
+        })
+    }
+}
+
+fn is_rate_limited(resp: &Response<Bytes>) -> bool {
+    resp.status() == StatusCode::TOO_MANY_REQUESTS
+        || resp.status().as_u16() == 418
+        || error_code(resp) == Some(TOO_MANY_REQUESTS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http_body_util::{Empty, Full};
+    use hyper::header::RETRY_AFTER;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    fn request(path: &str) -> Request<RequestBody> {
+        Request::get(format!("https://api.binance.com{}", path))
+            .body(RequestBody::Empty(Empty::<Bytes>::new()))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_circuit_open() {
+        let sent = Arc::new(AtomicUsize::new(0));
+        let sent_requests = sent.clone();
+        let inner = tower::service_fn(move |_: Request<RequestBody>| {
+            sent_requests.fetch_add(1, Ordering::SeqCst);
+            async {
+                Ok::<_, BoxError>(
+                    Response::builder()
+                        .status(429)
+                        .header(RETRY_AFTER, "30")
+                        .body(Full::new(Bytes::from(r#"{"code":-1003,"msg":"Too many requests"}"#)))
+                        .unwrap(),
+                )
+            }
+        });
+        let mut breaker = CircuitBreaker::new(inner, CircuitBreakerConfig::new_default());
+        let resp = breaker.call(request("/api/v3/depth")).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+
+        let err = breaker.call(request("/api/v3/ticker/price")).await.unwrap_err();
+        let Some(SdkError::CircuitOpen(host_class, reopen_at)) = err.downcast_ref::<SdkError>() else {
+            panic!("{}", err);
+        };
+        assert_eq!(host_class, "api");
+        assert!(*reopen_at > SystemTime::now() + Duration::from_secs(25));
+        assert_eq!(sent.load(Ordering::SeqCst), 1);
+        assert_eq!(breaker.get_circuit_state().len(), 1);
+
+        // the requests to the sapi hosts are still sent
+        breaker.call(request("/sapi/v1/system/status")).await.unwrap();
+        assert_eq!(sent.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,123 @@
+use dashmap::DashMap;
+use std::fmt::{Display, Formatter};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+// the hosts binance bans together: the rate limits of /sapi are apart from those of /api, and the futures
+// have hosts of their own
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum HostClass {
+    Api,
+    Sapi,
+    Fapi,
+}
+
+impl HostClass {
+    pub fn of(path: &str) -> Self {
+        if path.starts_with("/sapi") {
+            HostClass::Sapi
+        } else if path.starts_with("/fapi") {
+            HostClass::Fapi
+        } else {
+            HostClass::Api
+        }
+    }
+}
+
+impl Display for HostClass {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HostClass::Api => write!(f, "api"),
+            HostClass::Sapi => write!(f, "sapi"),
+            HostClass::Fapi => write!(f, "fapi"),
+        }
+    }
+}
+
+// an open circuit: the requests to the hosts of the class fail without being sent until reopen_at
+#[derive(Debug, Clone)]
+pub struct CircuitState {
+    pub host_class: HostClass,
+    pub reopen_at: SystemTime,
+    // the status of the response which opened the circuit, 429 or 418
+    pub status: u16,
+    pub opened_at: SystemTime,
+}
+
+impl CircuitState {
+    pub fn is_open(&self) -> bool {
+        SystemTime::now() < self.reopen_at
+    }
+}
+
+// the circuits of every host class, shared by every clone of the client
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Circuits {
+    circuits: Arc<DashMap<HostClass, CircuitState>>,
+}
+
+impl Circuits {
+    // the reopen time when the circuit of the class is open
+    pub(crate) fn reopen_at(&self, host_class: HostClass) -> Option<SystemTime> {
+        self.circuits
+            .get(&host_class)
+            .filter(|circuit| circuit.is_open())
+            .map(|circuit| circuit.reopen_at)
+    }
+
+    // a later ban extends the circuit, an earlier one leaves it as it is
+    pub(crate) fn open(&self, host_class: HostClass, status: u16, reopen_at: SystemTime) {
+        let opened_at = SystemTime::now();
+        let mut circuit = self.circuits.entry(host_class).or_insert(CircuitState {
+            host_class,
+            reopen_at,
+            status,
+            opened_at,
+        });
+        if !circuit.is_open() || circuit.reopen_at < reopen_at {
+            *circuit = CircuitState {
+                host_class,
+                reopen_at,
+                status,
+                opened_at,
+            };
+        }
+    }
+
+    // the circuits which are open now
+    pub(crate) fn snapshot(&self) -> Vec<CircuitState> {
+        self.circuits
+            .iter()
+            .filter(|circuit| circuit.is_open())
+            .map(|circuit| circuit.value().clone())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_open_circuit() {
+        assert_eq!(HostClass::of("/sapi/v1/capital/config/getall"), HostClass::Sapi);
+        assert_eq!(HostClass::of("/fapi/v1/order"), HostClass::Fapi);
+        assert_eq!(HostClass::of("/api/v3/order"), HostClass::Api);
+
+        let circuits = Circuits::default();
+        let now = SystemTime::now();
+        circuits.open(HostClass::Api, 429, now + Duration::from_secs(30));
+        circuits.open(HostClass::Api, 429, now + Duration::from_secs(10));
+        assert_eq!(circuits.reopen_at(HostClass::Api), Some(now + Duration::from_secs(30)));
+        assert_eq!(circuits.reopen_at(HostClass::Sapi), None);
+        circuits.open(HostClass::Api, 418, now + Duration::from_secs(120));
+        assert_eq!(circuits.snapshot().len(), 1);
+        assert_eq!(circuits.snapshot()[0].status, 418);
+
+        // a ban which has ended does not stop the requests
+        circuits.open(HostClass::Sapi, 429, now - Duration::from_secs(1));
+        assert_eq!(circuits.reopen_at(HostClass::Sapi), None);
+        assert_eq!(circuits.snapshot().len(), 1);
+    }
+}
@@ -0,0 +1,36 @@
+use crate::environment::Environment;
+use crate::rest::config::EndpointConfig;
+use crate::rest::layer::circuit::types::Circuits;
+use crate::rest::layer::endpoint::Endpoint;
+use tower::Layer;
+
+#[derive(Debug)]
+pub struct EndpointLayer {
+    environment: Environment,
+    endpoint_config: EndpointConfig,
+    circuits: Circuits,
+}
+
+impl EndpointLayer {
+    pub fn new(environment: Environment, endpoint_config: EndpointConfig) -> Self {
+        EndpointLayer {
+            environment,
+            endpoint_config,
+            circuits: Circuits::default(),
+        }
+    }
+
+    // the circuits of the circuit breaker above, the latency probe pauses while the api circuit is open
+    pub(crate) fn with_circuits(mut self, circuits: Circuits) -> Self {
+        self.circuits = circuits;
+        self
+    }
+}
+
+impl<I> Layer<I> for EndpointLayer {
+    type Service = Endpoint<I>;
+
+    fn layer(&self, inner: I) -> Self::Service {
+        Endpoint::new(inner, self.environment, self.endpoint_config).with_circuits(self.circuits.clone())
+    }
+}
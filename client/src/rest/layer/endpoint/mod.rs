@@ -0,0 +1,334 @@
+use crate::environment::Environment;
+use crate::rest::body::{copy_request, RequestBody};
+use crate::rest::config::EndpointConfig;
+use crate::rest::extension::RequestExtension;
+use crate::rest::layer::circuit::types::{Circuits, HostClass};
+use crate::rest::layer::endpoint::types::{HostHealth, HostPool};
+use crate::rest::layer::retry::policy::RequestKind;
+use crate::rest::layer::take_ready;
+use bytes::Bytes;
+use http_body_util::Empty;
+use hyper::{Request, Response, StatusCode, Uri};
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use tower::{BoxError, Service, ServiceExt};
+
+pub mod layer;
+pub mod types;
+
+const PING_PATH: &str = "/api/v3/ping";
+
+// Endpoint carries the environment of a client, the api clients take the hosts of their requests from it.
+// The spot requests are sent to the fastest healthy host equivalent to the host of the environment: a host fails
+// on an error or a 5xx response. A request which could not connect, and a GET answered with a 5xx, is sent again
+// to the next host, any other request answered with a 5xx may have landed and is returned as it is.
+#[derive(Debug, Clone)]
+pub struct Endpoint<S> {
+    inner: S,
+    environment: Environment,
+    probe_interval: Duration,
+    hosts: HostPool,
+    circuits: Circuits,
+}
+
+impl<S> Endpoint<S> {
+    pub fn new(inner: S, environment: Environment, endpoint_config: EndpointConfig) -> Self {
+        let domains = if endpoint_config.failover() {
+            environment.get_spot().equivalent_rest_domains()
+        } else {
+            vec![environment.spot_rest_domain()]
+        };
+        Endpoint {
+            inner,
+            environment,
+            probe_interval: endpoint_config.probe_interval(),
+            hosts: HostPool::new(domains, endpoint_config.recover_after()),
+            circuits: Circuits::default(),
+        }
+    }
+    pub(crate) fn with_circuits(mut self, circuits: Circuits) -> Self {
+        self.circuits = circuits;
+        self
+    }
+    pub fn get_inner_ref(&self) -> &S {
+        &self.inner
+    }
+    pub fn get_environment(&self) -> &Environment {
+        &self.environment
+    }
+    // the spot hosts in the order of the config, with their latency and failures
+    pub fn get_hosts_health(&self) -> Vec<HostHealth> {
+        self.hosts.health()
+    }
+}
+
+impl<S, B> Endpoint<S>
+where
+    S: Service<Request<RequestBody>, Response = Response<B>> + Clone + Send + 'static,
+    S::Future: Send,
+    S::Error: Into<BoxError>,
+    B: Send + 'static,
+{
+    // measure the latency of every host periodically until the last clone of the client is dropped,
+    // the hosts are not pinged while the api circuit is open since binance bans the ip and not the host
+    fn spawn_probe(&self) {
+        if !self.hosts.is_failover() || !self.hosts.start_probe() {
+            return;
+        }
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        let mut inner = self.inner.clone();
+        let hosts = self.hosts.downgrade();
+        let circuits = self.circuits.clone();
+        let probe_interval = self.probe_interval;
+        runtime.spawn(async move {
+            while let Some(pool) = HostPool::from_weak(&hosts) {
+                for domain in pool.domains() {
+                    if circuits.reopen_at(HostClass::Api).is_some() {
+                        break;
+                    }
+                    let request = ping_request(domain);
+                    let start = Instant::now();
+                    match call(&mut inner, request).await {
+                        Ok(resp) if resp.status().is_success() => pool.record_latency(domain, start.elapsed()),
+                        Ok(resp) if is_banned(resp.status()) => {
+                            // the ip is rate limited, the host is fine and the other hosts would answer the same
+                            log::warn!("ping of {} answered {}", domain, resp.status());
+                            break;
+                        }
+                        Ok(resp) => {
+                            log::warn!("ping of {} answered {}", domain, resp.status());
+                            pool.record_failure(domain);
+                        }
+                        Err(err) => {
+                            log::warn!("ping of {} failed: {}", domain, err);
+                            pool.record_failure(domain);
+                        }
+                    }
+                }
+                drop(pool);
+                tokio::time::sleep(probe_interval).await;
+            }
+        });
+    }
+}
+
+impl<S, B> Service<Request<RequestBody>> for Endpoint<S>
+where
+    S: Service<Request<RequestBody>, Response = Response<B>> + Clone + Send + 'static,
+    S::Future: Send,
+    S::Error: Into<BoxError>,
+    B: Send + 'static,
+{
+    type Response = Response<B>;
+    type Error = BoxError;
+    type Future = Pin<Box<dyn Future<Output = Result<Response<B>, BoxError>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, req: Request<RequestBody>) -> Self::Future {
+        let domain = req.uri().authority().map(|authority| authority.host().to_string());
+        if !domain.is_some_and(|domain| self.hosts.contains(&domain)) {
+            let future = self.inner.call(req);
+            return Box::pin(async move { future.await.map_err(Into::into) });
+        }
+        self.spawn_probe();
+        Box::pin(send_with_failover(take_ready(&mut self.inner), self.hosts.clone(), req))
+    }
+}
+
+async fn send_with_failover<S, B>(
+    mut inner: S,
+    hosts: HostPool,
+    req: Request<RequestBody>,
+) -> Result<Response<B>, BoxError>
+where
+    S: Service<Request<RequestBody>, Response = Response<B>>,
+    S::Error: Into<BoxError>,
+{
+    let kind = RequestKind::of(&req);
+    let mut tried = vec![];
+    let mut domain = hosts.select();
+    loop {
+        tried.push(domain);
+        match call(&mut inner, with_domain(&req, domain)).await {
+            Ok(resp) => {
+                if !resp.status().is_server_error() {
+                    hosts.record_success(domain);
+                    return Ok(resp);
+                }
+                hosts.record_failure(domain);
+                if kind == RequestKind::Idempotent
+                    && let Some(next) = hosts.select_except(&tried)
+                {
+                    log::warn!("{} answered {}, failover to {}", domain, resp.status(), next);
+                    domain = next;
+                    continue;
+                }
+                return Ok(resp);
+            }
+            Err(err) => {
+                hosts.record_failure(domain);
+                // nothing reached binance when the connection failed, so the request is safe to send again
+                if is_connect_error(&err)
+                    && let Some(next) = hosts.select_except(&tried)
+                {
+                    log::warn!("connect to {} failed, failover to {}: {}", domain, next, err);
+                    domain = next;
+                    continue;
+                }
+                return Err(err);
+            }
+        }
+    }
+}
+
+async fn call<S>(inner: &mut S, req: Request<RequestBody>) -> Result<S::Response, BoxError>
+where
+    S: Service<Request<RequestBody>>,
+    S::Error: Into<BoxError>,
+{
+    inner.ready().await.map_err(Into::into)?.call(req).await.map_err(Into::into)
+}
+
+fn is_banned(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.as_u16() == 418
+}
+
+fn is_connect_error(err: &BoxError) -> bool {
+    err.downcast_ref::<hyper_util::client::legacy::Error>()
+        .is_some_and(|err| err.is_connect())
+}
+
+fn with_domain(req: &Request<RequestBody>, domain: &str) -> Request<RequestBody> {
+    let mut request = copy_request(req);
+    let mut uri_parts = req.uri().clone().into_parts();
+    uri_parts.authority = Some(domain.parse().expect("Failed to parse domain"));
+    *request.uri_mut() = Uri::from_parts(uri_parts).expect("Failed to parse URI");
+    request
+}
+
+fn ping_request(domain: &str) -> Request<RequestBody> {
+    let uri = Uri::builder()
+        .scheme("https")
+        .authority(domain)
+        .path_and_query(PING_PATH)
+        .build()
+        .unwrap();
+    Request::get(uri)
+        .extension(RequestExtension::none_auth_api(1))
+        .body(RequestBody::Empty(Empty::<Bytes>::new()))
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use general::error::ApplicationError;
+    use hyper::Method;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::SystemTime;
+    use std::sync::{Arc, Mutex};
+    use tower::util::BoxCloneService;
+
+    type TestService = BoxCloneService<Request<RequestBody>, Response<()>, BoxError>;
+
+    // answers with the status of the host, an empty host stands for any other, it fails for a host without status.
+    // the hosts of the requests other than the pings are recorded
+    fn endpoint(statuses: Vec<(&'static str, Option<StatusCode>)>) -> (Endpoint<TestService>, Arc<Mutex<Vec<String>>>) {
+        let sent = Arc::new(Mutex::new(vec![]));
+        let sent_hosts = sent.clone();
+        let inner = tower::service_fn(move |req: Request<RequestBody>| {
+            let host = req.uri().host().unwrap().to_string();
+            if req.uri().path() != PING_PATH {
+                sent_hosts.lock().unwrap().push(host.clone());
+            }
+            let status = statuses
+                .iter()
+                .find(|(domain, _)| *domain == host || domain.is_empty())
+                .and_then(|(_, status)| *status);
+            async move {
+                match status {
+                    Some(status) => Response::builder().status(status).body(()),
+                    None => return Err(ApplicationError::new("down".to_string()).into()),
+                }
+                .map_err(Into::into)
+            }
+        });
+        let config = EndpointConfig::new(true, Duration::from_secs(3600), Duration::from_secs(30));
+        (Endpoint::new(BoxCloneService::new(inner), Environment::production(), config), sent)
+    }
+
+    fn request(method: Method, domain: &str) -> Request<RequestBody> {
+        Request::builder()
+            .method(method)
+            .uri(format!("https://{}/api/v3/order", domain))
+            .extension(RequestExtension::none_auth_api(1))
+            .body(RequestBody::Empty(Empty::<Bytes>::new()))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_failover_on_server_error() {
+        let (mut endpoint, sent) = endpoint(vec![
+            ("api.binance.com", Some(StatusCode::BAD_GATEWAY)),
+            ("data-api.binance.vision", None),
+            ("", Some(StatusCode::OK)),
+        ]);
+        let resp = endpoint.call(request(Method::GET, "api.binance.com")).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert!(!endpoint.get_hosts_health()[0].healthy);
+        assert!(endpoint.get_hosts_health()[1..].iter().all(|host| host.healthy));
+        assert_ne!(sent.lock().unwrap().last().unwrap(), "api.binance.com");
+
+        // the requests to other hosts are not redirected
+        assert!(endpoint.call(request(Method::GET, "data-api.binance.vision")).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_no_failover_of_order_on_server_error() {
+        let (mut endpoint, sent) = endpoint(vec![("", Some(StatusCode::BAD_GATEWAY))]);
+        // the order may have landed, it is not sent again
+        let resp = endpoint.call(request(Method::POST, "api.binance.com")).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::BAD_GATEWAY);
+        assert_eq!(sent.lock().unwrap().len(), 1);
+
+        // a GET is tried on every host before the 5xx is returned
+        let resp = endpoint.call(request(Method::GET, "api.binance.com")).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::BAD_GATEWAY);
+        assert_eq!(sent.lock().unwrap().len(), 1 + endpoint.get_hosts_health().len());
+    }
+
+    // a probe which answers the pings with the status and counts them
+    fn probed_endpoint(status: StatusCode) -> (Endpoint<TestService>, Arc<AtomicUsize>) {
+        let pings = Arc::new(AtomicUsize::new(0));
+        let sent_pings = pings.clone();
+        let inner = tower::service_fn(move |_: Request<RequestBody>| {
+            sent_pings.fetch_add(1, Ordering::SeqCst);
+            async move { Response::builder().status(status).body(()).map_err(Into::into) }
+        });
+        let config = EndpointConfig::new(true, Duration::from_secs(3600), Duration::from_secs(30));
+        (Endpoint::new(BoxCloneService::new(inner), Environment::production(), config), pings)
+    }
+
+    #[tokio::test]
+    async fn test_no_probe_while_banned() {
+        let (endpoint, pings) = probed_endpoint(StatusCode::OK);
+        endpoint.circuits.open(HostClass::Api, 418, SystemTime::now() + Duration::from_secs(30));
+        endpoint.spawn_probe();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(pings.load(Ordering::SeqCst), 0);
+
+        // a ban of the ip is not a failure of the host, the round of pings ends at the first ban
+        let (endpoint, pings) = probed_endpoint(StatusCode::TOO_MANY_REQUESTS);
+        endpoint.spawn_probe();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(pings.load(Ordering::SeqCst), 1);
+        assert!(endpoint.get_hosts_health().iter().all(|host| host.healthy));
+    }
+}
@@ -0,0 +1,201 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant};
+
+// the health of a host as seen by the REST client
+#[derive(Debug, Clone)]
+pub struct HostHealth {
+    pub domain: &'static str,
+    pub healthy: bool,
+    // the smoothed round trip of /api/v3/ping, None until the host is probed successfully
+    pub latency: Option<Duration>,
+    pub consecutive_failures: u32,
+    // the host is skipped until then
+    pub unhealthy_until: Option<Instant>,
+}
+
+#[derive(Debug)]
+struct HostState {
+    domain: &'static str,
+    latency: Option<Duration>,
+    consecutive_failures: u32,
+    unhealthy_until: Option<Instant>,
+}
+
+impl HostState {
+    fn is_healthy(&self, now: Instant) -> bool {
+        self.unhealthy_until.is_none_or(|until| until <= now)
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct HostPoolState {
+    hosts: Mutex<Vec<HostState>>,
+    recover_after: Duration,
+    probe_started: AtomicBool,
+}
+
+// the equivalent hosts a request may be sent to, shared by every clone of the client
+#[derive(Debug, Clone)]
+pub(crate) struct HostPool {
+    state: Arc<HostPoolState>,
+}
+
+impl HostPool {
+    // domains: the equivalent hosts, the first one is preferred while no latency is known
+    pub(crate) fn new(domains: Vec<&'static str>, recover_after: Duration) -> Self {
+        let hosts = domains
+            .into_iter()
+            .map(|domain| HostState {
+                domain,
+                latency: None,
+                consecutive_failures: 0,
+                unhealthy_until: None,
+            })
+            .collect();
+        HostPool {
+            state: Arc::new(HostPoolState {
+                hosts: Mutex::new(hosts),
+                recover_after,
+                probe_started: AtomicBool::new(false),
+            }),
+        }
+    }
+
+    pub(crate) fn from_weak(state: &Weak<HostPoolState>) -> Option<Self> {
+        state.upgrade().map(|state| HostPool { state })
+    }
+
+    pub(crate) fn downgrade(&self) -> Weak<HostPoolState> {
+        Arc::downgrade(&self.state)
+    }
+
+    pub(crate) fn is_failover(&self) -> bool {
+        self.state.hosts.lock().unwrap().len() > 1
+    }
+
+    pub(crate) fn contains(&self, domain: &str) -> bool {
+        self.state.hosts.lock().unwrap().iter().any(|host| host.domain == domain)
+    }
+
+    pub(crate) fn domains(&self) -> Vec<&'static str> {
+        self.state.hosts.lock().unwrap().iter().map(|host| host.domain).collect()
+    }
+
+    // true only for the first caller, the latency probe is started once per pool
+    pub(crate) fn start_probe(&self) -> bool {
+        !self.state.probe_started.swap(true, Ordering::SeqCst)
+    }
+
+    // the fastest healthy host, the hosts without latency come after the measured ones.
+    // when every host failed, the one recovering first.
+    pub(crate) fn select(&self) -> &'static str {
+        self.select_except(&[]).unwrap_or_else(|| self.domains()[0])
+    }
+
+    pub(crate) fn select_except(&self, tried: &[&str]) -> Option<&'static str> {
+        let now = Instant::now();
+        let hosts = self.state.hosts.lock().unwrap();
+        let candidates = hosts
+            .iter()
+            .enumerate()
+            .filter(|(_, host)| !tried.contains(&host.domain));
+        let healthy = candidates
+            .clone()
+            .filter(|(_, host)| host.is_healthy(now))
+            .min_by_key(|(index, host)| (host.latency.is_none(), host.latency, *index));
+        match healthy {
+            Some((_, host)) => Some(host.domain),
+            None => candidates
+                .min_by_key(|(_, host)| host.unhealthy_until)
+                .map(|(_, host)| host.domain),
+        }
+    }
+
+    pub(crate) fn record_latency(&self, domain: &str, latency: Duration) {
+        self.update(domain, |host| {
+            // smooth the samples so that one slow ping does not move the requests
+            host.latency = Some(match host.latency {
+                Some(previous) => (previous * 4 + latency) / 5,
+                None => latency,
+            });
+            host.consecutive_failures = 0;
+            host.unhealthy_until = None;
+        });
+    }
+
+    pub(crate) fn record_success(&self, domain: &str) {
+        self.update(domain, |host| {
+            host.consecutive_failures = 0;
+            host.unhealthy_until = None;
+        });
+    }
+
+    pub(crate) fn record_failure(&self, domain: &str) {
+        let recover_after = self.state.recover_after;
+        self.update(domain, |host| {
+            host.consecutive_failures += 1;
+            host.unhealthy_until = Some(Instant::now() + recover_after);
+        });
+    }
+
+    pub(crate) fn health(&self) -> Vec<HostHealth> {
+        let now = Instant::now();
+        self.state
+            .hosts
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|host| HostHealth {
+                domain: host.domain,
+                healthy: host.is_healthy(now),
+                latency: host.latency,
+                consecutive_failures: host.consecutive_failures,
+                unhealthy_until: host.unhealthy_until.filter(|until| *until > now),
+            })
+            .collect()
+    }
+
+    fn update<F>(&self, domain: &str, update: F)
+    where
+        F: FnOnce(&mut HostState),
+    {
+        let mut hosts = self.state.hosts.lock().unwrap();
+        if let Some(host) = hosts.iter_mut().find(|host| host.domain == domain) {
+            update(host);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_host_selection() {
+        let pool = HostPool::new(
+            vec!["api.binance.com", "api1.binance.com", "api2.binance.com"],
+            Duration::from_secs(30),
+        );
+        assert_eq!(pool.select(), "api.binance.com");
+
+        pool.record_latency("api2.binance.com", Duration::from_millis(20));
+        pool.record_latency("api.binance.com", Duration::from_millis(50));
+        assert_eq!(pool.select(), "api2.binance.com");
+
+        pool.record_failure("api2.binance.com");
+        assert_eq!(pool.select(), "api.binance.com");
+        assert_eq!(pool.select_except(&["api.binance.com"]), Some("api1.binance.com"));
+
+        pool.record_failure("api.binance.com");
+        pool.record_failure("api1.binance.com");
+        assert_eq!(pool.select(), "api2.binance.com");
+        assert!(pool.health().iter().all(|host| !host.healthy));
+
+        pool.record_success("api1.binance.com");
+        assert_eq!(pool.select(), "api1.binance.com");
+        let health = pool.health();
+        assert_eq!(health[2].consecutive_failures, 1);
+        assert_eq!(health[2].latency, Some(Duration::from_millis(20)));
+    }
+}
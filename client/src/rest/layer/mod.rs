@@ -1,2 +1,11 @@
 pub mod authorization;
-pub(crate) mod rate;
\ No newline at end of file
+pub mod circuit;
+pub mod endpoint;
+pub mod rate;
+pub mod retry;
+
+// the service made ready by poll_ready for the call being made, the call may send later, so a clone takes its place
+pub(crate) fn take_ready<S: Clone>(inner: &mut S) -> S {
+    let clone = inner.clone();
+    std::mem::replace(inner, clone)
+}
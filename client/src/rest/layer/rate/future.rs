@@ -1,39 +1,53 @@
+use crate::rest::layer::rate::Reconciler;
 use general::error::ApplicationError;
+use hyper::Response;
 use pin_project::pin_project;
 use std::future::Future;
 use std::pin::Pin;
 use std::task::{Context, Poll};
 use tower::BoxError;
 
-#[pin_project]
-pub struct RateFuture<S> {
-    #[pin]
-    inner: S,
-    condition: bool,
+#[pin_project(project = RateFutureProj)]
+pub enum RateFuture<S, R> {
+    // the windows had room, the request is sent at once
+    Admitted(#[pin] S, Box<Reconciler>),
+    Rejected,
+    // the request waits in line for room in the windows before it is sent
+    Queued(Pin<Box<dyn Future<Output = Result<R, BoxError>> + Send>>),
 }
 
-impl<S> RateFuture<S> {
-    pub fn new(inner: S, condition: bool) -> Self {
-        RateFuture { inner, condition }
+impl<S, R> RateFuture<S, R> {
+    pub fn new(inner: S, condition: bool, reconciler: Reconciler) -> Self {
+        if condition {
+            RateFuture::Admitted(inner, Box::new(reconciler))
+        } else {
+            RateFuture::Rejected
+        }
     }
 }
 
-impl<S, R, E> Future for RateFuture<S>
+impl<S, B, E> Future for RateFuture<S, Response<B>>
 where
-    S: Future<Output = Result<R, E>>,
+    S: Future<Output = Result<Response<B>, E>>,
     E: Into<BoxError>,
 {
-    type Output = Result<R, BoxError>;
+    type Output = Result<Response<B>, BoxError>;
 
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
-        if self.condition {
-            let this = self.project();
-            match this.inner.poll(cx) {
-                Poll::Ready(v) => Poll::Ready(v.map_err(Into::into)),
+        match self.project() {
+            RateFutureProj::Admitted(inner, reconciler) => match inner.poll(cx) {
+                Poll::Ready(v) => {
+                    if let Ok(resp) = &v {
+                        reconciler.reconcile(resp.headers());
+                    }
+                    Poll::Ready(v.map_err(Into::into))
+                }
                 Poll::Pending => Poll::Pending,
+            },
+            RateFutureProj::Rejected => {
+                Poll::Ready(Err(ApplicationError::new("quoted rate limit".to_string()).into()))
             }
-        } else {
-            Poll::Ready(Err(ApplicationError::new("quoted rate limit".to_string()).into()))
+            RateFutureProj::Queued(queued) => queued.as_mut().poll(cx),
         }
     }
 }
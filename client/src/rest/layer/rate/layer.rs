@@ -1,12 +1,25 @@
+use crate::rest::config::{RateLimitMode, RateLimitProfile};
 use crate::rest::layer::rate::WeightRateLimiter;
+use crate::rest::layer::rate::store::RateLimitStore;
+use std::sync::Arc;
 use tower::Layer;
 
-#[derive(Debug, Default)]
-pub struct WeightRateLimitLayer;
+#[derive(Debug)]
+pub struct WeightRateLimitLayer {
+    mode: RateLimitMode,
+    store: Arc<dyn RateLimitStore>,
+    spot: RateLimitProfile,
+    future: RateLimitProfile,
+}
 
 impl WeightRateLimitLayer {
-    pub fn new() -> Self {
-        Default::default()
+    pub fn new(
+        mode: RateLimitMode,
+        store: Arc<dyn RateLimitStore>,
+        spot: RateLimitProfile,
+        future: RateLimitProfile,
+    ) -> Self {
+        WeightRateLimitLayer { mode, store, spot, future }
     }
 }
 
@@ -14,6 +27,6 @@ impl<I> Layer<I> for WeightRateLimitLayer  {
     type Service = WeightRateLimiter<I>;
 
     fn layer(&self, inner: I) -> Self::Service {
-        WeightRateLimiter::new_with_default(inner)
+        WeightRateLimiter::new(inner, self.mode, &self.store, &self.spot, &self.future)
     }
 }
@@ -1,45 +1,147 @@
 mod future;
 pub(crate) mod layer;
+pub mod store;
 pub mod types;
 
 use crate::rest::body::RequestBody;
+use crate::rest::config::{RateLimitMarket, RateLimitMode, RateLimitProfile};
 use crate::rest::extension::RequestExtension;
 use crate::rest::layer::rate::future::RateFuture;
-use hyper::Request;
+use crate::rest::layer::rate::store::RateLimitStore;
+use crate::rest::layer::take_ready;
+use dashmap::DashMap;
+use general::error::SdkError;
+use hyper::{HeaderMap, Request, Response};
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
 use std::task::{Context, Poll};
-use tower::{BoxError, Service};
+use std::time::Duration;
+use tokio::sync::Semaphore;
+use tokio::time::Instant;
+use tower::{BoxError, Service, ServiceExt};
 use crate::rest::layer::rate::types::ip_handle::IpWeightHandle;
 use crate::rest::layer::rate::types::order_handle::OrderHandle;
 use crate::rest::layer::rate::types::uid_handle::UidWeightHandle;
+use crate::rest::layer::rate::types::usage::{parse_usage, RateUsage, UsageHandle};
+use crate::rest::layer::rate::types::window::WeightWindow;
 
-#[derive(Clone, Debug)]
+// the requests of a market and an account wait in a line of their own, one window set does not hold back another
+type QueueKey = (RateLimitMarket, Option<u64>);
+
+#[derive(Clone)]
 pub struct WeightRateLimiter<S> {
     inner: S,
     ip_weight_rate_handle: IpWeightHandle,
     uid_weight_handle: UidWeightHandle,
     order_handle: OrderHandle,
+    usage_handle: UsageHandle,
+    mode: RateLimitMode,
+    // one permit per line, the requests waiting for room in the windows are served first in first out
+    queues: Arc<DashMap<QueueKey, Arc<Semaphore>>>,
 }
 
 impl<S> WeightRateLimiter<S>
 {
-    pub(crate) fn new_with_default(inner: S) -> Self {
+    pub(crate) fn new(
+        inner: S,
+        mode: RateLimitMode,
+        store: &Arc<dyn RateLimitStore>,
+        spot: &RateLimitProfile,
+        future: &RateLimitProfile,
+    ) -> Self {
         WeightRateLimiter {
             inner,
-            ip_weight_rate_handle: IpWeightHandle::new_default(),
-            uid_weight_handle: UidWeightHandle::new_with_default(),
-            order_handle: OrderHandle::new_with_default(),
+            ip_weight_rate_handle: IpWeightHandle::new(store, spot, future),
+            uid_weight_handle: UidWeightHandle::new_with_default(store),
+            order_handle: OrderHandle::new(store, spot, future),
+            usage_handle: UsageHandle::default(),
+            mode,
+            queues: Arc::new(DashMap::new()),
+        }
+    }
+
+    // the usage binance reported in the latest responses, the ip weight, the sapi uid weight and the order count
+    pub fn get_rate_usage(&self) -> Vec<RateUsage> {
+        self.usage_handle.snapshot()
+    }
+
+    // replace the limits of a market, e.g. by the rateLimits of its exchangeInfo, for every clone of the client
+    pub fn set_rate_limits(&self, market: RateLimitMarket, profile: &RateLimitProfile) {
+        self.ip_weight_rate_handle.set_rate_limits(market, profile);
+        self.order_handle.set_rate_limits(market, profile);
+    }
+
+    fn reconciler(&self, uid: Option<u64>, market: RateLimitMarket) -> Reconciler {
+        Reconciler {
+            ip_weight_rate_handle: self.ip_weight_rate_handle.clone(),
+            uid_weight_handle: self.uid_weight_handle.clone(),
+            order_handle: self.order_handle.clone(),
+            usage_handle: self.usage_handle.clone(),
+            uid,
+            market,
+        }
+    }
+
+    fn queue(&self, key: QueueKey) -> Arc<Semaphore> {
+        self.queues.entry(key).or_insert_with(|| Arc::new(Semaphore::new(1))).clone()
+    }
+}
+
+// the local windows of a request, they take the usage binance reports in the response, which counts the requests
+// of every process sharing the ip or the account
+#[derive(Debug, Clone)]
+pub struct Reconciler {
+    ip_weight_rate_handle: IpWeightHandle,
+    uid_weight_handle: UidWeightHandle,
+    order_handle: OrderHandle,
+    usage_handle: UsageHandle,
+    uid: Option<u64>,
+    market: RateLimitMarket,
+}
+
+impl Reconciler {
+    pub(crate) fn reconcile(&mut self, headers: &HeaderMap) {
+        for mut usage in parse_usage(headers, self.uid) {
+            self.ip_weight_rate_handle.reconcile(&mut usage, self.market);
+            self.uid_weight_handle.reconcile(&mut usage);
+            self.order_handle.reconcile(&mut usage, self.market);
+            self.usage_handle.update(usage);
         }
     }
 }
 
-impl<S> Service<Request<RequestBody>> for WeightRateLimiter<S>
+// the usd-m futures live under /fapi, everything else counts against the spot limits
+fn rate_limit_market(path: &str) -> RateLimitMarket {
+    if path.starts_with("/fapi") {
+        RateLimitMarket::Future
+    } else {
+        RateLimitMarket::Spot
+    }
+}
+
+impl<S: Debug> Debug for WeightRateLimiter<S> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WeightRateLimiter")
+            .field("inner", &self.inner)
+            .field("ip_weight_rate_handle", &self.ip_weight_rate_handle)
+            .field("uid_weight_handle", &self.uid_weight_handle)
+            .field("order_handle", &self.order_handle)
+            .field("usage_handle", &self.usage_handle)
+            .field("mode", &self.mode)
+            .finish()
+    }
+}
+
+impl<S, B> Service<Request<RequestBody>> for WeightRateLimiter<S>
 where
-    S: Service<Request<RequestBody>>,
+    S: Service<Request<RequestBody>, Response = Response<B>> + Clone + Send + 'static,
+    S::Future: Send,
     S::Error: Into<BoxError>,
+    B: Send + 'static,
 {
-    type Response = S::Response;
+    type Response = Response<B>;
     type Error = BoxError;
-    type Future = RateFuture<S::Future>;
+    type Future = RateFuture<S::Future, Response<B>>;
     fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
         self.inner.poll_ready(cx).map_err(Into::into)
     }
@@ -49,18 +151,160 @@ where
         let ip_rate = RequestExtension::explain_request_ip_rate(req.extensions());
         let uid_rate = RequestExtension::explain_request_uid_rate(req.extensions());
         let order_rate = RequestExtension::explain_request_order_rate(req.extensions());
-        let condition = {
-            let uid_check = self.uid_weight_handle.available(weight, uid_rate);
-            let order_check = self.order_handle.available(order_rate);
-            let ip_check = self.ip_weight_rate_handle.available(weight, ip_rate);
-            if uid_check && order_check && ip_check {
-                true
-            } else {
-                false
+        let market = rate_limit_market(req.uri().path());
+        let uid = uid_rate.or(order_rate).and_then(|rate| rate.get_uid().ok());
+        let mut reconciler = self.reconciler(uid, market);
+        let mut windows = self.uid_weight_handle.windows(weight, uid_rate);
+        windows.extend(self.order_handle.windows(order_rate, market));
+        windows.extend(self.ip_weight_rate_handle.windows(weight, ip_rate, market));
+        let max_wait = match self.mode {
+            RateLimitMode::Reject => {
+                let condition = WeightWindow::acquire(&windows) == Duration::ZERO;
+                return RateFuture::new(self.inner.call(req), condition, reconciler);
             }
+            RateLimitMode::Queue(max_wait) => max_wait,
         };
 
-        RateFuture::new(self.inner.call(req), condition)
+        let queue = self.queue((market, uid));
+        let mut inner = take_ready(&mut self.inner);
+        RateFuture::Queued(Box::pin(async move {
+            let deadline = Instant::now() + max_wait;
+            let permit = match tokio::time::timeout_at(deadline, queue.acquire_owned()).await {
+                Ok(permit) => permit.expect("the rate limit queue is never closed"),
+                Err(_) => return Err(SdkError::RateLimitWaitExceeded(max_wait).into()),
+            };
+            loop {
+                let wait = WeightWindow::acquire(&windows);
+                if wait == Duration::ZERO {
+                    break;
+                }
+                // fail now rather than hold the line for a wait which ends after the deadline
+                if Instant::now() + wait > deadline {
+                    return Err(SdkError::RateLimitWaitExceeded(max_wait).into());
+                }
+                tokio::time::sleep(wait).await;
+            }
+            drop(permit);
+            let resp = inner.ready().await.map_err(Into::into)?.call(req).await.map_err(Into::into)?;
+            reconciler.reconcile(resp.headers());
+            Ok(resp)
+        }))
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rest::layer::authorization::types::{AuthType, Certificate};
+    use crate::rest::layer::rate::store::MemoryRateLimitStore;
+    use crate::rest::layer::rate::types::usage::UsageKind;
+    use bytes::Bytes;
+    use general::enums::rate_limiter::{RateLimitType, RateLimitUnit, RateLimiter};
+    use http_body_util::Empty;
+
+    fn rate_limiter<S>(inner: S, mode: RateLimitMode) -> WeightRateLimiter<S> {
+        let store: Arc<dyn RateLimitStore> = Arc::new(MemoryRateLimitStore::new());
+        WeightRateLimiter::new(inner, mode, &store, &RateLimitProfile::spot_default(), &RateLimitProfile::future_default())
+    }
+
+    fn order_request() -> Request<RequestBody> {
+        Request::post("https://api.binance.com/api/v3/order")
+            .extension(RequestExtension::auth_order_api(
+                AuthType::Trade,
+                1,
+                Certificate::new("key", "secret"),
+                7,
+            ))
+            .body(RequestBody::Empty(Empty::<Bytes>::new()))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_queue_wait_exceeded() {
+        let inner = tower::service_fn(|_: Request<RequestBody>| async { Ok::<_, BoxError>(Response::new(())) });
+        let mut limiter = rate_limiter(inner, RateLimitMode::Queue(Duration::from_millis(100)));
+        // 100 orders per 10 seconds
+        for _ in 0..100 {
+            limiter.ready().await.unwrap().call(order_request()).await.unwrap();
+        }
+        let err = limiter.ready().await.unwrap().call(order_request()).await.unwrap_err();
+        assert!(matches!(err.downcast_ref::<SdkError>(), Some(SdkError::RateLimitWaitExceeded(_))));
+
+        let mut limiter = rate_limiter(inner, RateLimitMode::Reject);
+        for _ in 0..100 {
+            limiter.call(order_request()).await.unwrap();
+        }
+        assert!(limiter.call(order_request()).await.is_err());
+    }
+
+    fn get_request(path: &str) -> Request<RequestBody> {
+        Request::get(format!("https://api.binance.com{}", path))
+            .extension(RequestExtension::none_auth_api(1))
+            .body(RequestBody::Empty(Empty::<Bytes>::new()))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_queue_order() {
+        let inner = tower::service_fn(|_: Request<RequestBody>| async { Ok::<_, BoxError>(Response::new(())) });
+        let store: Arc<dyn RateLimitStore> = Arc::new(MemoryRateLimitStore::new());
+        // one spot request per second
+        let spot = RateLimitProfile::new(vec![RateLimiter {
+            rate_limit_type: RateLimitType::RequestWeight,
+            interval: RateLimitUnit::SECOND,
+            interval_num: 1,
+            limit: 1,
+        }]);
+        let mut limiter = WeightRateLimiter::new(
+            inner,
+            RateLimitMode::Queue(Duration::from_secs(3)),
+            &store,
+            &spot,
+            &RateLimitProfile::future_default(),
+        );
+        limiter.ready().await.unwrap().call(get_request("/api/v3/time")).await.unwrap();
+
+        // the second spot request waits for the window at the head of the spot line, the third waits behind it
+        let start = Instant::now();
+        let mut second = limiter.clone();
+        let mut third = limiter.clone();
+        let second = tokio::spawn(async move { second.ready().await?.call(get_request("/api/v3/time")).await.map(|_| Instant::now()) });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        let third = tokio::spawn(async move { third.ready().await?.call(get_request("/api/v3/time")).await.map(|_| Instant::now()) });
+
+        // the futures have a line of their own
+        limiter.ready().await.unwrap().call(get_request("/fapi/v1/time")).await.unwrap();
+        assert!(start.elapsed() < Duration::from_millis(500));
+        assert!(!second.is_finished());
+
+        let second = second.await.unwrap().unwrap();
+        let third = third.await.unwrap().unwrap();
+        assert!(second < third);
+        assert!(third - start > Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn test_reconcile_usage() {
+        let inner = tower::service_fn(|_: Request<RequestBody>| async {
+            Ok::<_, BoxError>(
+                Response::builder()
+                    .header("X-MBX-USED-WEIGHT-1M", "1200")
+                    .header("X-MBX-ORDER-COUNT-1D", "200000")
+                    .body(())
+                    .unwrap(),
+            )
+        });
+        let mut limiter = rate_limiter(inner, RateLimitMode::Queue(Duration::from_millis(100)));
+        limiter.ready().await.unwrap().call(order_request()).await.unwrap();
+
+        let mut usage = limiter.get_rate_usage();
+        usage.sort_by_key(|usage| usage.kind as u8);
+        assert_eq!(usage.len(), 2);
+        assert_eq!((usage[0].kind, usage[0].used, usage[0].limit, usage[0].uid), (UsageKind::IpWeight, 1200, Some(6000), None));
+        assert_eq!((usage[1].kind, usage[1].used, usage[1].limit, usage[1].uid), (UsageKind::OrderCount, 200000, Some(200000), Some(7)));
+
+        // binance counted every order of the day, the next one waits until tomorrow
+        let err = limiter.ready().await.unwrap().call(order_request()).await.unwrap_err();
+        assert!(matches!(err.downcast_ref::<SdkError>(), Some(SdkError::RateLimitWaitExceeded(_))));
+    }
+}
@@ -0,0 +1,205 @@
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+// the state of a rate limit window: the weight used in it and its last second
+pub type WindowState = (u32, u64);
+
+// where the rate limit windows keep their state, every limiter using the same store counts against the same windows.
+// update changes the states of the windows, given by their key and the state a missing window starts with, at once:
+// no other update sees a window between the reading of the states and their writing. f gets the states in the
+// order of windows, they are returned after f changed them.
+pub trait RateLimitStore: Debug + Send + Sync {
+    fn update(&self, windows: &[(&str, WindowState)], f: &mut dyn FnMut(&mut [WindowState])) -> Vec<WindowState>;
+}
+
+// the windows of this process, the default store
+#[derive(Debug, Default)]
+pub struct MemoryRateLimitStore {
+    windows: Mutex<HashMap<String, WindowState>>,
+}
+
+impl MemoryRateLimitStore {
+    pub fn new() -> Self {
+        MemoryRateLimitStore::default()
+    }
+}
+
+impl RateLimitStore for MemoryRateLimitStore {
+    fn update(&self, windows: &[(&str, WindowState)], f: &mut dyn FnMut(&mut [WindowState])) -> Vec<WindowState> {
+        let mut stored = self.windows.lock().unwrap();
+        update_windows(&mut stored, windows, f)
+    }
+}
+
+fn update_windows(
+    stored: &mut HashMap<String, WindowState>,
+    windows: &[(&str, WindowState)],
+    f: &mut dyn FnMut(&mut [WindowState]),
+) -> Vec<WindowState> {
+    let mut states = windows
+        .iter()
+        .map(|(key, init)| *stored.get(*key).unwrap_or(init))
+        .collect::<Vec<_>>();
+    f(&mut states);
+    for ((key, _), state) in windows.iter().zip(&states) {
+        stored.insert(key.to_string(), *state);
+    }
+    states
+}
+
+// the windows of every process on the machine opening the same file, e.g. bots sharing one ip and one api key.
+// each update holds an exclusive lock on the file while it reads, changes and writes the windows back.
+#[derive(Debug)]
+pub struct FileRateLimitStore {
+    path: PathBuf,
+    // the file lock is held per open file, the mutex keeps the threads of this process apart
+    file: Mutex<File>,
+    // the windows as last written to the file, they keep counting while the file cannot be used
+    last_known: MemoryRateLimitStore,
+}
+
+impl FileRateLimitStore {
+    pub fn open(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&path)?;
+        Ok(FileRateLimitStore {
+            path,
+            file: Mutex::new(file),
+            last_known: MemoryRateLimitStore::new(),
+        })
+    }
+
+    pub fn get_path(&self) -> &Path {
+        &self.path
+    }
+
+    fn read_windows(file: &mut File) -> std::io::Result<HashMap<String, WindowState>> {
+        let mut content = String::new();
+        file.seek(SeekFrom::Start(0))?;
+        file.read_to_string(&mut content)?;
+        // an empty or broken file counts as no windows, the next write replaces it
+        Ok(serde_json::from_str(&content).unwrap_or_default())
+    }
+
+    fn write_windows(file: &mut File, windows: &HashMap<String, WindowState>) -> std::io::Result<()> {
+        let content = serde_json::to_vec(windows)?;
+        file.seek(SeekFrom::Start(0))?;
+        file.set_len(0)?;
+        file.write_all(&content)?;
+        file.flush()
+    }
+
+    fn locked_update(
+        file: &mut File,
+        windows: &[(&str, WindowState)],
+        f: &mut dyn FnMut(&mut [WindowState]),
+    ) -> std::io::Result<Vec<WindowState>> {
+        let mut stored = Self::read_windows(file)?;
+        let current_time = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        // the windows which ended are dropped so the file does not grow with every uid
+        stored.retain(|key, (_, window_end)| *window_end >= current_time || windows.iter().any(|(window_key, _)| window_key == key));
+        let states = update_windows(&mut stored, windows, f);
+        Self::write_windows(file, &stored)?;
+        Ok(states)
+    }
+}
+
+impl RateLimitStore for FileRateLimitStore {
+    fn update(&self, windows: &[(&str, WindowState)], f: &mut dyn FnMut(&mut [WindowState])) -> Vec<WindowState> {
+        let mut file = self.file.lock().unwrap();
+        let result = match file.lock() {
+            Ok(()) => {
+                let result = Self::locked_update(&mut file, windows, f);
+                file.unlock().and(result)
+            }
+            Err(err) => Err(err),
+        };
+        match result {
+            Ok(states) => {
+                self.last_known.update(windows, &mut |last_known| last_known.copy_from_slice(&states));
+                states
+            }
+            // without the file the process keeps counting its own requests from the last known windows
+            Err(err) => {
+                log::error!("rate limit store {} failed: {}", self.path.display(), err);
+                self.last_known.update(windows, f)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rest::layer::rate::types::window::{WeightWindow, WindowUnit};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    fn temp_path(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("rate-limit-store-{}-{}.json", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn test_file_store_shared() {
+        let path = temp_path("shared");
+        let first = FileRateLimitStore::open(&path).unwrap();
+        let second = FileRateLimitStore::open(&path).unwrap();
+        let end = u64::MAX;
+        assert_eq!(first.update(&[("ip:spot:60", (0, end))], &mut |states| states[0].0 += 5), vec![(5, end)]);
+        // another process opening the file sees the weight of the first one
+        assert_eq!(second.update(&[("ip:spot:60", (0, end))], &mut |states| states[0].0 += 1), vec![(6, end)]);
+        assert_eq!(
+            first.update(&[("ip:spot:60", (0, end)), ("uid:7:api:60", (0, end))], &mut |_| {}),
+            vec![(6, end), (0, end)]
+        );
+
+        let memory = MemoryRateLimitStore::new();
+        assert_eq!(memory.update(&[("ip:spot:60", (0, end))], &mut |states| states[0].0 += 1), vec![(1, end)]);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_file_store_limit() {
+        let path = temp_path("limit");
+        let first: Arc<dyn RateLimitStore> = Arc::new(FileRateLimitStore::open(&path).unwrap());
+        let second: Arc<dyn RateLimitStore> = Arc::new(FileRateLimitStore::open(&path).unwrap());
+        // every request takes weight 2 from the request weight window and 1 from the raw requests window
+        let threads = [first, second.clone(), second, Arc::new(FileRateLimitStore::open(&path).unwrap())]
+            .into_iter()
+            .map(|store| {
+                std::thread::spawn(move || {
+                    let request_weight = WeightWindow::new(&store, "ip:spot:request_weight", 50, 1, WindowUnit::Hour);
+                    let raw_requests = WeightWindow::new(&store, "ip:spot:raw_requests", 30, 1, WindowUnit::Hour);
+                    (0..20)
+                        .filter(|_| {
+                            WeightWindow::acquire(&[(request_weight.clone(), 2), (raw_requests.clone(), 1)]) == Duration::ZERO
+                        })
+                        .count()
+                })
+            })
+            .collect::<Vec<_>>();
+        let admitted = threads.into_iter().map(|thread| thread.join().unwrap()).sum::<usize>();
+        assert_eq!(admitted, 25);
+
+        let store = FileRateLimitStore::open(&path).unwrap();
+        let windows = [("ip:spot:request_weight:3600", (0, 0)), ("ip:spot:raw_requests:3600", (0, 0))];
+        let states = store.update(&windows, &mut |_| {});
+        assert_eq!((states[0].0, states[1].0), (50, 25));
+        std::fs::remove_file(&path).unwrap();
+    }
+}
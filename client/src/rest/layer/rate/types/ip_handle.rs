@@ -1,29 +1,131 @@
+use crate::rest::config::{RateLimitMarket, RateLimitProfile};
+use crate::rest::layer::rate::store::RateLimitStore;
 use crate::rest::layer::rate::types::RateType;
 use crate::rest::layer::rate::types::window::{WeightWindow, WindowUnit};
+use crate::rest::layer::rate::types::usage::{RateUsage, UsageKind};
+use general::enums::rate_limiter::RateLimitType;
+use std::sync::{Arc, RwLock};
+
+// the ip windows of a market, a request takes its weight from REQUEST_WEIGHT and 1 from RAW_REQUESTS
+#[derive(Debug)]
+struct MarketWindows {
+    request_weight: Vec<WeightWindow>,
+    raw_requests: Vec<WeightWindow>,
+}
+
+impl MarketWindows {
+    fn new(store: &Arc<dyn RateLimitStore>, market: RateLimitMarket, profile: &RateLimitProfile) -> Self {
+        let name = match market {
+            RateLimitMarket::Spot => "ip:spot",
+            RateLimitMarket::Future => "ip:future",
+        };
+        MarketWindows {
+            request_weight: profile
+                .of_type(RateLimitType::RequestWeight)
+                .map(|rate_limit| WeightWindow::from_rate_limit(store, &format!("{}:request_weight", name), rate_limit))
+                .collect(),
+            raw_requests: profile
+                .of_type(RateLimitType::RawRequests)
+                .map(|rate_limit| WeightWindow::from_rate_limit(store, &format!("{}:raw_requests", name), rate_limit))
+                .collect(),
+        }
+    }
+
+    // the windows a request of weight counts in
+    fn windows(&self, weight: u32) -> Vec<(WeightWindow, u32)> {
+        let request_weight = self.request_weight.iter().map(|window| (window.clone(), weight));
+        let raw_requests = self.raw_requests.iter().map(|window| (window.clone(), 1));
+        request_weight.chain(raw_requests).collect()
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct IpWeightHandle {
-    api_window: WeightWindow,
+    store: Arc<dyn RateLimitStore>,
+    spot: Arc<RwLock<MarketWindows>>,
+    future: Arc<RwLock<MarketWindows>>,
     sapi_window: WeightWindow,
 }
 
 impl IpWeightHandle {
-    pub fn new_default() -> Self {
+    pub fn new(store: &Arc<dyn RateLimitStore>, spot: &RateLimitProfile, future: &RateLimitProfile) -> Self {
         IpWeightHandle {
-            api_window: WeightWindow::new(6000, 1, WindowUnit::Minute),
-            sapi_window: WeightWindow::new(12000, 1, WindowUnit::Minute),
+            store: store.clone(),
+            spot: Arc::new(RwLock::new(MarketWindows::new(store, RateLimitMarket::Spot, spot))),
+            future: Arc::new(RwLock::new(MarketWindows::new(store, RateLimitMarket::Future, future))),
+            sapi_window: WeightWindow::new(store, "ip:sapi", 12000, 1, WindowUnit::Minute),
         }
     }
 
-    pub fn available(&mut self, weight: u32, rate_type: Option<RateType>) -> bool {
-        if rate_type.is_none() {
-            return true;
+    // the windows take the limits of the profile, the weight used in a window of the same size is kept
+    pub fn set_rate_limits(&self, market: RateLimitMarket, profile: &RateLimitProfile) {
+        *self.market(market).write().unwrap() = MarketWindows::new(&self.store, market, profile);
+    }
+
+    fn market(&self, market: RateLimitMarket) -> &RwLock<MarketWindows> {
+        match market {
+            RateLimitMarket::Spot => &self.spot,
+            RateLimitMarket::Future => &self.future,
         }
-        let rate_type = rate_type.unwrap();
-        if rate_type.is_sapi() {
-            self.sapi_window.check_weight(weight)
-        } else {
-            self.api_window.check_weight(weight)
+    }
+
+    // the windows a request counts in, with the weight it takes from each of them
+    pub(crate) fn windows(&self, weight: u32, rate_type: Option<RateType>, market: RateLimitMarket) -> Vec<(WeightWindow, u32)> {
+        match rate_type {
+            Some(rate_type) if rate_type.is_sapi() => vec![(self.sapi_window.clone(), weight)],
+            Some(_) => self.market(market).read().unwrap().windows(weight),
+            None => Vec::new(),
         }
     }
-}
\ No newline at end of file
+
+    // take the ip weight binance reported, the usage gets the limit of the window it matched
+    pub fn reconcile(&mut self, usage: &mut RateUsage, market: RateLimitMarket) {
+        let market_windows = match market {
+            RateLimitMarket::Spot => &self.spot,
+            RateLimitMarket::Future => &self.future,
+        };
+        let mut market_windows = market_windows.write().unwrap();
+        let windows = match usage.kind {
+            UsageKind::IpWeight => market_windows.request_weight.iter_mut(),
+            UsageKind::SapiIpWeight => std::slice::from_mut(&mut self.sapi_window).iter_mut(),
+            _ => return,
+        };
+        for window in windows {
+            if window.matches(usage.interval_num, usage.unit) {
+                window.reconcile(usage.used, usage.window_end());
+                usage.limit = Some(window.basic_weight());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rest::layer::rate::store::MemoryRateLimitStore;
+    use std::time::Duration;
+
+    fn available(handle: &IpWeightHandle, weight: u32, rate_type: Option<RateType>, market: RateLimitMarket) -> bool {
+        WeightWindow::acquire(&handle.windows(weight, rate_type, market)) == Duration::ZERO
+    }
+
+    #[test]
+    fn test_market_profiles() {
+        let store: Arc<dyn RateLimitStore> = Arc::new(MemoryRateLimitStore::new());
+        let handle = IpWeightHandle::new(&store, &RateLimitProfile::spot_default(), &RateLimitProfile::future_default());
+        let api = Some(RateType::IpWeightRate(false));
+        assert!(available(&handle, 6000, api, RateLimitMarket::Spot));
+        assert!(!available(&handle, 1, api, RateLimitMarket::Spot));
+        // the futures windows are apart from the spot ones
+        assert!(available(&handle, 2400, api, RateLimitMarket::Future));
+        assert!(!available(&handle, 1, api, RateLimitMarket::Future));
+        assert!(available(&handle, 1, Some(RateType::IpWeightRate(true)), RateLimitMarket::Spot));
+
+        // a profile with a larger limit leaves room in the same window
+        let spot = RateLimitProfile::spot_default().with_weight_window(crate::rest::config::WeightWindow::new(6001, 1));
+        handle.set_rate_limits(RateLimitMarket::Spot, &spot);
+        assert!(available(&handle, 1, api, RateLimitMarket::Spot));
+        assert!(!available(&handle, 1, api, RateLimitMarket::Spot));
+        assert!(!available(&handle, 1, api, RateLimitMarket::Future));
+    }
+}
@@ -3,7 +3,8 @@ use general::error::SdkError;
 use general::result::BinanceResult;
 
 pub(crate) mod ip_handle;
-mod window;
+pub mod usage;
+pub mod window;
 pub(crate) mod order_handle;
 pub(crate) mod uid_handle;
 
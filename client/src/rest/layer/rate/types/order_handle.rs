@@ -1,64 +1,117 @@
 use dashmap::DashMap;
+use crate::rest::config::{RateLimitMarket, RateLimitProfile};
+use crate::rest::layer::rate::store::RateLimitStore;
 use crate::rest::layer::rate::types::RateType;
-use crate::rest::layer::rate::types::window::{WeightWindow, WindowUnit};
+use crate::rest::layer::rate::types::window::WeightWindow;
+use crate::rest::layer::rate::types::usage::{RateUsage, UsageKind};
+use general::enums::rate_limiter::RateLimitType;
+use std::sync::{Arc, RwLock};
 
+// the ORDERS limits count per account, every order weighs 1 in each of them
 #[derive(Debug, Clone)]
 pub struct OrderHandle {
-    second_level_order_weight: DashMap<u64, WeightWindow>,
-    second_level_order_basic_weight_window: WeightWindow,
-    day_level_order_weight: DashMap<u64, WeightWindow>,
-    day_level_order_basic_weight_window: WeightWindow,
-    minute_level_order_weight: DashMap<u64, WeightWindow>,
-    minute_level_order_basic_weight_window: WeightWindow,
+    store: Arc<dyn RateLimitStore>,
+    spot_profile: Arc<RwLock<RateLimitProfile>>,
+    future_profile: Arc<RwLock<RateLimitProfile>>,
+    order_weight: Arc<DashMap<(u64, RateLimitMarket), Vec<WeightWindow>>>,
 }
 
 impl OrderHandle {
-    pub fn new_with_default() -> OrderHandle {
+    pub fn new(store: &Arc<dyn RateLimitStore>, spot: &RateLimitProfile, future: &RateLimitProfile) -> OrderHandle {
         OrderHandle {
-            second_level_order_weight: DashMap::new(),
-            second_level_order_basic_weight_window: WeightWindow::new(100, 10, WindowUnit::Second),
-            day_level_order_weight: DashMap::new(),
-            day_level_order_basic_weight_window: WeightWindow::new(200000, 1, WindowUnit::Day),
-            minute_level_order_weight: DashMap::new(),
-            minute_level_order_basic_weight_window: WeightWindow::new(61000, 5, WindowUnit::Minute),
+            store: store.clone(),
+            spot_profile: Arc::new(RwLock::new(spot.clone())),
+            future_profile: Arc::new(RwLock::new(future.clone())),
+            order_weight: Arc::new(DashMap::new()),
         }
     }
 
+    // the windows of every account take the limits of the profile, the orders counted in a window of the same size are kept
+    pub fn set_rate_limits(&self, market: RateLimitMarket, profile: &RateLimitProfile) {
+        *self.profile(market).write().unwrap() = profile.clone();
+        self.order_weight.retain(|(_, window_market), _| *window_market != market);
+    }
 
-    fn init_uid_rate(&mut self, uid: u64) {
-        if self.second_level_order_weight.contains_key(&uid) && self.minute_level_order_weight.contains_key(&uid) && self.day_level_order_weight.contains_key(&uid) {
-            return;
+    fn profile(&self, market: RateLimitMarket) -> &RwLock<RateLimitProfile> {
+        match market {
+            RateLimitMarket::Spot => &self.spot_profile,
+            RateLimitMarket::Future => &self.future_profile,
         }
-        self.second_level_order_weight.entry(uid).or_insert(self.second_level_order_basic_weight_window.clone());
-        self.minute_level_order_weight.entry(uid).or_insert(self.minute_level_order_basic_weight_window.clone());
-        self.day_level_order_weight.entry(uid).or_insert(self.day_level_order_basic_weight_window.clone());
     }
 
-
-    fn second_level_check(&mut self, uid: u64) -> bool {
-        self.second_level_order_weight.get_mut(&uid).unwrap().check_weight(1)
+    // every account gets its own windows on its first order
+    fn init_uid_rate(&self, uid: u64, market: RateLimitMarket) {
+        if self.order_weight.contains_key(&(uid, market)) {
+            return;
+        }
+        let profile = self.profile(market).read().unwrap();
+        let name = match market {
+            RateLimitMarket::Spot => format!("order:{}:spot", uid),
+            RateLimitMarket::Future => format!("order:{}:future", uid),
+        };
+        self.order_weight.entry((uid, market)).or_insert_with(|| {
+            profile
+                .of_type(RateLimitType::ORDERS)
+                .map(|rate_limit| WeightWindow::from_rate_limit(&self.store, &name, rate_limit))
+                .collect()
+        });
     }
 
-    fn minute_level_check(&mut self, uid: u64) -> bool {
-        self.minute_level_order_weight.get_mut(&uid).unwrap().check_weight(1)
+    // the windows of the account an order counts in, every order weighs 1
+    pub(crate) fn windows(&self, rate_type: Option<RateType>, market: RateLimitMarket) -> Vec<(WeightWindow, u32)> {
+        let Some(rate_type) = rate_type else {
+            return Vec::new();
+        };
+        let uid = rate_type.get_uid().unwrap();
+        self.init_uid_rate(uid, market);
+        self.order_weight
+            .get(&(uid, market))
+            .unwrap()
+            .iter()
+            .map(|window| (window.clone(), 1))
+            .collect()
     }
 
-    fn day_level_check(&mut self, uid: u64) -> bool {
-        self.day_level_order_weight.get_mut(&uid).unwrap().check_weight(1)
+    // the order count binance reported goes to the window of the same interval
+    pub fn reconcile(&mut self, usage: &mut RateUsage, market: RateLimitMarket) {
+        let Some(uid) = usage.uid.filter(|_| usage.kind == UsageKind::OrderCount) else {
+            return;
+        };
+        self.init_uid_rate(uid, market);
+        for window in self.order_weight.get_mut(&(uid, market)).unwrap().iter_mut() {
+            if window.matches(usage.interval_num, usage.unit) {
+                window.reconcile(usage.used, usage.window_end());
+                usage.limit = Some(window.basic_weight());
+            }
+        }
     }
+}
 
-    pub fn available(&mut self, rate_type: Option<RateType>) -> bool {
-        if rate_type.is_none() {
-            return true;
-        }
-        let rate_type = rate_type.unwrap();
-        let uid = rate_type.get_uid().unwrap();
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rest::layer::rate::store::MemoryRateLimitStore;
+    use std::time::Duration;
 
-        self.init_uid_rate(uid);
+    fn available(handle: &OrderHandle, rate_type: Option<RateType>, market: RateLimitMarket) -> bool {
+        WeightWindow::acquire(&handle.windows(rate_type, market)) == Duration::ZERO
+    }
 
-        if self.second_level_check(uid) && self.minute_level_check(uid) && self.day_level_check(uid) {
-            return true;
+    #[test]
+    fn test_order_windows_per_account() {
+        let store: Arc<dyn RateLimitStore> = Arc::new(MemoryRateLimitStore::new());
+        let handle = OrderHandle::new(&store, &RateLimitProfile::spot_default(), &RateLimitProfile::future_default());
+        let first = Some(RateType::OrderRate(1));
+        for _ in 0..100 {
+            assert!(available(&handle, first, RateLimitMarket::Spot));
         }
-        false
+        assert!(!available(&handle, first, RateLimitMarket::Spot));
+        // another account and the futures orders have windows of their own
+        assert!(available(&handle, Some(RateType::OrderRate(2)), RateLimitMarket::Spot));
+        assert!(available(&handle, first, RateLimitMarket::Future));
+
+        // the orders counted so far are kept under the new limits
+        handle.set_rate_limits(RateLimitMarket::Spot, &RateLimitProfile::spot_default());
+        assert!(!available(&handle, first, RateLimitMarket::Spot));
     }
 }
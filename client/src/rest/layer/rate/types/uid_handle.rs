@@ -1,52 +1,65 @@
 use dashmap::DashMap;
+use crate::rest::layer::rate::store::RateLimitStore;
 use crate::rest::layer::rate::types::RateType;
 use crate::rest::layer::rate::types::window::{WeightWindow, WindowUnit};
+use crate::rest::layer::rate::types::usage::{RateUsage, UsageKind};
+use std::sync::Arc;
+
+const UID_WEIGHT: u32 = 180000;
 
 #[derive(Debug, Clone)]
 pub struct UidWeightHandle {
+    store: Arc<dyn RateLimitStore>,
     sapi_uid_weight: DashMap<u64, WeightWindow>,
-    sapi_basic_weight_window: WeightWindow,
     api_uid_weight: DashMap<u64, WeightWindow>,
-    api_basic_weight_window: WeightWindow,
 }
 
 impl UidWeightHandle {
-    pub fn new_with_default() -> UidWeightHandle {
+    pub fn new_with_default(store: &Arc<dyn RateLimitStore>) -> UidWeightHandle {
         UidWeightHandle {
+            store: store.clone(),
             sapi_uid_weight: DashMap::new(),
-            sapi_basic_weight_window: WeightWindow::new(180000, 1, WindowUnit::Minute),
             api_uid_weight: DashMap::new(),
-            api_basic_weight_window: WeightWindow::new(180000, 1, WindowUnit::Minute),
         }
     }
 
-    fn init_uid_rate(&mut self, uid: u64) {
+    // every account counts in windows of its own
+    fn init_uid_rate(&self, uid: u64) {
         if self.sapi_uid_weight.contains_key(&uid) && self.api_uid_weight.contains_key(&uid) {
             return;
         }
-        self.api_uid_weight.entry(uid).or_insert(self.api_basic_weight_window.clone());
-        self.sapi_uid_weight.entry(uid).or_insert(self.sapi_basic_weight_window.clone());
-    }
-
-    fn sapi_check(&mut self, weight: u32, uid: u64) -> bool {
-        self.sapi_uid_weight.get_mut(&uid).unwrap().check_weight(weight)
+        self.api_uid_weight.entry(uid).or_insert_with(|| {
+            WeightWindow::new(&self.store, &format!("uid:{}:api", uid), UID_WEIGHT, 1, WindowUnit::Minute)
+        });
+        self.sapi_uid_weight.entry(uid).or_insert_with(|| {
+            WeightWindow::new(&self.store, &format!("uid:{}:sapi", uid), UID_WEIGHT, 1, WindowUnit::Minute)
+        });
     }
 
-    fn api_check(&mut self, weight: u32, uid: u64) -> bool {
-        self.api_uid_weight.get_mut(&uid).unwrap().check_weight(weight)
-    }
-
-    pub fn available(&mut self, weight: u32, rate_type: Option<RateType>) -> bool {
-        if rate_type.is_none() {
-            return true;
-        }
-        let rate_type = rate_type.unwrap();
+    // the window of the account a request counts in, sapi and api requests count apart
+    pub(crate) fn windows(&self, weight: u32, rate_type: Option<RateType>) -> Vec<(WeightWindow, u32)> {
+        let Some(rate_type) = rate_type else {
+            return Vec::new();
+        };
         let uid = rate_type.get_uid().unwrap();
         self.init_uid_rate(uid);
-        if rate_type.is_sapi() {
-            self.sapi_check(weight, uid)
+        let windows = if rate_type.is_sapi() {
+            &self.sapi_uid_weight
         } else {
-            self.api_check(weight, uid)
+            &self.api_uid_weight
+        };
+        vec![(windows.get(&uid).unwrap().clone(), weight)]
+    }
+
+    pub fn reconcile(&mut self, usage: &mut RateUsage) {
+        let Some(uid) = usage.uid.filter(|_| usage.kind == UsageKind::SapiUidWeight) else {
+            return;
+        };
+        self.init_uid_rate(uid);
+        let mut window = self.sapi_uid_weight.get_mut(&uid).unwrap();
+        if window.matches(usage.interval_num, usage.unit) {
+            window.reconcile(usage.used, usage.window_end());
+            usage.limit = Some(window.basic_weight());
         }
     }
-}
\ No newline at end of file
+}
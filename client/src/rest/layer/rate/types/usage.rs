@@ -0,0 +1,154 @@
+use crate::rest::layer::rate::types::window::WindowUnit;
+use dashmap::DashMap;
+use hyper::HeaderMap;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+const IP_WEIGHT_HEADER: &str = "x-mbx-used-weight-";
+const SAPI_IP_WEIGHT_HEADER: &str = "x-sapi-used-ip-weight-";
+const SAPI_UID_WEIGHT_HEADER: &str = "x-sapi-used-uid-weight-";
+const ORDER_COUNT_HEADER: &str = "x-mbx-order-count-";
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum UsageKind {
+    // X-MBX-USED-WEIGHT-*, the weight of the ip on /api and /fapi
+    IpWeight,
+    // X-SAPI-USED-IP-WEIGHT-*
+    SapiIpWeight,
+    // X-SAPI-USED-UID-WEIGHT-*
+    SapiUidWeight,
+    // X-MBX-ORDER-COUNT-*, the orders of the account
+    OrderCount,
+}
+
+// the usage of a rate limit as binance counted it in its last response
+#[derive(Debug, Clone)]
+pub struct RateUsage {
+    pub kind: UsageKind,
+    pub interval_num: u64,
+    pub unit: WindowUnit,
+    pub used: u32,
+    // the limit of the local window the usage was reconciled with
+    pub limit: Option<u32>,
+    // the account of the request for the uid weight and the order count
+    pub uid: Option<u64>,
+    pub updated_at: SystemTime,
+}
+
+impl RateUsage {
+    // binance windows start at the interval boundaries of the clock, e.g. at every full minute for 1M
+    pub fn window_end(&self) -> u64 {
+        let seconds = self.unit.seconds(self.interval_num).max(1);
+        let updated_at = self
+            .updated_at
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        (updated_at / seconds + 1) * seconds
+    }
+
+    // the usage is zero again once its window has ended
+    pub fn current_used(&self) -> u32 {
+        let current_time = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        if current_time >= self.window_end() { 0 } else { self.used }
+    }
+
+    // what is left of the limit in the current window, None when the limit is unknown
+    pub fn remaining(&self) -> Option<u32> {
+        self.limit.map(|limit| limit.saturating_sub(self.current_used()))
+    }
+}
+
+// parse the usage headers of a response, the uid is the account of the request
+pub(crate) fn parse_usage(headers: &HeaderMap, uid: Option<u64>) -> Vec<RateUsage> {
+    let updated_at = SystemTime::now();
+    headers
+        .iter()
+        .filter_map(|(name, value)| {
+            let name = name.as_str();
+            let (kind, interval) = [
+                (UsageKind::IpWeight, IP_WEIGHT_HEADER),
+                (UsageKind::SapiIpWeight, SAPI_IP_WEIGHT_HEADER),
+                (UsageKind::SapiUidWeight, SAPI_UID_WEIGHT_HEADER),
+                (UsageKind::OrderCount, ORDER_COUNT_HEADER),
+            ]
+            .into_iter()
+            .find_map(|(kind, prefix)| name.strip_prefix(prefix).map(|interval| (kind, interval)))?;
+            let (interval_num, unit) = parse_interval(interval)?;
+            let used = value.to_str().ok()?.trim().parse::<u32>().ok()?;
+            let uid = match kind {
+                UsageKind::SapiUidWeight | UsageKind::OrderCount => uid,
+                UsageKind::IpWeight | UsageKind::SapiIpWeight => None,
+            };
+            Some(RateUsage {
+                kind,
+                interval_num,
+                unit,
+                used,
+                limit: None,
+                uid,
+                updated_at,
+            })
+        })
+        .collect()
+}
+
+// 1m, 10s, 1d
+fn parse_interval(interval: &str) -> Option<(u64, WindowUnit)> {
+    let letter = interval.chars().last()?;
+    let unit = WindowUnit::from_letter(letter)?;
+    let interval_num = interval[..interval.len() - letter.len_utf8()].parse::<u64>().ok()?;
+    Some((interval_num, unit))
+}
+
+// a rate limit: its kind, interval and account
+type UsageKey = (UsageKind, u64, WindowUnit, Option<u64>);
+
+// the last usage of every rate limit, shared by every clone of the client
+#[derive(Debug, Clone, Default)]
+pub(crate) struct UsageHandle {
+    usage: Arc<DashMap<UsageKey, RateUsage>>,
+}
+
+impl UsageHandle {
+    pub(crate) fn update(&self, usage: RateUsage) {
+        self.usage
+            .insert((usage.kind, usage.interval_num, usage.unit, usage.uid), usage);
+    }
+
+    pub(crate) fn snapshot(&self) -> Vec<RateUsage> {
+        self.usage.iter().map(|entry| entry.value().clone()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hyper::header::HeaderValue;
+
+    #[test]
+    fn test_parse_usage() {
+        let mut headers = HeaderMap::new();
+        headers.insert("X-MBX-USED-WEIGHT-1M", HeaderValue::from_static("120"));
+        headers.insert("X-MBX-USED-WEIGHT", HeaderValue::from_static("120"));
+        headers.insert("X-SAPI-USED-UID-WEIGHT-1M", HeaderValue::from_static("30"));
+        headers.insert("X-MBX-ORDER-COUNT-10S", HeaderValue::from_static("3"));
+        headers.insert("X-MBX-ORDER-COUNT-1D", HeaderValue::from_static("42"));
+        headers.insert("Content-Type", HeaderValue::from_static("application/json"));
+        let mut usage = parse_usage(&headers, Some(7));
+        usage.sort_by_key(|usage| (usage.kind as u8, usage.unit.seconds(usage.interval_num)));
+        assert_eq!(usage.len(), 4);
+        assert_eq!((usage[0].kind, usage[0].interval_num, usage[0].unit, usage[0].used), (UsageKind::IpWeight, 1, WindowUnit::Minute, 120));
+        assert_eq!(usage[0].uid, None);
+        assert_eq!((usage[1].kind, usage[1].used, usage[1].uid), (UsageKind::SapiUidWeight, 30, Some(7)));
+        assert_eq!((usage[2].kind, usage[2].interval_num, usage[2].unit), (UsageKind::OrderCount, 10, WindowUnit::Second));
+        assert_eq!((usage[3].kind, usage[3].unit, usage[3].used), (UsageKind::OrderCount, WindowUnit::Day, 42));
+
+        let minute = &usage[0];
+        assert_eq!(minute.window_end() % 60, 0);
+        assert_eq!(minute.remaining(), None);
+    }
+}
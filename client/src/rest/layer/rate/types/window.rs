@@ -1,7 +1,9 @@
-use std::sync::{Arc, Mutex};
-use std::time::SystemTime;
+use general::enums::rate_limiter::{RateLimitUnit, RateLimiter};
+use crate::rest::layer::rate::store::{RateLimitStore, WindowState};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum WindowUnit {
     Second,
     Minute,
@@ -10,61 +12,247 @@ pub enum WindowUnit {
 }
 
 impl WindowUnit {
+    // the last second of the current window, binance windows are fixed and start at the interval
+    // boundaries of the clock, e.g. at every full minute for 1 minute
     pub fn calculate_window_timestamp(&self, window_interval: u64) -> u64 {
         let current_time = SystemTime::now()
             .duration_since(SystemTime::UNIX_EPOCH)
             .unwrap()
             .as_secs();
+        let seconds = self.seconds(window_interval).max(1);
+        (current_time / seconds + 1) * seconds - 1
+    }
+
+    pub fn seconds(&self, window_interval: u64) -> u64 {
         match self {
-            WindowUnit::Second => current_time + window_interval,
-            WindowUnit::Minute => current_time - window_interval * 60,
-            WindowUnit::Hour => current_time + window_interval * 60 * 60,
-            WindowUnit::Day => current_time + window_interval * 60 * 60 * 24,
+            WindowUnit::Second => window_interval,
+            WindowUnit::Minute => window_interval * 60,
+            WindowUnit::Hour => window_interval * 60 * 60,
+            WindowUnit::Day => window_interval * 60 * 60 * 24,
+        }
+    }
+
+    // the letter binance puts after the interval number in its headers, e.g. the M of X-MBX-USED-WEIGHT-1M
+    pub fn from_letter(letter: char) -> Option<WindowUnit> {
+        match letter.to_ascii_uppercase() {
+            'S' => Some(WindowUnit::Second),
+            'M' => Some(WindowUnit::Minute),
+            'H' => Some(WindowUnit::Hour),
+            'D' => Some(WindowUnit::Day),
+            _ => None,
         }
     }
 }
 
+impl From<RateLimitUnit> for WindowUnit {
+    fn from(unit: RateLimitUnit) -> Self {
+        match unit {
+            RateLimitUnit::SECOND => WindowUnit::Second,
+            RateLimitUnit::MINUTE => WindowUnit::Minute,
+            RateLimitUnit::HOUR => WindowUnit::Hour,
+            RateLimitUnit::DAY => WindowUnit::Day,
+        }
+    }
+}
 
 #[derive(Debug, Clone)]
 pub(crate) struct WeightWindow {
-    weight_window: Arc<Mutex<(u32, u64)>>,
+    store: Arc<dyn RateLimitStore>,
+    // the window in the store, e.g. ip:spot:request_weight:60
+    key: String,
     basic_weight: u32,
     interval: u64,
     unit: WindowUnit
 }
 
 impl WeightWindow {
-    // window_interval: the basic size about slide window size;
-    // weight: the current window weight
-    pub fn new(weight: u32, interval: u64, unit: WindowUnit) -> Self {
+    // name: the limit the window counts, the key in the store adds the window size in seconds;
+    // weight: the weight allowed in a window
+    pub fn new(store: &Arc<dyn RateLimitStore>, name: &str, weight: u32, interval: u64, unit: WindowUnit) -> Self {
         WeightWindow {
-            weight_window: Arc::new(Mutex::new((weight, unit.calculate_window_timestamp(interval)))),
+            store: store.clone(),
+            key: format!("{}:{}", name, unit.seconds(interval)),
             basic_weight: weight,
             interval,
             unit
         }
     }
 
-    pub fn check_weight(&mut self, weight: u32) -> bool {
-        let mut guard = self.weight_window.lock().unwrap();
+    pub fn from_rate_limit(store: &Arc<dyn RateLimitStore>, name: &str, rate_limit: &RateLimiter) -> Self {
+        WeightWindow::new(store, name, rate_limit.limit, rate_limit.interval_num, rate_limit.interval.into())
+    }
+
+    // the state a window starts with, empty until the end of the current window
+    fn init(&self) -> (&str, WindowState) {
+        (&self.key, (0, self.unit.calculate_window_timestamp(self.interval)))
+    }
+
+    // change the state of the window, a window which ended starts again empty
+    fn update(&self, f: &mut dyn FnMut(&mut WindowState)) -> WindowState {
+        let current_time = current_time();
+        let (key, init) = self.init();
+        self.store.update(&[(key, init)], &mut |states| {
+            if current_time > states[0].1 {
+                states[0] = init;
+            }
+            f(&mut states[0])
+        })[0]
+    }
+
+    // take the weight of a request from every window it counts in, in a single update of the store so that no
+    // other limiter sharing the store takes the same room in between. nothing is taken when a window has no room,
+    // the answer is then how long until every window has room. the windows must share the same store.
+    pub fn acquire(windows: &[(WeightWindow, u32)]) -> Duration {
+        let Some((first, _)) = windows.first() else {
+            return Duration::ZERO;
+        };
+        let current_time = current_time();
+        let inits = windows.iter().map(|(window, _)| window.init()).collect::<Vec<_>>();
+        let mut wait = Duration::ZERO;
+        first.store.update(&inits, &mut |states| {
+            for (state, (_, init)) in states.iter_mut().zip(&inits) {
+                if current_time > state.1 {
+                    *state = *init;
+                }
+            }
+            wait = states
+                .iter()
+                .zip(windows)
+                .filter(|(state, (window, weight))| state.0.saturating_add(*weight) > window.basic_weight)
+                // the window is renewed in the second after its end
+                .map(|(state, _)| Duration::from_secs(state.1 + 1 - current_time))
+                .max()
+                .unwrap_or(Duration::ZERO);
+            if wait == Duration::ZERO {
+                for (state, (_, weight)) in states.iter_mut().zip(windows) {
+                    state.0 = state.0.saturating_add(*weight);
+                }
+            }
+        });
+        wait
+    }
+
+    pub fn matches(&self, interval: u64, unit: WindowUnit) -> bool {
+        self.unit.seconds(self.interval) == unit.seconds(interval)
+    }
+
+    pub fn basic_weight(&self) -> u32 {
+        self.basic_weight
+    }
+
+    // take the usage binance reported for its window ending at window_end, binance counts the requests of
+    // every process sharing the ip or the account. the responses of overlapping requests arrive in any order,
+    // so a usage never lowers the weight of its window, and a usage of an earlier window is ignored.
+    pub fn reconcile(&mut self, used: u32, window_end: u64) {
+        let window_end = window_end.saturating_sub(1);
+        self.update(&mut |state| {
+            if state.1 == window_end {
+                state.0 = state.0.max(used);
+            } else if state.1 < window_end {
+                *state = (used, window_end);
+            }
+        });
+    }
+}
+
+fn current_time() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rest::layer::rate::store::MemoryRateLimitStore;
+
+    fn memory_store() -> Arc<dyn RateLimitStore> {
+        Arc::new(MemoryRateLimitStore::new())
+    }
+
+    #[test]
+    fn test_window_timestamp() {
         let current_time = SystemTime::now()
             .duration_since(SystemTime::UNIX_EPOCH)
             .unwrap()
             .as_secs();
-        if current_time > guard.1 {
-            *guard = (
-                self.basic_weight - weight,
-                self.unit.calculate_window_timestamp(self.interval)
-            );
-            true
-        } else {
-            if guard.0 >= weight {
-                *guard = (guard.0 - weight, guard.1);
-                true
-            } else {
-                false
-            }
+        for (unit, interval) in [
+            (WindowUnit::Second, 10),
+            (WindowUnit::Minute, 1),
+            (WindowUnit::Minute, 5),
+            (WindowUnit::Hour, 1),
+            (WindowUnit::Day, 1),
+        ] {
+            let end = unit.calculate_window_timestamp(interval);
+            let seconds = unit.seconds(interval);
+            assert!(end >= current_time && end < current_time + seconds, "{:?} {}", unit, interval);
+            assert_eq!((end + 1) % seconds, 0);
         }
     }
-}
 
+    fn available(window: &WeightWindow, weight: u32) -> bool {
+        WeightWindow::acquire(&[(window.clone(), weight)]) == Duration::ZERO
+    }
+
+    #[test]
+    fn test_acquire() {
+        let store = memory_store();
+        let window = WeightWindow::new(&store, "test", 10, 5, WindowUnit::Second);
+        let raw_requests = WeightWindow::new(&store, "raw", 2, 5, WindowUnit::Second);
+        assert!(available(&window, 8));
+        let wait = WeightWindow::acquire(&[(window.clone(), 3)]);
+        assert!(wait > Duration::ZERO && wait <= Duration::from_secs(6));
+        // a request takes its weight from every window or from none
+        assert_eq!(WeightWindow::acquire(&[(window.clone(), 1), (raw_requests.clone(), 1)]), Duration::ZERO);
+        assert!(WeightWindow::acquire(&[(window.clone(), 1), (raw_requests.clone(), 2)]) > Duration::ZERO);
+        assert!(available(&window, 1));
+        assert!(!available(&window, 1));
+        assert!(available(&raw_requests, 1));
+    }
+
+    #[test]
+    fn test_reconcile() {
+        let mut window = WeightWindow::new(&memory_store(), "test", 10, 1, WindowUnit::Minute);
+        assert!(window.matches(60, WindowUnit::Second));
+        assert!(!window.matches(1, WindowUnit::Day));
+        let window_end = WindowUnit::Minute.calculate_window_timestamp(1) + 1;
+        window.reconcile(9, window_end);
+        assert!(WeightWindow::acquire(&[(window.clone(), 2)]) > Duration::ZERO);
+        assert!(available(&window, 1));
+        window.reconcile(20, window_end);
+        assert!(!available(&window, 1));
+        // the usage of the previous window is too late
+        let mut window = WeightWindow::new(&memory_store(), "test", 10, 1, WindowUnit::Minute);
+        window.reconcile(9, window_end - 60);
+        assert!(available(&window, 10));
+    }
+
+    #[test]
+    fn test_reconcile_overlapping_requests() {
+        let mut window = WeightWindow::new(&memory_store(), "test", 10, 1, WindowUnit::Minute);
+        let window_end = WindowUnit::Minute.calculate_window_timestamp(1) + 1;
+        window.reconcile(3, window_end);
+        // two requests of weight 1 are sent before either answers
+        assert!(available(&window, 1));
+        assert!(available(&window, 1));
+        // binance answers the second request first, its usage counts both
+        window.reconcile(5, window_end);
+        window.reconcile(4, window_end);
+        assert!(WeightWindow::acquire(&[(window.clone(), 6)]) > Duration::ZERO);
+        assert!(available(&window, 5));
+    }
+
+    #[test]
+    fn test_shared_store() {
+        let store = memory_store();
+        let first = WeightWindow::new(&store, "ip:spot:request_weight", 10, 1, WindowUnit::Minute);
+        let second = WeightWindow::new(&store, "ip:spot:request_weight", 10, 1, WindowUnit::Minute);
+        let other = WeightWindow::new(&store, "uid:7:api", 10, 1, WindowUnit::Minute);
+        assert!(available(&first, 8));
+        assert!(!available(&second, 3));
+        assert!(available(&second, 2));
+        assert!(!available(&first, 1));
+        assert!(available(&other, 10));
+    }
+}
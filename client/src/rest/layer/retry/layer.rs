@@ -0,0 +1,22 @@
+use crate::rest::config::RetryConfig;
+use crate::rest::layer::retry::Retry;
+use tower::Layer;
+
+#[derive(Debug)]
+pub struct RetryLayer {
+    config: RetryConfig,
+}
+
+impl RetryLayer {
+    pub fn new(config: RetryConfig) -> Self {
+        RetryLayer { config }
+    }
+}
+
+impl<I> Layer<I> for RetryLayer {
+    type Service = Retry<I>;
+
+    fn layer(&self, inner: I) -> Self::Service {
+        Retry::new(inner, self.config)
+    }
+}
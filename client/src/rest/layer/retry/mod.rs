@@ -0,0 +1,200 @@
+use crate::rest::body::{copy_request, RequestBody};
+use crate::rest::config::RetryConfig;
+use crate::rest::layer::take_ready;
+use crate::rest::layer::retry::policy::{order_not_found, order_status_request, retry_delay, RequestKind};
+use bytes::Bytes;
+use http_body_util::Full;
+use hyper::{Request, Response};
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tower::{BoxError, Service, ServiceExt};
+
+pub mod layer;
+pub(crate) mod policy;
+
+// Retry sends a failed request again after a backoff: on an error, a 5xx, a -1001, -1007 or -1008 code,
+// and after the Retry-After of a 429 or 418 or once an open circuit reopens. A GET is sent again directly, a new order only when it has a
+// newClientOrderId and the status check by that id shows the first attempt did not land, any other request never.
+// The inner service answers with the body read, as the circuit breaker does.
+#[derive(Debug, Clone)]
+pub struct Retry<S> {
+    inner: S,
+    config: RetryConfig,
+}
+
+impl<S> Retry<S> {
+    pub fn new(inner: S, config: RetryConfig) -> Self {
+        Retry { inner, config }
+    }
+    pub fn get_inner_ref(&self) -> &S {
+        &self.inner
+    }
+}
+
+impl<S> Service<Request<RequestBody>> for Retry<S>
+where
+    S: Service<Request<RequestBody>, Response = Response<Bytes>> + Clone + Send + 'static,
+    S::Future: Send,
+    S::Error: Into<BoxError>,
+{
+    type Response = Response<Full<Bytes>>;
+    type Error = BoxError;
+    type Future = Pin<Box<dyn Future<Output = Result<Response<Full<Bytes>>, BoxError>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, req: Request<RequestBody>) -> Self::Future {
+        let inner = take_ready(&mut self.inner);
+        let config = self.config;
+        Box::pin(async move {
+            let resp = send_with_retry(inner, config, req).await?;
+            Ok(resp.map(Full::new))
+        })
+    }
+}
+
+async fn send_with_retry<S>(
+    mut inner: S,
+    config: RetryConfig,
+    req: Request<RequestBody>,
+) -> Result<Response<Bytes>, BoxError>
+where
+    S: Service<Request<RequestBody>, Response = Response<Bytes>> + Clone,
+    S::Error: Into<BoxError>,
+{
+    let kind = RequestKind::of(&req);
+    let mut retry = 1;
+    loop {
+        let result = call(&mut inner, copy_request(&req)).await;
+        if kind == RequestKind::Once {
+            return result;
+        }
+        let Some(delay) = retry_delay(&result, retry, &config) else {
+            return result;
+        };
+        log::warn!("{} {} failed, retry {} in {:?}", req.method(), req.uri().path(), retry, delay);
+        // hold no ready service of the layers below while waiting
+        drop(take_ready(&mut inner));
+        tokio::time::sleep(delay).await;
+        if let RequestKind::Order(params) = &kind {
+            match call(&mut inner, order_status_request(&req, params)).await {
+                Ok(status) if order_not_found(&status) => {}
+                Ok(status) => {
+                    log::warn!("the order {} is not sent again, its status check answered {}", params, status.status());
+                    return result;
+                }
+                Err(err) => {
+                    log::warn!("the order {} is not sent again, its status check failed: {}", params, err);
+                    return result;
+                }
+            }
+        }
+        retry += 1;
+    }
+}
+
+async fn call<S>(inner: &mut S, req: Request<RequestBody>) -> Result<Response<Bytes>, BoxError>
+where
+    S: Service<Request<RequestBody>, Response = Response<Bytes>>,
+    S::Error: Into<BoxError>,
+{
+    inner.ready().await.map_err(Into::into)?.call(req).await.map_err(Into::into)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rest::extension::RequestExtension;
+    use crate::rest::layer::authorization::types::{AuthType, Certificate};
+    use http_body_util::Empty;
+    use hyper::{Method, StatusCode};
+    use std::collections::VecDeque;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+    use tower::util::BoxCloneService;
+
+    type TestService = BoxCloneService<Request<RequestBody>, Response<Bytes>, BoxError>;
+
+    // answers the requests in order and records them as "METHOD query"
+    fn scripted(answers: Vec<(u16, &'static str)>) -> (Retry<TestService>, Arc<Mutex<Vec<String>>>) {
+        let answers = Arc::new(Mutex::new(VecDeque::from(answers)));
+        let sent = Arc::new(Mutex::new(vec![]));
+        let sent_requests = sent.clone();
+        let inner = tower::service_fn(move |req: Request<RequestBody>| {
+            let params = RequestExtension::explain_request_params(req.extensions()).unwrap_or_default();
+            sent_requests.lock().unwrap().push(format!("{} {}", req.method(), params));
+            let (status, body) = answers.lock().unwrap().pop_front().unwrap();
+            async move {
+                Ok::<_, BoxError>(
+                    Response::builder()
+                        .status(status)
+                        .body(Bytes::from(body))
+                        .unwrap(),
+                )
+            }
+        });
+        let config = RetryConfig::new(2, Duration::from_millis(1), Duration::from_millis(5), Duration::from_secs(1));
+        (Retry::new(BoxCloneService::new(inner), config), sent)
+    }
+
+    fn request(method: Method, body: Option<&str>) -> Request<RequestBody> {
+        let mut extension = vec![
+            RequestExtension::Auth(AuthType::Trade),
+            RequestExtension::Weight(1),
+            RequestExtension::Cert(Certificate::new("key", "secret")),
+        ];
+        if let Some(body) = body {
+            extension.push(RequestExtension::Body(body.to_string()));
+        }
+        Request::builder()
+            .method(method)
+            .uri("https://api.binance.com/api/v3/order")
+            .extension(extension)
+            .body(RequestBody::Empty(Empty::<Bytes>::new()))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_retry_get() {
+        let (mut retry, sent) = scripted(vec![(503, ""), (400, r#"{"code":-1008,"msg":"busy"}"#), (200, "{}")]);
+        let resp = retry.call(request(Method::GET, None)).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(sent.lock().unwrap().len(), 3);
+
+        let (mut retry, sent) = scripted(vec![(400, r#"{"code":-1121,"msg":"Invalid symbol"}"#)]);
+        let resp = retry.call(request(Method::GET, None)).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(sent.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_retry_order() {
+        let order = "symbol=BTCUSDT&side=BUY&newClientOrderId=abc";
+        let (mut retry, sent) = scripted(vec![
+            (503, ""),
+            (400, r#"{"code":-2013,"msg":"Order does not exist."}"#),
+            (200, "{}"),
+        ]);
+        let resp = retry.call(request(Method::POST, Some(order))).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(
+            *sent.lock().unwrap(),
+            vec!["POST ", "GET symbol=BTCUSDT&origClientOrderId=abc", "POST "]
+        );
+
+        // the first attempt landed, it is not sent again
+        let (mut retry, sent) = scripted(vec![(503, ""), (200, "{}")]);
+        let resp = retry.call(request(Method::POST, Some(order))).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(sent.lock().unwrap().len(), 2);
+
+        // without newClientOrderId the order can not be checked
+        let (mut retry, sent) = scripted(vec![(503, "")]);
+        let resp = retry.call(request(Method::POST, Some("symbol=BTCUSDT&side=BUY"))).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(sent.lock().unwrap().len(), 1);
+    }
+}
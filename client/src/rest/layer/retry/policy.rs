@@ -0,0 +1,221 @@
+use crate::rest::body::RequestBody;
+use crate::rest::config::RetryConfig;
+use crate::rest::extension::RequestExtension;
+use crate::rest::layer::authorization::types::AuthType;
+use bytes::Bytes;
+use general::error::SdkError;
+use hyper::header::RETRY_AFTER;
+use hyper::{Method, Request, Response, StatusCode};
+use serde::Deserialize;
+use std::time::{Duration, SystemTime};
+use tower::BoxError;
+
+// -1001 internal error, -1007 timeout waiting for the backend, -1008 server busy
+const RETRYABLE_CODES: [i64; 3] = [-1001, -1007, -1008];
+// the order of the status check does not exist
+const NO_SUCH_ORDER: i64 = -2013;
+// the weight of GET /api/v3/order, /fapi/v1/order weighs less
+const ORDER_STATUS_WEIGHT: u32 = 4;
+
+#[derive(Debug, Deserialize)]
+struct ErrorCode {
+    code: i64,
+}
+
+// how a failed request may be sent again
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum RequestKind {
+    // a GET, sending it again changes nothing
+    Idempotent,
+    // a new order with newClientOrderId, it is sent again only when the status check does not find it.
+    // the params of the status check: symbol and origClientOrderId
+    Order(String),
+    // never sent again
+    Once,
+}
+
+impl RequestKind {
+    pub(crate) fn of(req: &Request<RequestBody>) -> Self {
+        if req.method() == Method::GET {
+            return RequestKind::Idempotent;
+        }
+        if req.method() != Method::POST || !req.uri().path().ends_with("/order") {
+            return RequestKind::Once;
+        }
+        let params = RequestExtension::explain_request_body(req.extensions())
+            .and_then(|body| serde_urlencoded::from_str::<Vec<(String, String)>>(&body).ok())
+            .unwrap_or_default();
+        let find = |name: &str| params.iter().find(|(key, _)| key == name).map(|(_, value)| value.as_str());
+        match (find("symbol"), find("newClientOrderId")) {
+            (Some(symbol), Some(client_order_id)) => RequestKind::Order(
+                serde_urlencoded::to_string([("symbol", symbol), ("origClientOrderId", client_order_id)]).unwrap(),
+            ),
+            _ => RequestKind::Once,
+        }
+    }
+}
+
+// the delay before the next attempt, None when the result is final.
+// retry starts from 1, result is the outcome of the previous attempt.
+pub(crate) fn retry_delay(result: &Result<Response<Bytes>, BoxError>, retry: u32, config: &RetryConfig) -> Option<Duration> {
+    if retry > config.max_retries() {
+        return None;
+    }
+    let resp = match result {
+        Ok(resp) => resp,
+        Err(err) => {
+            // the request was not sent, the circuit reopens when the ban ends
+            if let Some(SdkError::CircuitOpen(_, reopen_at)) = err.downcast_ref::<SdkError>() {
+                let wait = reopen_at.duration_since(SystemTime::now()).unwrap_or_default();
+                return (wait <= config.max_retry_after()).then_some(wait);
+            }
+            return Some(config.backoff(retry));
+        }
+    };
+    let status = resp.status();
+    if status == StatusCode::TOO_MANY_REQUESTS || status.as_u16() == 418 {
+        return match retry_after(resp) {
+            Some(retry_after) if retry_after <= config.max_retry_after() => Some(retry_after),
+            Some(_) => None,
+            None => Some(config.backoff(retry)),
+        };
+    }
+    if status.is_server_error() || error_code(resp).is_some_and(|code| RETRYABLE_CODES.contains(&code)) {
+        return Some(config.backoff(retry));
+    }
+    None
+}
+
+// the Retry-After header in seconds
+pub(crate) fn retry_after<B>(resp: &Response<B>) -> Option<Duration> {
+    resp.headers()
+        .get(RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .map(Duration::from_secs)
+}
+
+pub(crate) fn error_code(resp: &Response<Bytes>) -> Option<i64> {
+    if resp.status().is_success() {
+        return None;
+    }
+    serde_json::from_slice::<ErrorCode>(resp.body()).ok().map(|error| error.code)
+}
+
+// true when the status check proves the order was not accepted, false when it was or when it is unknown
+pub(crate) fn order_not_found(resp: &Response<Bytes>) -> bool {
+    error_code(resp) == Some(NO_SUCH_ORDER)
+}
+
+// GET of the same path with symbol and origClientOrderId, signed with the certificate of the order
+pub(crate) fn order_status_request(req: &Request<RequestBody>, params: &str) -> Request<RequestBody> {
+    let mut extension = req
+        .extensions()
+        .get::<Vec<RequestExtension>>()
+        .cloned()
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|ext| {
+            !matches!(
+                ext,
+                RequestExtension::Auth(_)
+                    | RequestExtension::Weight(_)
+                    | RequestExtension::OrderRate(_)
+                    | RequestExtension::Body(_)
+                    | RequestExtension::Param(_)
+            )
+        })
+        .collect::<Vec<_>>();
+    extension.push(RequestExtension::Auth(AuthType::UserData));
+    extension.push(RequestExtension::Weight(ORDER_STATUS_WEIGHT));
+    extension.push(RequestExtension::Param(params.to_string()));
+    Request::get(req.uri().clone())
+        .extension(extension)
+        .body(RequestBody::Empty(http_body_util::Empty::<Bytes>::new()))
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rest::layer::authorization::types::Certificate;
+
+    fn response(status: u16, body: &str) -> Result<Response<Bytes>, BoxError> {
+        Ok(Response::builder()
+            .status(status)
+            .body(Bytes::from(body.to_string()))
+            .unwrap())
+    }
+
+    #[test]
+    fn test_retry_delay() {
+        let config = RetryConfig::new(2, Duration::from_millis(100), Duration::from_secs(1), Duration::from_secs(30));
+        assert_eq!(retry_delay(&response(200, "{}"), 1, &config), None);
+        assert_eq!(retry_delay(&response(502, ""), 1, &config), Some(Duration::from_millis(100)));
+        assert_eq!(retry_delay(&response(502, ""), 2, &config), Some(Duration::from_millis(200)));
+        assert_eq!(retry_delay(&response(502, ""), 3, &config), None);
+        assert_eq!(
+            retry_delay(&response(400, r#"{"code":-1007,"msg":"Timeout"}"#), 1, &config),
+            Some(Duration::from_millis(100))
+        );
+        assert_eq!(retry_delay(&response(400, r#"{"code":-1102,"msg":"Mandatory"}"#), 1, &config), None);
+        assert_eq!(retry_delay(&Err("connection reset".into()), 1, &config), Some(Duration::from_millis(100)));
+
+        let circuit_open = |reopen_in: u64| {
+            Err(SdkError::CircuitOpen("api".to_string(), SystemTime::now() + Duration::from_secs(reopen_in)).into())
+        };
+        assert!(retry_delay(&circuit_open(10), 1, &config).is_some_and(|delay| delay <= Duration::from_secs(10)));
+        assert_eq!(retry_delay(&circuit_open(120), 1, &config), None);
+
+        let banned = |retry_after: &str| {
+            Ok::<_, BoxError>(
+                Response::builder()
+                    .status(418)
+                    .header(RETRY_AFTER, retry_after)
+                    .body(Bytes::new())
+                    .unwrap(),
+            )
+        };
+        assert_eq!(retry_delay(&banned("12"), 1, &config), Some(Duration::from_secs(12)));
+        assert_eq!(retry_delay(&banned("120"), 1, &config), None);
+    }
+
+    #[test]
+    fn test_request_kind() {
+        let request = |method: Method, path: &str, body: &str| {
+            let extension = vec![
+                RequestExtension::Auth(AuthType::Trade),
+                RequestExtension::Weight(1),
+                RequestExtension::Cert(Certificate::new("key", "secret")),
+                RequestExtension::Body(body.to_string()),
+            ];
+            Request::builder()
+                .method(method)
+                .uri(format!("https://api.binance.com{}", path))
+                .extension(extension)
+                .body(RequestBody::Empty(http_body_util::Empty::<Bytes>::new()))
+                .unwrap()
+        };
+        assert_eq!(RequestKind::of(&request(Method::GET, "/api/v3/order", "")), RequestKind::Idempotent);
+        assert_eq!(
+            RequestKind::of(&request(Method::POST, "/api/v3/order", "symbol=BTCUSDT&side=BUY")),
+            RequestKind::Once
+        );
+        assert_eq!(RequestKind::of(&request(Method::DELETE, "/api/v3/order", "")), RequestKind::Once);
+
+        let order = request(Method::POST, "/api/v3/order", "symbol=BTCUSDT&side=BUY&newClientOrderId=my-order");
+        let kind = RequestKind::of(&order);
+        assert_eq!(kind, RequestKind::Order("symbol=BTCUSDT&origClientOrderId=my-order".to_string()));
+
+        let RequestKind::Order(params) = kind else { unreachable!() };
+        let status = order_status_request(&order, &params);
+        assert_eq!(status.method(), Method::GET);
+        assert_eq!(status.uri().path(), "/api/v3/order");
+        assert_eq!(RequestExtension::explain_request_params(status.extensions()), Some(params));
+        assert_eq!(RequestExtension::explain_request_body(status.extensions()), None);
+        assert_eq!(RequestExtension::explain_request_weight(status.extensions()), Some(ORDER_STATUS_WEIGHT));
+    }
+}
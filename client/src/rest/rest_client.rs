@@ -5,19 +5,33 @@ use hyper_util::client::legacy::connect::HttpConnector;
 use hyper_util::rt::TokioExecutor;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
-use tower::{Service, ServiceBuilder};
+use tower::{BoxError, Service, ServiceBuilder};
 use tower::timeout::{Timeout, TimeoutLayer};
+use general::error::{ApplicationError, SdkError};
 use general::result::BinanceResult;
 use crate::rest::body::RequestBody;
 use crate::rest::client::{BinanceClient, BinanceClientAction};
-use crate::rest::config::Config;
+use crate::rest::config::{Config, RateLimitMarket, RateLimitProfile};
 use crate::rest::extension::RequestExtension;
 use crate::rest::layer::authorization::Authorization;
 use crate::rest::layer::authorization::layer::AuthorizationLayer;
+use crate::rest::layer::circuit::CircuitBreaker;
+use crate::rest::layer::circuit::layer::CircuitBreakerLayer;
+use crate::rest::layer::circuit::types::{CircuitState, Circuits};
+use crate::environment::Environment;
+use crate::rest::layer::endpoint::Endpoint;
+use crate::rest::layer::endpoint::types::HostHealth;
+use crate::rest::layer::endpoint::layer::EndpointLayer;
 use crate::rest::layer::rate::layer::WeightRateLimitLayer;
 use crate::rest::layer::rate::WeightRateLimiter;
+use crate::rest::layer::rate::types::usage::RateUsage;
+use crate::rest::layer::retry::Retry;
+use crate::rest::layer::retry::layer::RetryLayer;
 
-pub type BinanceRestClient = Timeout<WeightRateLimiter<Authorization<Client<HttpsConnector<HttpConnector>, RequestBody>>>>;
+// the layers of the rest client over the service which sends the requests
+pub type RestLayers<S> = Retry<CircuitBreaker<Endpoint<WeightRateLimiter<Timeout<Authorization<S>>>>>>;
+
+pub type BinanceRestClient = RestLayers<Client<HttpsConnector<HttpConnector>, RequestBody>>;
 
 impl BinanceClient for BinanceRestClient {
     type Client = BinanceRestClient;
@@ -31,22 +45,57 @@ impl BinanceClient for BinanceRestClient {
         let client = Client::builder(TokioExecutor::new())
             .http2_only(true)
             .build(connector);
-        ServiceBuilder::new()
-            .layer(TimeoutLayer::new(config.request_timeout()))
-            .layer(WeightRateLimitLayer::new())
-            .layer(AuthorizationLayer::default())
-            .service(client)
+        layer_client(&config, client)
     }
 }
+
+fn layer_client<S>(config: &Config, client: S) -> RestLayers<S> {
+    let circuits = Circuits::default();
+    ServiceBuilder::new()
+        .layer(RetryLayer::new(config.retry_config()))
+        .layer(CircuitBreakerLayer::new(config.circuit_breaker_config()).with_circuits(circuits.clone()))
+        .layer(EndpointLayer::new(config.environment(), config.endpoint_config()).with_circuits(circuits))
+        .layer(WeightRateLimitLayer::new(
+            config.rate_limit_mode(),
+            config.rate_limit_store(),
+            config.spot_rate_limits(),
+            config.future_rate_limits(),
+        ))
+        // under the rate limiter, the wait in line is bounded by the queue and not by the request timeout
+        .layer(TimeoutLayer::new(config.request_timeout()))
+        .layer(AuthorizationLayer::default())
+        .service(client)
+}
+
 #[async_trait]
 impl BinanceClientAction for BinanceRestClient {
+    fn get_environment(&self) -> &Environment {
+        self.get_inner_ref().get_inner_ref().get_environment()
+    }
+
+    fn get_hosts_health(&self) -> Vec<HostHealth> {
+        self.get_inner_ref().get_inner_ref().get_hosts_health()
+    }
+
+    fn get_rate_usage(&self) -> Vec<RateUsage> {
+        self.get_inner_ref().get_inner_ref().get_inner_ref().get_rate_usage()
+    }
+
+    fn get_circuit_state(&self) -> Vec<CircuitState> {
+        self.get_inner_ref().get_circuit_state()
+    }
+
+    fn set_rate_limits(&self, market: RateLimitMarket, profile: &RateLimitProfile) {
+        self.get_inner_ref().get_inner_ref().get_inner_ref().set_rate_limits(market, profile)
+    }
+
     async fn get<I, O>(&self, request: Option<I>, path: &str, domain: &str, extension: Vec<RequestExtension>) -> BinanceResult<O>
     where
         I: Serialize + Send + Sync ,
         O: DeserializeOwned + Send
     {
         let request = Self::build_get_request(request, path, domain, extension);
-        let resp = self.clone().call(request).await.unwrap();
+        let resp = self.clone().call(request).await.map_err(into_sdk_error)?;
         Self::deserialize_response_body(resp).await
     }
 
@@ -56,7 +105,7 @@ impl BinanceClientAction for BinanceRestClient {
         O: DeserializeOwned + Send
     {
         let request = Self::build_get_request(request, path, domain, extension);
-        let resp = self.clone().call(request).await.unwrap();
+        let resp = self.clone().call(request).await.map_err(into_sdk_error)?;
         Self::deserialize_response_body(resp).await
     }
 
@@ -66,7 +115,7 @@ impl BinanceClientAction for BinanceRestClient {
         O: DeserializeOwned + Send
     {
         let request = Self::build_post_request(request, path, domain, extension);
-        let resp = self.clone().call(request).await.unwrap();
+        let resp = self.clone().call(request).await.map_err(into_sdk_error)?;
         Self::deserialize_response_body(resp).await
     }
 
@@ -76,7 +125,7 @@ impl BinanceClientAction for BinanceRestClient {
         O: DeserializeOwned + Send
     {
         let request = Self::build_put_request(request, path, domain, extension);
-        let resp = self.clone().call(request).await.unwrap();
+        let resp = self.clone().call(request).await.map_err(into_sdk_error)?;
         Self::deserialize_response_body(resp).await
     }
 
@@ -86,9 +135,119 @@ impl BinanceClientAction for BinanceRestClient {
         O: DeserializeOwned + Send
     {
         let request = Self::build_delete_request(request, path, domain, extension);
-        let resp = self.clone().call(request).await.unwrap();
+        let resp = self.clone().call(request).await.map_err(into_sdk_error)?;
         Self::deserialize_response_body(resp).await
     }
+}
+
+// the layers fail with an SdkError or an ApplicationError, the http client and the timeout with errors of their own
+fn into_sdk_error(err: BoxError) -> SdkError {
+    let err = match err.downcast::<SdkError>() {
+        Ok(err) => return *err,
+        Err(err) => err,
+    };
+    let err = match err.downcast::<ApplicationError>() {
+        Ok(err) => return SdkError::ApplicationError(*err),
+        Err(err) => err,
+    };
+    match err.downcast::<hyper_util::client::legacy::Error>() {
+        Ok(err) => SdkError::HttpError(*err),
+        Err(err) => SdkError::ApplicationError(ApplicationError::new(err.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rest::config::{RateLimitMode, RetryConfig};
+    use crate::rest::layer::circuit::types::HostClass;
+    use bytes::Bytes;
+    use general::enums::rate_limiter::{RateLimitType, RateLimitUnit, RateLimiter};
+    use http_body_util::{Empty, Full};
+    use hyper::{Request, Response, StatusCode};
+    use std::time::{Duration, SystemTime};
+    use tokio::time::Instant;
+    use tower::ServiceExt;
+    use tower::util::BoxCloneService;
+
+    type TestService = BoxCloneService<Request<RequestBody>, Response<Full<Bytes>>, BoxError>;
+
+    // the layers of the client over a service which answers every request at once
+    fn stub_client(config: &Config) -> RestLayers<TestService> {
+        let inner = tower::service_fn(|_: Request<RequestBody>| async {
+            Ok::<_, BoxError>(Response::new(Full::new(Bytes::from("{}"))))
+        });
+        layer_client(config, BoxCloneService::new(inner))
+    }
+
+    fn request(domain: &str, path: &str) -> Request<RequestBody> {
+        Request::get(format!("https://{}{}", domain, path))
+            .extension(RequestExtension::none_auth_api(1))
+            .body(RequestBody::Empty(Empty::<Bytes>::new()))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_get_circuit_open() {
+        let client = BinanceRestClient::build_client(Config::new_default());
+        let reopen_at = SystemTime::now() + Duration::from_secs(3600);
+        client.get_inner_ref().open_circuit(HostClass::Api, reopen_at);
+
+        let domain = client.get_environment().spot_rest_domain();
+        let err = client
+            .get::<(), serde_json::Value>(None, "/api/v3/time", domain, RequestExtension::none_auth_api(1))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, SdkError::CircuitOpen(host_class, at) if host_class == "api" && at == reopen_at));
+    }
 
+    #[tokio::test]
+    async fn test_queue_wait_longer_than_timeout() {
+        let mut config = Config::new_default();
+        config.set_request_timeout(Duration::from_millis(200));
+        config.set_rate_limit_mode(RateLimitMode::Queue(Duration::from_secs(5)));
+        config.set_retry_config(RetryConfig::disabled());
+        // one request per second
+        config.set_spot_rate_limits(RateLimitProfile::new(vec![RateLimiter {
+            rate_limit_type: RateLimitType::RequestWeight,
+            interval: RateLimitUnit::SECOND,
+            interval_num: 1,
+            limit: 1,
+        }]));
+        let client = stub_client(&config);
+        let domain = config.environment().spot_rest_domain();
 
+        // the third request waits in line for more than a second, longer than the request timeout
+        let start = Instant::now();
+        let results = futures_util::future::join_all((0..3).map(|_| {
+            let mut client = client.clone();
+            async move { client.ready().await?.call(request(domain, "/api/v3/time")).await }
+        }))
+        .await;
+        assert!(start.elapsed() > Duration::from_secs(1));
+        for result in results {
+            assert!(result.is_ok());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_open_circuit_holds_no_place_in_line() {
+        let mut config = Config::new_default();
+        config.set_rate_limit_mode(RateLimitMode::Queue(Duration::from_millis(300)));
+        let client = stub_client(&config);
+        // the api request waits in the retry for its circuit to reopen
+        client.get_inner_ref().open_circuit(HostClass::Api, SystemTime::now() + Duration::from_secs(30));
+        let mut banned = client.clone();
+        let spot = config.environment().spot_rest_domain();
+        let waiting = tokio::spawn(async move { banned.ready().await?.call(request(spot, "/api/v3/time")).await });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!waiting.is_finished());
+
+        // the requests of the other host classes are not held back by it
+        let mut future = client.clone();
+        let future_domain = config.environment().future_rest_domain();
+        let resp = future.ready().await.unwrap().call(request(future_domain, "/fapi/v1/time")).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        waiting.abort();
+    }
 }
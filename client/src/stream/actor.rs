@@ -1,26 +1,33 @@
+use crate::stream::pending::SocketOperationResponder;
 use crate::stream::stream::StreamNameFormat;
+use general::error::SdkError;
+use general::result::BinanceResult;
 use std::fmt::Debug;
 use std::hash::Hash;
-use tokio::sync::mpsc;
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
 
-// websocket operator
+// websocket operator, the responder of Add and Remove is completed with the answer of binance
 #[derive(Debug)]
 pub enum SocketItemChangeActor<I> {
-    Add(Vec<I>),
-    Remove(Vec<I>),
+    Add(Vec<I>, SocketOperationResponder),
+    Remove(Vec<I>, SocketOperationResponder),
     Close,
 }
 
 pub struct SocketActorHandle<I> {
     socket_item_change_sender: mpsc::Sender<SocketItemChangeActor<I>>,
+    request_timeout: Duration,
 }
 
 impl<I> SocketActorHandle<I> {
     pub fn new(
         socket_item_change_sender: mpsc::Sender<SocketItemChangeActor<I>>,
+        request_timeout: Duration,
     ) -> Self {
         SocketActorHandle {
             socket_item_change_sender,
+            request_timeout,
         }
     }
 }
@@ -29,17 +36,45 @@ impl<I> SocketActorHandle<I>
 where
     I: StreamNameFormat + Clone + Hash + Eq + Send,
 {
-    pub async fn add_or_remove_socket_item(&mut self, socket_item: SocketItemChangeActor<I>) {
-        self.socket_item_change_sender
-            .send(socket_item)
+    pub async fn add_socket_item(&mut self, socket_item: Vec<I>) -> BinanceResult<()> {
+        self.request(|responder| SocketItemChangeActor::Add(socket_item, responder))
             .await
-            .unwrap();
     }
 
+    pub async fn remove_socket_item(&mut self, socket_item: Vec<I>) -> BinanceResult<()> {
+        self.request(|responder| SocketItemChangeActor::Remove(socket_item, responder))
+            .await
+    }
+
+    // send the subscription change to the socket, and wait until binance answers it or the request times out
+    async fn request(
+        &mut self,
+        socket_item: impl FnOnce(SocketOperationResponder) -> SocketItemChangeActor<I>,
+    ) -> BinanceResult<()> {
+        let (responder, response) = oneshot::channel();
+        if self.socket_item_change_sender.send(socket_item(responder)).await.is_err() {
+            return Err(socket_closed_error());
+        }
+        match tokio::time::timeout(self.request_timeout, response).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(_)) => Err(socket_closed_error()),
+            Err(_) => Err(SdkError::WebsocketRequestTimeout(self.request_timeout)),
+        }
+    }
+
+    // the socket may already be closed by itself, e.g. when all the reconnect attempts failed
     pub async fn send_close(self) {
-        self.socket_item_change_sender
+        if self
+            .socket_item_change_sender
             .send(SocketItemChangeActor::Close)
             .await
-            .expect("send close websocket signal failed");
+            .is_err()
+        {
+            log::info!("The socket is already closed");
+        }
     }
 }
+
+fn socket_closed_error() -> SdkError {
+    SdkError::WebsocketError(tungstenite::Error::AlreadyClosed)
+}
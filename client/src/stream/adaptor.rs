@@ -1,18 +1,21 @@
+use crate::stream::config::SocketConfig;
 use crate::stream::stream::SocketPayloadProcess;
 use async_trait::async_trait;
+use general::result::BinanceResult;
 
 #[async_trait]
 pub trait BinanceWebsocketAdaptor {
     type CLIENT;
     type INPUT;
     type OUTPUT;
-    async fn create_client<P>(process: P, uri: &str) -> Self::CLIENT
+    // socket_config: the reconnection, rotation, heartbeat and payload buffer of the connections of the client
+    async fn create_client<P>(process: P, uri: &str, socket_config: SocketConfig) -> BinanceResult<Self::CLIENT>
     where
         P: SocketPayloadProcess<Self::OUTPUT> + Send + 'static ;
     async fn close(self);
-    async fn subscribe_item(&mut self, input: Self::INPUT);
-    async fn subscribe_items(&mut self, input: Vec<Self::INPUT>);
-    async fn unsubscribe_item(&mut self, input: Self::INPUT);
-    async fn unsubscribe_items(&mut self, input: Vec<Self::INPUT>);
+    async fn subscribe_item(&mut self, input: Self::INPUT) -> BinanceResult<()>;
+    async fn subscribe_items(&mut self, input: Vec<Self::INPUT>) -> BinanceResult<()>;
+    async fn unsubscribe_item(&mut self, input: Self::INPUT) -> BinanceResult<()>;
+    async fn unsubscribe_items(&mut self, input: Vec<Self::INPUT>) -> BinanceResult<()>;
     fn get_subscribe_items(&self) -> Vec<Self::INPUT>;
 }
@@ -0,0 +1,324 @@
+use crate::stream::payload::SocketPayloadActor;
+use futures_util::Stream;
+use general::result::BinanceResult;
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+use tokio::sync::Notify;
+
+// what happens to a payload received while the payload buffer is full
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum BackpressurePolicy {
+    // stop reading the socket until the consumer takes a payload,
+    // binance closes the connection when the consumer is slow for too long.
+    Block,
+    // drop the oldest buffered payload to make room for the new one
+    DropOldest,
+    // drop the new payload
+    DropNewest,
+    // a new payload replaces the buffered payload of the same stream, even when the buffer is not full,
+    // when no payload of the same stream is buffered, the oldest payload is dropped.
+    LatestPerKey,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct PayloadBufferConfig {
+    capacity: usize,
+    policy: BackpressurePolicy,
+}
+
+impl PayloadBufferConfig {
+    // capacity: how many payloads are buffered for the consumer, reconnect and close signals are never dropped
+    // policy: what happens to a payload received while the buffer is full
+    pub fn new(capacity: usize, policy: BackpressurePolicy) -> Self {
+        PayloadBufferConfig {
+            capacity: capacity.max(1),
+            policy,
+        }
+    }
+
+    pub fn new_default() -> Self {
+        PayloadBufferConfig::new(4096, BackpressurePolicy::Block)
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+    pub fn policy(&self) -> BackpressurePolicy {
+        self.policy
+    }
+}
+
+type PayloadItem<O> = BinanceResult<SocketPayloadActor<O>>;
+
+struct BufferState<O> {
+    items: VecDeque<(Option<String>, PayloadItem<O>)>,
+    senders: usize,
+    receiver_closed: bool,
+    receiver_waker: Option<Waker>,
+}
+
+struct PayloadBuffer<O> {
+    config: PayloadBufferConfig,
+    state: Mutex<BufferState<O>>,
+    space_available: Notify,
+    dropped: Arc<AtomicU64>,
+}
+
+// the number of payloads dropped by the backpressure policy, shared by the sender and the receiver of a payload buffer
+#[derive(Debug, Clone, Default)]
+pub struct PayloadDropCounter(Arc<AtomicU64>);
+
+impl PayloadDropCounter {
+    pub fn get_dropped_count(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+// the consumer of the payload buffer is gone
+#[derive(Debug)]
+pub struct PayloadReceiverClosed;
+
+// create a bounded payload buffer, the receiver ends when every sender is dropped and every payload is consumed
+pub fn payload_buffer<O>(config: PayloadBufferConfig) -> (PayloadSender<O>, PayloadReceiver<O>) {
+    let buffer = Arc::new(PayloadBuffer {
+        config,
+        state: Mutex::new(BufferState {
+            items: VecDeque::new(),
+            senders: 1,
+            receiver_closed: false,
+            receiver_waker: None,
+        }),
+        space_available: Notify::new(),
+        dropped: Arc::new(AtomicU64::new(0)),
+    });
+    (
+        PayloadSender {
+            buffer: buffer.clone(),
+        },
+        PayloadReceiver { buffer },
+    )
+}
+
+pub struct PayloadSender<O> {
+    buffer: Arc<PayloadBuffer<O>>,
+}
+
+impl<O> PayloadSender<O> {
+    // a payload key is only needed by BackpressurePolicy::LatestPerKey
+    pub fn needs_key(&self) -> bool {
+        self.buffer.config.policy == BackpressurePolicy::LatestPerKey
+    }
+
+    pub fn get_drop_counter(&self) -> PayloadDropCounter {
+        PayloadDropCounter(self.buffer.dropped.clone())
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.buffer.state.lock().unwrap().receiver_closed
+    }
+
+    // send a payload to the consumer, key is the stream of the payload.
+    // errors and signals are always buffered, payloads follow the backpressure policy.
+    pub async fn send(&self, item: PayloadItem<O>, key: Option<String>) -> Result<(), PayloadReceiverClosed> {
+        let droppable = matches!(item, Ok(SocketPayloadActor::Payload(_)));
+        let mut item = Some(item);
+        loop {
+            let space_available = self.buffer.space_available.notified();
+            {
+                let mut state = self.buffer.state.lock().unwrap();
+                if state.receiver_closed {
+                    return Err(PayloadReceiverClosed);
+                }
+                let next = item.take().unwrap();
+                match self.push(&mut state, key.clone(), next, droppable) {
+                    None => {
+                        if let Some(waker) = state.receiver_waker.take() {
+                            waker.wake();
+                        }
+                        return Ok(());
+                    }
+                    Some(rejected) => {
+                        // only BackpressurePolicy::Block rejects a payload, wait until the consumer takes one
+                        item = Some(rejected);
+                    }
+                }
+            }
+            space_available.await;
+        }
+    }
+
+    // push the item into the buffer, return the item when it must wait for space
+    fn push(
+        &self,
+        state: &mut BufferState<O>,
+        key: Option<String>,
+        item: PayloadItem<O>,
+        droppable: bool,
+    ) -> Option<PayloadItem<O>> {
+        let config = self.buffer.config;
+        if droppable && config.policy == BackpressurePolicy::LatestPerKey && key.is_some() {
+            let buffered = state
+                .items
+                .iter_mut()
+                .find(|(buffered_key, buffered)| *buffered_key == key && matches!(buffered, Ok(SocketPayloadActor::Payload(_))));
+            if let Some(buffered) = buffered {
+                buffered.1 = item;
+                self.buffer.dropped.fetch_add(1, Ordering::Relaxed);
+                return None;
+            }
+        }
+        if droppable && state.items.len() >= config.capacity {
+            match config.policy {
+                BackpressurePolicy::Block => return Some(item),
+                BackpressurePolicy::DropNewest => {
+                    self.buffer.dropped.fetch_add(1, Ordering::Relaxed);
+                    return None;
+                }
+                BackpressurePolicy::DropOldest | BackpressurePolicy::LatestPerKey => {
+                    let oldest = state
+                        .items
+                        .iter()
+                        .position(|(_, buffered)| matches!(buffered, Ok(SocketPayloadActor::Payload(_))));
+                    if let Some(oldest) = oldest {
+                        state.items.remove(oldest);
+                        self.buffer.dropped.fetch_add(1, Ordering::Relaxed);
+                    }
+                }
+            }
+        }
+        state.items.push_back((key, item));
+        None
+    }
+}
+
+impl<O> Clone for PayloadSender<O> {
+    fn clone(&self) -> Self {
+        self.buffer.state.lock().unwrap().senders += 1;
+        PayloadSender {
+            buffer: self.buffer.clone(),
+        }
+    }
+}
+
+impl<O> Drop for PayloadSender<O> {
+    fn drop(&mut self) {
+        let mut state = self.buffer.state.lock().unwrap();
+        state.senders -= 1;
+        if state.senders == 0
+            && let Some(waker) = state.receiver_waker.take()
+        {
+            waker.wake();
+        }
+    }
+}
+
+pub struct PayloadReceiver<O> {
+    buffer: Arc<PayloadBuffer<O>>,
+}
+
+impl<O> PayloadReceiver<O> {
+    pub fn get_drop_counter(&self) -> PayloadDropCounter {
+        PayloadDropCounter(self.buffer.dropped.clone())
+    }
+
+    pub fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<PayloadItem<O>>> {
+        let mut state = self.buffer.state.lock().unwrap();
+        match state.items.pop_front() {
+            Some((_, item)) => {
+                self.buffer.space_available.notify_waiters();
+                Poll::Ready(Some(item))
+            }
+            None if state.senders == 0 => Poll::Ready(None),
+            None => {
+                state.receiver_waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+
+    pub async fn recv(&mut self) -> Option<PayloadItem<O>> {
+        futures_util::future::poll_fn(|cx| self.poll_recv(cx)).await
+    }
+}
+
+impl<O> Stream for PayloadReceiver<O> {
+    type Item = PayloadItem<O>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.get_mut().poll_recv(cx)
+    }
+}
+
+impl<O> Drop for PayloadReceiver<O> {
+    fn drop(&mut self) {
+        let mut state = self.buffer.state.lock().unwrap();
+        state.receiver_closed = true;
+        state.items.clear();
+        drop(state);
+        self.buffer.space_available.notify_waiters();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn payload(value: u32) -> PayloadItem<u32> {
+        Ok(SocketPayloadActor::Payload(value))
+    }
+
+    async fn drain(receiver: &mut PayloadReceiver<u32>, sender: PayloadSender<u32>) -> Vec<u32> {
+        drop(sender);
+        let mut values = Vec::new();
+        while let Some(item) = receiver.recv().await {
+            match item {
+                Ok(SocketPayloadActor::Payload(value)) => values.push(value),
+                Ok(SocketPayloadActor::Close(_)) => values.push(0),
+                _ => {}
+            }
+        }
+        values
+    }
+
+    #[tokio::test]
+    async fn test_drop_policy() {
+        let (sender, mut receiver) = payload_buffer(PayloadBufferConfig::new(2, BackpressurePolicy::DropOldest));
+        for value in 1..=3 {
+            sender.send(payload(value), None).await.unwrap();
+        }
+        sender.send(Ok(SocketPayloadActor::Close(1)), None).await.unwrap();
+        assert_eq!(drain(&mut receiver, sender).await, vec![2, 3, 0]);
+        assert_eq!(receiver.get_drop_counter().get_dropped_count(), 1);
+
+        let (sender, mut receiver) = payload_buffer(PayloadBufferConfig::new(2, BackpressurePolicy::DropNewest));
+        for value in 1..=3 {
+            sender.send(payload(value), None).await.unwrap();
+        }
+        assert_eq!(drain(&mut receiver, sender).await, vec![1, 2]);
+        assert_eq!(receiver.get_drop_counter().get_dropped_count(), 1);
+
+        let (sender, mut receiver) = payload_buffer(PayloadBufferConfig::new(4, BackpressurePolicy::LatestPerKey));
+        sender.send(payload(1), Some("btcusdt".to_string())).await.unwrap();
+        sender.send(payload(2), Some("ethusdt".to_string())).await.unwrap();
+        sender.send(payload(3), Some("btcusdt".to_string())).await.unwrap();
+        assert_eq!(drain(&mut receiver, sender).await, vec![3, 2]);
+        assert_eq!(receiver.get_drop_counter().get_dropped_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_block_policy() {
+        let (sender, mut receiver) = payload_buffer(PayloadBufferConfig::new(1, BackpressurePolicy::Block));
+        sender.send(payload(1), None).await.unwrap();
+        let blocked_sender = sender.clone();
+        let blocked = tokio::spawn(async move { blocked_sender.send(payload(2), None).await });
+        tokio::task::yield_now().await;
+        assert!(!blocked.is_finished());
+        assert!(matches!(receiver.recv().await, Some(Ok(SocketPayloadActor::Payload(1)))));
+        blocked.await.unwrap().unwrap();
+        assert_eq!(drain(&mut receiver, sender).await, vec![2]);
+        assert_eq!(receiver.get_drop_counter().get_dropped_count(), 0);
+    }
+}
@@ -1,44 +1,77 @@
 use crate::stream::actor::{SocketActorHandle, SocketItemChangeActor};
-use crate::stream::payload::SocketPayloadActor;
+use crate::stream::config::SocketConfig;
+use crate::stream::buffer::{PayloadDropCounter, PayloadReceiver, PayloadSender, payload_buffer};
 use crate::stream::socket::{SocketReceiverState, SocketSenderState, stream};
 use crate::stream::stream::StreamNameFormat;
+use general::error::SdkError;
 use general::result::BinanceResult;
 use serde::de::DeserializeOwned;
 use std::collections::HashSet;
 use std::fmt::Debug;
 use std::hash::Hash;
-use tokio::sync::mpsc::{UnboundedReceiver, channel, unbounded_channel};
+use tokio::sync::mpsc::channel;
 use tokio_tungstenite::tungstenite::Message;
 
 pub struct WebsocketClient<I> {
     socket_item: HashSet<I>,
     socket_actor_handle: SocketActorHandle<I>,
+    drop_counter: PayloadDropCounter,
 }
 
 impl<I> WebsocketClient<I> {
 
-    pub async fn new_with_uri<O>(uri: &str) -> (
+    pub async fn new_with_uri<O>(uri: &str) -> BinanceResult<(
         WebsocketClient<I>,
-        UnboundedReceiver<BinanceResult<SocketPayloadActor<O>>>,
-    )
+        PayloadReceiver<O>,
+    )>
+    where
+        I: StreamNameFormat + Clone + Hash + Eq + Send + 'static,
+        O: DeserializeOwned + Send + 'static + Debug,
+    {
+        Self::new_with_config(uri, SocketConfig::new_default()).await
+    }
+
+    pub async fn new_with_config<O>(uri: &str, socket_config: SocketConfig) -> BinanceResult<(
+        WebsocketClient<I>,
+        PayloadReceiver<O>,
+    )>
+    where
+        I: StreamNameFormat + Clone + Hash + Eq + Send + 'static,
+        O: DeserializeOwned + Send + 'static + Debug,
+    {
+        let (payload_sender, payload_receiver) = payload_buffer::<O>(socket_config.buffer_config());
+        let client = Self::new_with_sender(uri, socket_config, payload_sender).await?;
+        Ok((client, payload_receiver))
+    }
+
+    // the payloads are sent to payload_sender, several clients can share the same payload buffer
+    pub async fn new_with_sender<O>(
+        uri: &str,
+        socket_config: SocketConfig,
+        payload_sender: PayloadSender<O>,
+    ) -> BinanceResult<WebsocketClient<I>>
     where
         I: StreamNameFormat + Clone + Hash + Eq + Send + 'static,
         O: DeserializeOwned + Send + 'static + Debug,
     {
         let (item_sender, item_receiver) = channel::<SocketItemChangeActor<I>>(64);
-        let socket_actor_handle = SocketActorHandle::new(item_sender);
+        let socket_actor_handle = SocketActorHandle::new(item_sender, socket_config.request_timeout());
         let (socket_health_sender, socket_health_receiver) = channel::<Message>(1);
-        let (payload_sender, payload_receiver) =
-            unbounded_channel::<BinanceResult<SocketPayloadActor<O>>>();
+        let drop_counter = payload_sender.get_drop_counter();
         let socket_sender_state =
-            SocketSenderState::new_with_uri(uri, item_receiver, socket_health_receiver).await;
+            SocketSenderState::new_with_uri(uri, socket_config, item_receiver, socket_health_receiver).await?;
         let socket_receiver_state = SocketReceiverState::new(payload_sender, socket_health_sender);
         tokio::spawn(stream(socket_sender_state, socket_receiver_state));
-        let client = WebsocketClient {
+        Ok(WebsocketClient {
             socket_item: HashSet::new(),
             socket_actor_handle,
-        };
-        (client, payload_receiver)
+            drop_counter,
+        })
+    }
+
+    // the number of payloads dropped by the backpressure policy of the payload buffer
+    pub fn get_dropped_count(&self) -> u64 {
+        self.drop_counter.get_dropped_count()
     }
 }
 
@@ -50,12 +83,21 @@ where
         self.subscribe_multiple(vec![param]).await
     }
 
+    // resolve when binance accepts the subscription, the items are forgotten again if binance rejects it.
+    // the items are kept after a timeout, they are subscribed again whenever the connection is reopened.
     pub async fn subscribe_multiple(&mut self, params: Vec<I>) -> BinanceResult<()> {
         let effect_items = self.add_socket_items(params);
-        self.socket_actor_handle
-            .add_or_remove_socket_item(SocketItemChangeActor::Add(effect_items))
+        if effect_items.is_empty() {
+            return Ok(());
+        }
+        let result = self
+            .socket_actor_handle
+            .add_socket_item(effect_items.clone())
             .await;
-        Ok(())
+        if let Err(SdkError::WebsocketRequestError(..)) = &result {
+            self.remove_socket_items(effect_items);
+        }
+        result
     }
 
     pub async fn unsubscribe_single(&mut self, param: I) -> BinanceResult<()> {
@@ -64,10 +106,17 @@ where
 
     pub async fn unsubscribe_multiple(&mut self, params: Vec<I>) -> BinanceResult<()> {
         let effect_items = self.remove_socket_items(params);
-        self.socket_actor_handle
-            .add_or_remove_socket_item(SocketItemChangeActor::Remove(effect_items))
+        if effect_items.is_empty() {
+            return Ok(());
+        }
+        let result = self
+            .socket_actor_handle
+            .remove_socket_item(effect_items.clone())
             .await;
-        Ok(())
+        if let Err(SdkError::WebsocketRequestError(..)) = &result {
+            self.add_socket_items(effect_items);
+        }
+        result
     }
     pub fn get_all_subscribers(&self) -> &HashSet<I> {
         &self.socket_item
@@ -0,0 +1,274 @@
+use crate::stream::buffer::PayloadBufferConfig;
+use std::collections::HashMap;
+use std::time::Duration;
+
+#[derive(Debug, Copy, Clone)]
+pub struct ReconnectConfig {
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    multiplier: u32,
+    max_attempts: Option<u32>,
+}
+
+impl ReconnectConfig {
+    // initial_backoff: the delay before the first reconnect attempt
+    // max_backoff: the upper bound of the delay between two attempts
+    // multiplier: every failed attempt multiplies the delay by this factor
+    // max_attempts: give up after this many consecutive failed attempts, None means retry forever
+    pub fn new(
+        initial_backoff: Duration,
+        max_backoff: Duration,
+        multiplier: u32,
+        max_attempts: Option<u32>,
+    ) -> Self {
+        ReconnectConfig {
+            initial_backoff,
+            max_backoff,
+            multiplier,
+            max_attempts,
+        }
+    }
+
+    pub fn new_default() -> Self {
+        ReconnectConfig::new(Duration::from_secs(1), Duration::from_secs(60), 2, None)
+    }
+
+    pub fn disabled() -> Self {
+        ReconnectConfig::new(Duration::ZERO, Duration::ZERO, 1, Some(0))
+    }
+
+    pub fn initial_backoff(&self) -> Duration {
+        self.initial_backoff
+    }
+    pub fn max_backoff(&self) -> Duration {
+        self.max_backoff
+    }
+    pub fn multiplier(&self) -> u32 {
+        self.multiplier
+    }
+    pub fn max_attempts(&self) -> Option<u32> {
+        self.max_attempts
+    }
+
+    pub fn is_exhausted(&self, attempt: u32) -> bool {
+        match self.max_attempts {
+            Some(max_attempts) => attempt > max_attempts,
+            None => false,
+        }
+    }
+
+    // attempt starts from 1, the delay grows exponentially until it reaches max_backoff
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = self
+            .multiplier
+            .checked_pow(attempt.saturating_sub(1))
+            .unwrap_or(u32::MAX);
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+}
+
+// Binance closes every websocket connection after 24 hours,
+// the connection is replaced by a new one before that deadline.
+#[derive(Debug, Copy, Clone)]
+pub struct RotationConfig {
+    interval: Option<Duration>,
+    overlap: Duration,
+}
+
+impl RotationConfig {
+    // interval: how long a connection is used before it is replaced, None means never replace it
+    // overlap: how long the old and the new connection both deliver payloads, duplicates are dropped
+    pub fn new(interval: Option<Duration>, overlap: Duration) -> Self {
+        RotationConfig { interval, overlap }
+    }
+
+    pub fn new_default() -> Self {
+        RotationConfig::new(Some(Duration::from_secs(23 * 60 * 60)), Duration::from_secs(10))
+    }
+
+    pub fn disabled() -> Self {
+        RotationConfig::new(None, Duration::ZERO)
+    }
+
+    pub fn interval(&self) -> Option<Duration> {
+        self.interval
+    }
+    pub fn overlap(&self) -> Duration {
+        self.overlap
+    }
+}
+
+// the limits of a single connection, see https://developers.binance.com/docs/binance-spot-api-docs/web-socket-streams#websocket-limits
+#[derive(Debug, Copy, Clone)]
+pub struct SocketLimitConfig {
+    max_streams: usize,
+    max_messages_per_second: u32,
+}
+
+impl SocketLimitConfig {
+    // max_streams: how many streams a single connection can subscribe, extra streams are subscribed on another connection
+    // max_messages_per_second: how many messages are sent to binance per second on a single connection
+    pub fn new(max_streams: usize, max_messages_per_second: u32) -> Self {
+        SocketLimitConfig {
+            max_streams,
+            max_messages_per_second,
+        }
+    }
+
+    pub fn new_default() -> Self {
+        SocketLimitConfig::new(1024, 5)
+    }
+
+    pub fn max_streams(&self) -> usize {
+        self.max_streams
+    }
+    pub fn max_messages_per_second(&self) -> u32 {
+        self.max_messages_per_second
+    }
+
+    // the minimal delay between two messages sent on a single connection
+    pub fn message_interval(&self) -> Duration {
+        Duration::from_secs(1) / self.max_messages_per_second.max(1)
+    }
+}
+
+// detects a half-open connection which delivers nothing while looking alive, a stale connection is reopened.
+#[derive(Debug, Clone)]
+pub struct HeartbeatConfig {
+    idle_timeout: Option<Duration>,
+    stream_idle_timeout: Option<Duration>,
+    stream_idle_timeouts: HashMap<String, Duration>,
+    ping_interval: Option<Duration>,
+}
+
+impl HeartbeatConfig {
+    // idle_timeout: the connection is stale when it receives no message at all for this long, None means never
+    // stream_idle_timeout: the connection is stale when a subscribed stream delivers nothing for this long,
+    //                      e.g. no trade of BTCUSDT for 30 seconds, None means never
+    // ping_interval: how often a ping is sent to binance, the pong keeps a quiet connection from looking idle, None means never
+    pub fn new(
+        idle_timeout: Option<Duration>,
+        stream_idle_timeout: Option<Duration>,
+        ping_interval: Option<Duration>,
+    ) -> Self {
+        HeartbeatConfig {
+            idle_timeout,
+            stream_idle_timeout,
+            stream_idle_timeouts: HashMap::new(),
+            ping_interval,
+        }
+    }
+
+    // the stream idle timeout of one stream, it replaces stream_idle_timeout for that stream,
+    // e.g. 10 minutes for the trades of a quiet symbol. stream is the binance stream name as
+    // StreamNameFormat::stream_name gives it, e.g. "btcusdt@trade", on the raw and the combined endpoint alike
+    pub fn with_stream_idle_timeout(mut self, stream: impl Into<String>, timeout: Duration) -> Self {
+        self.stream_idle_timeouts.insert(stream.into(), timeout);
+        self
+    }
+
+    pub fn new_default() -> Self {
+        HeartbeatConfig::new(Some(Duration::from_secs(90)), None, Some(Duration::from_secs(30)))
+    }
+
+    pub fn disabled() -> Self {
+        HeartbeatConfig::new(None, None, None)
+    }
+
+    pub fn idle_timeout(&self) -> Option<Duration> {
+        self.idle_timeout
+    }
+    pub fn stream_idle_timeout(&self) -> Option<Duration> {
+        self.stream_idle_timeout
+    }
+    // the stream idle timeout of stream, a stream without its own timeout takes stream_idle_timeout
+    pub fn stream_idle_timeout_of(&self, stream: &str) -> Option<Duration> {
+        self.stream_idle_timeouts.get(stream).copied().or(self.stream_idle_timeout)
+    }
+    pub fn ping_interval(&self) -> Option<Duration> {
+        self.ping_interval
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SocketConfig {
+    reconnect_config: ReconnectConfig,
+    rotation_config: RotationConfig,
+    limit_config: SocketLimitConfig,
+    buffer_config: PayloadBufferConfig,
+    heartbeat_config: HeartbeatConfig,
+    request_timeout: Duration,
+}
+
+impl SocketConfig {
+    // request_timeout: how long a SUBSCRIBE or UNSUBSCRIBE request waits for the answer of binance
+    pub fn new(
+        reconnect_config: ReconnectConfig,
+        rotation_config: RotationConfig,
+        limit_config: SocketLimitConfig,
+        buffer_config: PayloadBufferConfig,
+        heartbeat_config: HeartbeatConfig,
+        request_timeout: Duration,
+    ) -> Self {
+        SocketConfig {
+            reconnect_config,
+            rotation_config,
+            limit_config,
+            buffer_config,
+            heartbeat_config,
+            request_timeout,
+        }
+    }
+
+    pub fn new_default() -> Self {
+        SocketConfig::new(
+            ReconnectConfig::new_default(),
+            RotationConfig::new_default(),
+            SocketLimitConfig::new_default(),
+            PayloadBufferConfig::new_default(),
+            HeartbeatConfig::new_default(),
+            Duration::from_secs(10),
+        )
+    }
+
+    pub fn reconnect_config(&self) -> ReconnectConfig {
+        self.reconnect_config
+    }
+    pub fn rotation_config(&self) -> RotationConfig {
+        self.rotation_config
+    }
+    pub fn limit_config(&self) -> SocketLimitConfig {
+        self.limit_config
+    }
+    pub fn buffer_config(&self) -> PayloadBufferConfig {
+        self.buffer_config
+    }
+    pub fn heartbeat_config(&self) -> HeartbeatConfig {
+        self.heartbeat_config.clone()
+    }
+    pub fn request_timeout(&self) -> Duration {
+        self.request_timeout
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff() {
+        let config =
+            ReconnectConfig::new(Duration::from_millis(500), Duration::from_secs(5), 2, Some(6));
+        assert_eq!(config.backoff(1), Duration::from_millis(500));
+        assert_eq!(config.backoff(2), Duration::from_secs(1));
+        assert_eq!(config.backoff(4), Duration::from_secs(4));
+        assert_eq!(config.backoff(5), Duration::from_secs(5));
+        assert_eq!(config.backoff(64), Duration::from_secs(5));
+        assert!(!config.is_exhausted(6));
+        assert!(config.is_exhausted(7));
+        assert!(ReconnectConfig::disabled().is_exhausted(1));
+        assert_eq!(SocketLimitConfig::new_default().message_interval(), Duration::from_millis(200));
+    }
+}
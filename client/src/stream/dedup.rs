@@ -0,0 +1,249 @@
+use serde_json::Value;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use tokio::time::Instant;
+
+// the two connections alive while a connection is handed over to its replacement
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum HandoverConnection {
+    Old,
+    New,
+}
+
+// the events of a stream the new connection delivered before the old one caught up with it
+#[derive(Debug)]
+struct HeldEvents {
+    first_sequence: u64,
+    texts: Vec<String>,
+}
+
+// While a connection is handed over to its replacement, both connections deliver the same events.
+// SocketPayloadDeduplicator drops the second copy of every event:
+// 1. market events are ordered by their update id (depthUpdate), trade id (trade), aggregate trade id (aggTrade)
+//    or event time (every other event), an event not newer than the last delivered one of the same stream is dropped.
+// 2. events sharing the same event time, and payloads without an event type, are compared by their raw text.
+// A stream keeps being delivered from the old connection until it reaches the first event the new connection
+// delivered for it, the events of the new connection are held until then so a lagging old connection leaves no gap.
+#[derive(Debug)]
+pub struct SocketPayloadDeduplicator {
+    active_until: Instant,
+    last_sequence: HashMap<String, u64>,
+    seen_text: HashSet<u64>,
+    held: HashMap<String, HeldEvents>,
+    // the streams delivered from the new connection
+    switched: HashSet<String>,
+    // the old connection is gone, every stream is delivered from the new one
+    handed_over: bool,
+}
+
+impl SocketPayloadDeduplicator {
+    pub fn new(active_until: Instant) -> Self {
+        SocketPayloadDeduplicator {
+            active_until,
+            last_sequence: HashMap::new(),
+            seen_text: HashSet::new(),
+            held: HashMap::new(),
+            switched: HashSet::new(),
+            handed_over: false,
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        Instant::now() < self.active_until
+    }
+
+    pub fn set_active_until(&mut self, active_until: Instant) {
+        self.active_until = active_until;
+    }
+
+    // the payloads to send to the consumer in order, the payload received from connection, the events held for
+    // its stream, or nothing when it was delivered before
+    pub fn accept(&mut self, text: &str, connection: HandoverConnection) -> Vec<String> {
+        let sequence = serde_json::from_str::<Value>(text)
+            .ok()
+            .and_then(|value| sequence_of(&value));
+        let Some((key, field, sequence)) = sequence else {
+            return self.accept_text(text).then(|| text.to_string()).into_iter().collect();
+        };
+        if self.handed_over || self.switched.contains(&key) {
+            return self.deliver(key, field, sequence, text);
+        }
+        match connection {
+            HandoverConnection::Old => match self.held.get(&key) {
+                // the old connection caught up with the new one, the stream switches to the new connection
+                Some(held) if sequence >= held.first_sequence => {
+                    let held = self.held.remove(&key).unwrap();
+                    self.switched.insert(key.clone());
+                    let mut delivered = self.release(held);
+                    delivered.extend(self.deliver(key, field, sequence, text));
+                    delivered
+                }
+                _ => self.deliver(key, field, sequence, text),
+            },
+            HandoverConnection::New => {
+                // the new connection is behind the old one, its next events are the ones not delivered yet
+                if self.last_sequence.get(&key).is_some_and(|last| sequence <= *last) {
+                    self.switched.insert(key.clone());
+                    return self.deliver(key, field, sequence, text);
+                }
+                self.held
+                    .entry(key)
+                    .or_insert_with(|| HeldEvents {
+                        first_sequence: sequence,
+                        texts: Vec::new(),
+                    })
+                    .texts
+                    .push(text.to_string());
+                Vec::new()
+            }
+        }
+    }
+
+    // the old connection is gone, the held events of every stream are delivered
+    pub fn hand_over(&mut self) -> Vec<String> {
+        self.handed_over = true;
+        let held = std::mem::take(&mut self.held);
+        held.into_values().flat_map(|held| self.release(held)).collect()
+    }
+
+    fn release(&mut self, held: HeldEvents) -> Vec<String> {
+        held.texts
+            .into_iter()
+            .filter(|text| {
+                match serde_json::from_str::<Value>(text).ok().and_then(|value| sequence_of(&value)) {
+                    Some((key, field, sequence)) => !self.deliver(key, field, sequence, text).is_empty(),
+                    None => self.accept_text(text),
+                }
+            })
+            .collect()
+    }
+
+    fn deliver(&mut self, key: String, field: &str, sequence: u64, text: &str) -> Vec<String> {
+        let last = self.last_sequence.get(&key).copied();
+        let accepted = match last {
+            Some(last) if sequence < last => false,
+            Some(last) if sequence == last && field != "E" => false,
+            Some(last) if sequence == last => self.accept_text(text),
+            _ => {
+                self.last_sequence.insert(key, sequence);
+                self.accept_text(text)
+            }
+        };
+        accepted.then(|| text.to_string()).into_iter().collect()
+    }
+
+    fn accept_text(&mut self, text: &str) -> bool {
+        let mut hasher = DefaultHasher::new();
+        text.hash(&mut hasher);
+        self.seen_text.insert(hasher.finish())
+    }
+}
+
+// the stream key and the ordering field of a payload
+fn sequence_of(payload: &Value) -> Option<(String, &'static str, u64)> {
+    // the payload of the combined stream endpoint is wrapped as {"stream":...,"data":...}
+    let payload = payload.get("data").unwrap_or(payload);
+    let event_type = payload.get("e")?.as_str()?;
+    let field = match event_type {
+        "trade" => "t",
+        "aggTrade" => "a",
+        "depthUpdate" => "u",
+        _ => "E",
+    };
+    let sequence = payload.get(field)?.as_u64()?;
+    Some((event_key(payload)?, field, sequence))
+}
+
+// the stream of a payload, it is the stream name of a combined stream payload,
+// otherwise it is built from the fields of the event
+pub fn stream_key(text: &str) -> Option<String> {
+    let value = serde_json::from_str::<Value>(text).ok()?;
+    match value.get("stream").and_then(Value::as_str) {
+        Some(stream) => Some(stream.to_string()),
+        None => event_key(&value),
+    }
+}
+
+// event type, symbol, pair, contract type and kline interval joined by '@'
+fn event_key(payload: &Value) -> Option<String> {
+    let event_type = payload.get("e")?.as_str()?;
+    let key = [
+        Some(event_type),
+        payload.get("s").and_then(Value::as_str),
+        payload.get("ps").and_then(Value::as_str),
+        payload.get("ct").and_then(Value::as_str),
+        payload
+            .get("k")
+            .and_then(|kline| kline.get("i"))
+            .and_then(Value::as_str),
+    ]
+    .into_iter()
+    .flatten()
+    .collect::<Vec<_>>()
+    .join("@");
+    Some(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    // true when the payload of the old connection is sent to the consumer
+    fn accepted(dedup: &mut SocketPayloadDeduplicator, text: &str) -> bool {
+        !dedup.accept(text, HandoverConnection::Old).is_empty()
+    }
+
+    #[test]
+    fn test_accept() {
+        let mut dedup = SocketPayloadDeduplicator::new(Instant::now() + Duration::from_secs(10));
+        let trade_1 = r#"{"e":"trade","E":1,"s":"BTCUSDT","t":10,"p":"1","q":"1","T":1,"m":true}"#;
+        let trade_2 = r#"{"e":"trade","E":1,"s":"BTCUSDT","t":11,"p":"1","q":"1","T":1,"m":true}"#;
+        let other_symbol = r#"{"e":"trade","E":1,"s":"ETHUSDT","t":10,"p":"1","q":"1","T":1,"m":true}"#;
+        assert!(accepted(&mut dedup, trade_1));
+        assert!(accepted(&mut dedup, trade_2));
+        assert!(!accepted(&mut dedup, trade_1));
+        assert!(!accepted(&mut dedup, trade_2));
+        assert!(accepted(&mut dedup, other_symbol));
+        let combined_trade_1 = format!(r#"{{"stream":"btcusdt@trade","data":{}}}"#, trade_1);
+        assert!(!accepted(&mut dedup, &combined_trade_1));
+
+        let ticker_1 = r#"{"e":"24hrMiniTicker","E":5,"s":"BTCUSDT","c":"1"}"#;
+        let ticker_2 = r#"{"e":"24hrMiniTicker","E":5,"s":"BTCUSDT","c":"2"}"#;
+        assert!(accepted(&mut dedup, ticker_1));
+        assert!(accepted(&mut dedup, ticker_2));
+        assert!(!accepted(&mut dedup, ticker_1));
+
+        let book = r#"{"lastUpdateId":160,"bids":[],"asks":[]}"#;
+        assert!(accepted(&mut dedup, book));
+        assert!(!accepted(&mut dedup, book));
+        assert!(dedup.is_active());
+    }
+
+    #[test]
+    fn test_lagging_old_connection() {
+        let trade = |id: u64| format!(r#"{{"e":"trade","E":1,"s":"BTCUSDT","t":{},"p":"1","q":"1","T":1,"m":true}}"#, id);
+        let mut dedup = SocketPayloadDeduplicator::new(Instant::now() + Duration::from_secs(10));
+        let mut delivered = vec![];
+        let mut receive = |dedup: &mut SocketPayloadDeduplicator, id: u64, connection: HandoverConnection| {
+            delivered.extend(dedup.accept(&trade(id), connection));
+        };
+        receive(&mut dedup, 100, HandoverConnection::Old);
+        // the new connection is ahead of the old one
+        receive(&mut dedup, 105, HandoverConnection::New);
+        receive(&mut dedup, 106, HandoverConnection::New);
+        for id in 101..=106 {
+            receive(&mut dedup, id, HandoverConnection::Old);
+        }
+        receive(&mut dedup, 107, HandoverConnection::New);
+        receive(&mut dedup, 107, HandoverConnection::Old);
+        // a stream the old connection does not catch up with is delivered when it is gone
+        let other = r#"{"e":"trade","E":1,"s":"ETHUSDT","t":9,"p":"1","q":"1","T":1,"m":true}"#;
+        assert!(dedup.accept(other, HandoverConnection::New).is_empty());
+        let released = dedup.hand_over();
+        assert_eq!(delivered, (100..=107).map(trade).collect::<Vec<_>>());
+        assert_eq!(released, vec![other.to_string()]);
+        assert!(dedup.accept(other, HandoverConnection::New).is_empty());
+    }
+}
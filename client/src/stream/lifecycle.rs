@@ -0,0 +1,124 @@
+use crate::stream::buffer::PayloadSender;
+use crate::stream::payload::SocketPayloadActor;
+use std::time::{Duration, SystemTime};
+
+// what happened to a socket connection
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum SocketLifecycle {
+    // a connection is opened, the first one or a reopened one
+    Connected,
+    // the connection is lost, it will be reopened
+    Disconnected,
+    // the n-th attempt to reopen the connection starts after backoff
+    Reconnecting { attempt: u32, backoff: Duration },
+    // the n-th attempt to reopen the connection failed
+    ReconnectFailed { attempt: u32 },
+    // the connection is replaced by a new one before the 24 hours limit
+    Rotated,
+    // the replacement connection failed, the old connection is still in use
+    RotationFailed,
+    // nothing was received for too long, see HeartbeatConfig, the connection will be reopened.
+    // stream is the stream which stopped delivering, None when the whole connection is idle
+    Stale { stream: Option<String> },
+    // binance accepted a SUBSCRIBE or UNSUBSCRIBE request
+    SubscriptionConfirmed { request_id: u64 },
+    // binance rejected a SUBSCRIBE or UNSUBSCRIBE request
+    SubscriptionRejected { request_id: u64, code: i64 },
+    // the socket is closed and will not be reopened
+    Closed,
+}
+
+// a lifecycle event of a socket, it is delivered to the payload processor as SocketPayloadActor::Lifecycle
+#[derive(Debug, Clone)]
+pub struct SocketLifecycleEvent {
+    // the id of the socket, it stays the same when the connection is reopened or rotated
+    pub connection_id: u64,
+    pub lifecycle: SocketLifecycle,
+    pub reason: String,
+    // when the event happened, milliseconds since the unix epoch
+    pub timestamp: u64,
+    // when the current connection was opened, milliseconds since the unix epoch, None while disconnected
+    pub connected_at: Option<u64>,
+}
+
+impl SocketLifecycleEvent {
+    pub fn new(
+        connection_id: u64,
+        lifecycle: SocketLifecycle,
+        reason: impl Into<String>,
+        connected_at: Option<u64>,
+    ) -> Self {
+        SocketLifecycleEvent {
+            connection_id,
+            lifecycle,
+            reason: reason.into(),
+            timestamp: current_timestamp(),
+            connected_at,
+        }
+    }
+}
+
+// reports the lifecycle events of a socket to its consumer, it keeps when the current connection was opened
+pub(crate) struct SocketLifecycleReporter {
+    connection_id: u64,
+    connected_at: Option<u64>,
+}
+
+impl SocketLifecycleReporter {
+    pub(crate) fn new(connection_id: u64) -> Self {
+        SocketLifecycleReporter {
+            connection_id,
+            connected_at: None,
+        }
+    }
+
+    pub(crate) fn set_connected(&mut self) {
+        self.connected_at = Some(current_timestamp());
+    }
+
+    pub(crate) fn set_disconnected(&mut self) {
+        self.connected_at = None;
+    }
+
+    // the event is buffered like reconnect and close signals, it is lost only when the consumer is gone
+    pub(crate) async fn report<O>(&self, payload_sender: &PayloadSender<O>, lifecycle: SocketLifecycle, reason: impl Into<String>) {
+        let event = SocketLifecycleEvent::new(self.connection_id, lifecycle, reason, self.connected_at);
+        log::debug!("Socket lifecycle event: {:?}", event);
+        if payload_sender.send(Ok(SocketPayloadActor::Lifecycle(event)), None).await.is_err() {
+            log::debug!("Socket payload consumer is gone, drop lifecycle event: {}", self.connection_id);
+        }
+    }
+}
+
+pub(crate) fn current_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stream::buffer::{PayloadBufferConfig, payload_buffer};
+
+    #[tokio::test]
+    async fn test_report() {
+        let (sender, mut receiver) = payload_buffer::<u32>(PayloadBufferConfig::new_default());
+        let mut reporter = SocketLifecycleReporter::new(7);
+        reporter.report(&sender, SocketLifecycle::Reconnecting { attempt: 1, backoff: Duration::from_secs(1) }, "lost").await;
+        reporter.set_connected();
+        reporter.report(&sender, SocketLifecycle::Connected, "reconnected").await;
+        drop(sender);
+
+        let Some(Ok(SocketPayloadActor::Lifecycle(event))) = receiver.recv().await else { panic!("lifecycle event expected") };
+        assert_eq!(event.connection_id, 7);
+        assert_eq!(event.lifecycle, SocketLifecycle::Reconnecting { attempt: 1, backoff: Duration::from_secs(1) });
+        assert!(event.connected_at.is_none());
+        let Some(Ok(SocketPayloadActor::Lifecycle(event))) = receiver.recv().await else { panic!("lifecycle event expected") };
+        assert_eq!(event.lifecycle, SocketLifecycle::Connected);
+        assert_eq!(event.reason, "reconnected");
+        assert!(event.connected_at.is_some_and(|connected_at| connected_at <= event.timestamp));
+        assert!(receiver.recv().await.is_none());
+    }
+}
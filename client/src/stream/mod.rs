@@ -4,3 +4,11 @@ pub mod stream;
 pub mod socket;
 pub mod payload;
 pub mod adaptor;
+pub mod config;
+pub mod dedup;
+pub mod router;
+pub mod pending;
+pub mod pool;
+pub mod buffer;
+pub mod lifecycle;
+pub mod watchdog;
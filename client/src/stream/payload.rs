@@ -1,30 +1,76 @@
-use serde::de::DeserializeOwned;
+use crate::stream::lifecycle::SocketLifecycleEvent;
+use crate::stream::stream::StreamNameFormat;
+use serde::de::{DeserializeOwned, Error};
 use std::fmt::Debug;
-use serde::Deserialize;
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
 
 #[derive(Debug)]
 pub enum SocketPayloadActor<O>
 {
     Payload(O),
     Close(u8),
+    // a connection, disconnection, reconnect attempt, rotation or subscription answer of the socket, see SocketLifecycle
+    Lifecycle(SocketLifecycleEvent),
 }
 
 impl<O> SocketPayloadActor<O>
 where
     O: DeserializeOwned + Send + 'static + Debug,
 {
-    pub fn payload(self) -> O {
+    // the payload, None for the close and lifecycle events
+    pub fn payload(self) -> Option<O> {
         match self {
-            SocketPayloadActor::Payload(payload) => payload,
-            _ => panic!("other payload cannot support"),
+            SocketPayloadActor::Payload(payload) => Some(payload),
+            _ => None,
         }
     }
 }
 
 
 
+// the answer of a SUBSCRIBE or UNSUBSCRIBE request, error is set when binance rejects the request
 #[derive(Debug, Deserialize)]
 pub struct SocketOperationResp {
-    pub result: Option<String>,
+    pub result: Option<Value>,
+    pub error: Option<SocketOperationError>,
     pub id: u64,
-}
\ No newline at end of file
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SocketOperationError {
+    pub code: i64,
+    pub msg: String,
+}
+
+// a payload of the combined stream endpoint(/stream?streams=...), binance wraps every event into
+// {"stream":"<stream name>","data":<raw payload>}, the stream name is parsed back to the stream key I.
+#[derive(Debug)]
+pub struct CombinedStreamPayload<I, O> {
+    pub stream: I,
+    pub data: O,
+}
+
+impl<'de, I, O> Deserialize<'de> for CombinedStreamPayload<I, O>
+where
+    I: StreamNameFormat,
+    O: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        struct Envelope<O> {
+            stream: String,
+            data: O,
+        }
+        let envelope = Envelope::<O>::deserialize(deserializer)?;
+        let stream = I::from_stream_name(&envelope.stream)
+            .ok_or_else(|| D::Error::custom(format!("unknown stream name: {}", envelope.stream)))?;
+        Ok(CombinedStreamPayload {
+            stream,
+            data: envelope.data,
+        })
+    }
+}
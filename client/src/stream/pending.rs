@@ -0,0 +1,195 @@
+use crate::stream::payload::SocketOperationResp;
+use crate::stream::stream::StreamNameFormat;
+use general::error::SdkError;
+use general::result::BinanceResult;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::oneshot;
+use tokio::time::Instant;
+
+// the receiving side of a SUBSCRIBE or UNSUBSCRIBE request, it is completed with the answer of binance
+pub type SocketOperationResponder = oneshot::Sender<BinanceResult<()>>;
+
+// the subscription change made by a request, it is reverted if binance rejects the request
+enum SocketItemChange<I> {
+    Added(Vec<I>),
+    Removed(Vec<I>),
+}
+
+struct PendingRequest<I> {
+    change: SocketItemChange<I>,
+    responder: SocketOperationResponder,
+    expire_at: Instant,
+}
+
+struct PendingState<I> {
+    socket_item: HashSet<I>,
+    requests: HashMap<u64, PendingRequest<I>>,
+}
+
+// SocketPendingRequests holds the items subscribed on a socket and the requests sent to binance and not answered yet.
+// the sender of a socket registers every request, the receiver completes it when the answer with the same request id arrives.
+// requests nobody waits for anymore are dropped after the request timeout.
+pub struct SocketPendingRequests<I> {
+    request_timeout: Duration,
+    state: Arc<Mutex<PendingState<I>>>,
+}
+
+impl<I> Clone for SocketPendingRequests<I> {
+    fn clone(&self) -> Self {
+        SocketPendingRequests {
+            request_timeout: self.request_timeout,
+            state: self.state.clone(),
+        }
+    }
+}
+
+impl<I> SocketPendingRequests<I>
+where
+    I: StreamNameFormat + Clone + Hash + Eq,
+{
+    pub fn new(request_timeout: Duration, socket_item: HashSet<I>) -> Self {
+        SocketPendingRequests {
+            request_timeout,
+            state: Arc::new(Mutex::new(PendingState {
+                socket_item,
+                requests: HashMap::new(),
+            })),
+        }
+    }
+
+    // subscribe items with the request id, return the stream names to send
+    pub fn add(&self, id: u64, items: Vec<I>, responder: SocketOperationResponder) -> Vec<String> {
+        let stream_names = items.iter().map(|item| item.stream_name()).collect();
+        let mut state = self.state.lock().unwrap();
+        state.socket_item.extend(items.iter().cloned());
+        self.register(&mut state, id, SocketItemChange::Added(items), responder);
+        stream_names
+    }
+
+    // unsubscribe items with the request id, return the stream names to send
+    pub fn remove(&self, id: u64, items: Vec<I>, responder: SocketOperationResponder) -> Vec<String> {
+        let stream_names = items.iter().map(|item| item.stream_name()).collect();
+        let mut state = self.state.lock().unwrap();
+        items.iter().for_each(|item| {
+            state.socket_item.remove(item);
+        });
+        self.register(&mut state, id, SocketItemChange::Removed(items), responder);
+        stream_names
+    }
+
+    fn register(
+        &self,
+        state: &mut PendingState<I>,
+        id: u64,
+        change: SocketItemChange<I>,
+        responder: SocketOperationResponder,
+    ) {
+        let now = Instant::now();
+        state
+            .requests
+            .retain(|_, request| request.expire_at > now && !request.responder.is_closed());
+        state.requests.insert(
+            id,
+            PendingRequest {
+                change,
+                responder,
+                expire_at: now + self.request_timeout,
+            },
+        );
+    }
+
+    // complete the request answered by resp, return false if no request is waiting for this id
+    pub fn resolve(&self, resp: SocketOperationResp) -> bool {
+        let mut state = self.state.lock().unwrap();
+        let request = match state.requests.remove(&resp.id) {
+            Some(request) => request,
+            None => return false,
+        };
+        let result = match resp.error {
+            None => Ok(()),
+            Some(error) => {
+                match request.change {
+                    SocketItemChange::Added(items) => items.iter().for_each(|item| {
+                        state.socket_item.remove(item);
+                    }),
+                    SocketItemChange::Removed(items) => state.socket_item.extend(items),
+                }
+                Err(SdkError::WebsocketRequestError(error.code, error.msg))
+            }
+        };
+        let _ = request.responder.send(result);
+        true
+    }
+
+    // fail every waiting request, the connection they were sent on is lost.
+    // their subscription changes are kept, they are sent again with the next connection
+    pub fn fail_all(&self, error: impl Fn() -> tungstenite::Error) {
+        let requests = std::mem::take(&mut self.state.lock().unwrap().requests);
+        requests.into_values().for_each(|request| {
+            let _ = request.responder.send(Err(SdkError::WebsocketError(error())));
+        });
+    }
+
+    pub fn stream_names(&self) -> Vec<String> {
+        self.state
+            .lock()
+            .unwrap()
+            .socket_item
+            .iter()
+            .map(|item| item.stream_name())
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.state.lock().unwrap().requests.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stream::payload::SocketOperationError;
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    struct TestStream(&'static str);
+
+    impl StreamNameFormat for TestStream {
+        fn stream_name(&self) -> String {
+            self.0.to_string()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resolve() {
+        let pending = SocketPendingRequests::new(Duration::from_secs(10), HashSet::new());
+        let (accepted_sender, accepted_receiver) = oneshot::channel();
+        let (rejected_sender, rejected_receiver) = oneshot::channel();
+        let names = pending.add(1, vec![TestStream("btcusdt@trade")], accepted_sender);
+        assert_eq!(names, vec!["btcusdt@trade".to_string()]);
+        pending.add(2, vec![TestStream("unknown")], rejected_sender);
+        assert_eq!(pending.len(), 2);
+
+        assert!(pending.resolve(SocketOperationResp { result: None, error: None, id: 1 }));
+        assert!(!pending.resolve(SocketOperationResp { result: None, error: None, id: 1 }));
+        let error = SocketOperationError {
+            code: 2,
+            msg: "Invalid request".to_string(),
+        };
+        assert!(pending.resolve(SocketOperationResp { result: None, error: Some(error), id: 2 }));
+
+        assert!(accepted_receiver.await.unwrap().is_ok());
+        assert!(matches!(
+            rejected_receiver.await.unwrap(),
+            Err(SdkError::WebsocketRequestError(2, _))
+        ));
+        assert!(pending.is_empty());
+        assert_eq!(pending.stream_names(), vec!["btcusdt@trade".to_string()]);
+    }
+}
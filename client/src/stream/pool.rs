@@ -0,0 +1,159 @@
+use crate::stream::client::WebsocketClient;
+use crate::stream::config::SocketConfig;
+use crate::stream::buffer::{PayloadReceiver, PayloadSender, payload_buffer};
+use crate::stream::stream::StreamNameFormat;
+use general::result::BinanceResult;
+use serde::de::DeserializeOwned;
+use std::collections::HashSet;
+use std::fmt::Debug;
+use std::hash::Hash;
+
+// WebsocketPool spreads the subscribed streams over as many connections as the stream limit of binance requires(see SocketLimitConfig),
+// a new connection is opened when all the connections are full, and a connection without stream is closed.
+// the payloads of all the connections share a single payload buffer.
+pub struct WebsocketPool<I, O> {
+    uri: String,
+    socket_config: SocketConfig,
+    websocket_clients: Vec<WebsocketClient<I>>,
+    payload_sender: PayloadSender<O>,
+}
+
+impl<I, O> WebsocketPool<I, O>
+where
+    I: StreamNameFormat + Clone + Hash + Eq + Send + 'static,
+    O: DeserializeOwned + Send + 'static + Debug,
+{
+    pub async fn new_with_uri(
+        uri: &str,
+    ) -> BinanceResult<(
+        WebsocketPool<I, O>,
+        PayloadReceiver<O>,
+    )> {
+        Self::new_with_config(uri, SocketConfig::new_default()).await
+    }
+
+    pub async fn new_with_config(
+        uri: &str,
+        socket_config: SocketConfig,
+    ) -> BinanceResult<(
+        WebsocketPool<I, O>,
+        PayloadReceiver<O>,
+    )> {
+        let (payload_sender, payload_receiver) = payload_buffer(socket_config.buffer_config());
+        let mut pool = WebsocketPool {
+            uri: uri.to_string(),
+            socket_config,
+            websocket_clients: Vec::new(),
+            payload_sender,
+        };
+        pool.open_client().await?;
+        Ok((pool, payload_receiver))
+    }
+
+    async fn open_client(&mut self) -> BinanceResult<()> {
+        let client = WebsocketClient::<I>::new_with_sender::<O>(
+            &self.uri,
+            self.socket_config.clone(),
+            self.payload_sender.clone(),
+        )
+        .await?;
+        self.websocket_clients.push(client);
+        Ok(())
+    }
+
+    pub async fn subscribe_single(&mut self, param: I) -> BinanceResult<()> {
+        self.subscribe_multiple(vec![param]).await
+    }
+
+    // fill the connections which are not full yet, then open new connections for the remaining streams.
+    // every connection is tried, the first error is returned.
+    pub async fn subscribe_multiple(&mut self, params: Vec<I>) -> BinanceResult<()> {
+        let subscribed = self.get_all_subscribers();
+        let mut seen = HashSet::new();
+        let mut new_items = params
+            .into_iter()
+            .filter(|item| !subscribed.contains(item) && seen.insert(item.clone()))
+            .collect::<Vec<_>>();
+        let max_streams = self.socket_config.limit_config().max_streams().max(1);
+        let mut result = Ok(());
+        let mut index = 0;
+        while !new_items.is_empty() {
+            if index == self.websocket_clients.len() {
+                self.open_client().await?;
+            }
+            let client = &mut self.websocket_clients[index];
+            index += 1;
+            let capacity = max_streams.saturating_sub(client.get_all_subscribers().len());
+            if capacity == 0 {
+                continue;
+            }
+            let rest = new_items.split_off(capacity.min(new_items.len()));
+            let items = std::mem::replace(&mut new_items, rest);
+            let subscribe_result = client.subscribe_multiple(items).await;
+            if result.is_ok() {
+                result = subscribe_result;
+            }
+        }
+        result
+    }
+
+    pub async fn unsubscribe_single(&mut self, param: I) -> BinanceResult<()> {
+        self.unsubscribe_multiple(vec![param]).await
+    }
+
+    // unsubscribe every stream on the connection holding it, the connections left without stream are closed.
+    pub async fn unsubscribe_multiple(&mut self, params: Vec<I>) -> BinanceResult<()> {
+        let mut result = Ok(());
+        for client in self.websocket_clients.iter_mut() {
+            let items = params
+                .iter()
+                .filter(|item| client.get_all_subscribers().contains(item))
+                .cloned()
+                .collect::<Vec<_>>();
+            if items.is_empty() {
+                continue;
+            }
+            let unsubscribe_result = client.unsubscribe_multiple(items).await;
+            if result.is_ok() {
+                result = unsubscribe_result;
+            }
+        }
+        self.close_idle_clients().await;
+        result
+    }
+
+    // the first connection stays open, so that the pool keeps the same behavior as a single WebsocketClient
+    async fn close_idle_clients(&mut self) {
+        let mut clients = std::mem::take(&mut self.websocket_clients).into_iter();
+        self.websocket_clients.extend(clients.next());
+        for client in clients {
+            if client.get_all_subscribers().is_empty() {
+                client.close().await;
+            } else {
+                self.websocket_clients.push(client);
+            }
+        }
+    }
+
+    pub fn get_all_subscribers(&self) -> HashSet<I> {
+        self.websocket_clients
+            .iter()
+            .flat_map(|client| client.get_all_subscribers().iter().cloned())
+            .collect()
+    }
+
+    // the number of payloads dropped by the backpressure policy of the payload buffer
+    pub fn get_dropped_count(&self) -> u64 {
+        self.payload_sender.get_drop_counter().get_dropped_count()
+    }
+
+    pub fn get_socket_count(&self) -> usize {
+        self.websocket_clients.len()
+    }
+
+    pub async fn close(self) {
+        for client in self.websocket_clients {
+            client.close().await;
+        }
+    }
+}
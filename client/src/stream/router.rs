@@ -0,0 +1,142 @@
+use crate::stream::buffer::{PayloadBufferConfig, PayloadDropCounter, PayloadSender, payload_buffer};
+use crate::stream::payload::{CombinedStreamPayload, SocketPayloadActor};
+use crate::stream::stream::SocketPayloadProcess;
+use async_trait::async_trait;
+use futures_util::{Stream, StreamExt};
+use general::result::BinanceResult;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::pin::Pin;
+
+// CombinedStreamRouter is the consumer of a combined stream connection, it dispatches every payload
+// to the processor registered for its stream, reconnect, close and lifecycle signals are sent to every processor.
+// payloads of a stream without processor, and payloads that cannot be parsed, are only logged.
+// every processor has its own payload buffer, a full buffer follows the backpressure policy of buffer_config,
+// with BackpressurePolicy::Block a slow processor holds back the payloads of every stream of the connection.
+pub struct CombinedStreamRouter<I, O> {
+    buffer_config: PayloadBufferConfig,
+    routes: HashMap<I, PayloadSender<O>>,
+}
+
+impl<I, O> CombinedStreamRouter<I, O>
+where
+    I: Hash + Eq,
+    O: Send + 'static,
+{
+    pub fn new() -> Self {
+        CombinedStreamRouter::new_with_buffer(PayloadBufferConfig::new_default())
+    }
+
+    pub fn new_with_buffer(buffer_config: PayloadBufferConfig) -> Self {
+        CombinedStreamRouter {
+            buffer_config,
+            routes: HashMap::new(),
+        }
+    }
+
+    // register the processor of stream, the processor runs on its own task until the connection is closed.
+    // registering the same stream again replaces the previous processor.
+    pub fn route<P>(&mut self, stream: I, mut processor: P)
+    where
+        P: SocketPayloadProcess<O> + Send + 'static,
+    {
+        let (route_sender, route_receiver) = payload_buffer(self.buffer_config);
+        tokio::spawn(async move { processor.process(Box::pin(route_receiver)).await });
+        self.routes.insert(stream, route_sender);
+    }
+
+    pub fn get_routes(&self) -> impl Iterator<Item = &I> {
+        self.routes.keys()
+    }
+
+    // the number of payloads of stream dropped by the backpressure policy, None when stream has no processor
+    pub fn get_drop_counter(&self, stream: &I) -> Option<PayloadDropCounter> {
+        self.routes.get(stream).map(|route_sender| route_sender.get_drop_counter())
+    }
+
+    async fn broadcast(&self, signal: impl Fn() -> SocketPayloadActor<O>) {
+        for route_sender in self.routes.values() {
+            let _ = route_sender.send(Ok(signal()), None).await;
+        }
+    }
+}
+
+impl<I, O> Default for CombinedStreamRouter<I, O>
+where
+    I: Hash + Eq,
+    O: Send + 'static,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl<I, O> SocketPayloadProcess<CombinedStreamPayload<I, O>> for CombinedStreamRouter<I, O>
+where
+    I: Hash + Eq + Debug + Send + Sync + 'static,
+    O: Send + Debug + 'static,
+{
+    async fn process(
+        &mut self,
+        mut stream: Pin<
+            Box<dyn Stream<Item = BinanceResult<SocketPayloadActor<CombinedStreamPayload<I, O>>>> + Send>,
+        >,
+    ) {
+        while let Some(data) = stream.next().await {
+            match data {
+                Ok(SocketPayloadActor::Payload(CombinedStreamPayload { stream, data })) => {
+                    match self.routes.get(&stream) {
+                        Some(route_sender) => {
+                            // every payload of a route belongs to the same stream, LatestPerKey keeps only the latest one
+                            let key = route_sender.needs_key().then(String::new);
+                            if route_sender.send(Ok(SocketPayloadActor::Payload(data)), key).await.is_err() {
+                                log::warn!("Stream processor is gone, drop payload of stream: {:?}", stream);
+                            }
+                        }
+                        None => log::warn!("No processor for stream: {:?}, payload: {:?}", stream, data),
+                    }
+                }
+                Ok(SocketPayloadActor::Close(code)) => self.broadcast(|| SocketPayloadActor::Close(code)).await,
+                Ok(SocketPayloadActor::Lifecycle(event)) => {
+                    self.broadcast(|| SocketPayloadActor::Lifecycle(event.clone())).await
+                }
+                Err(e) => {
+                    log::error!("Accept combined socket payload error: error message is: {}", e);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stream::buffer::BackpressurePolicy;
+
+    // a processor which never takes a payload
+    struct StalledProcess;
+
+    #[async_trait]
+    impl SocketPayloadProcess<u32> for StalledProcess {
+        async fn process(&mut self, _stream: Pin<Box<dyn Stream<Item = BinanceResult<SocketPayloadActor<u32>>> + Send>>) {
+            std::future::pending::<()>().await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_route_backpressure() {
+        let mut router = CombinedStreamRouter::new_with_buffer(PayloadBufferConfig::new(1, BackpressurePolicy::DropNewest));
+        router.route("btcusdt@trade".to_string(), StalledProcess);
+        let payloads = (1..=3).map(|data| {
+            SocketPayloadActor::Payload(CombinedStreamPayload {
+                stream: "btcusdt@trade".to_string(),
+                data,
+            })
+        });
+        router.process(Box::pin(futures_util::stream::iter(payloads).map(Ok))).await;
+        let dropped = router.get_drop_counter(&"btcusdt@trade".to_string()).unwrap();
+        assert_eq!(dropped.get_dropped_count(), 2);
+    }
+}
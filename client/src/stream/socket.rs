@@ -1,17 +1,26 @@
 use crate::stream::actor::SocketItemChangeActor;
+use crate::stream::buffer::PayloadSender;
+use crate::stream::config::{HeartbeatConfig, SocketConfig};
+use crate::stream::dedup::{HandoverConnection, SocketPayloadDeduplicator, stream_key};
+use crate::stream::lifecycle::{SocketLifecycle, SocketLifecycleReporter};
 use crate::stream::payload::{SocketOperationResp, SocketPayloadActor};
+use crate::stream::pending::SocketPendingRequests;
 use crate::stream::stream::StreamNameFormat;
+use crate::stream::watchdog::{SocketStale, SocketWatchdog, new_ping_interval, wait_deadline, wait_ping};
 use futures_util::stream::{SplitSink, SplitStream};
 use futures_util::{SinkExt, StreamExt};
 use general::error::map_deserialization_error;
 use general::result::BinanceResult;
 use lazy_static::lazy_static;
 use serde::de::DeserializeOwned;
+use std::collections::HashSet;
 use std::fmt::Debug;
 use std::hash::Hash;
 use std::sync::atomic::{AtomicU64, Ordering};
 use tokio::net::TcpStream;
-use tokio::sync::mpsc::{Receiver, Sender, UnboundedSender};
+use tokio::sync::mpsc::{Receiver, Sender};
+use std::time::Duration;
+use tokio::time::Instant;
 use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
 use tungstenite::Message;
 
@@ -19,6 +28,13 @@ lazy_static! {
     static ref GLOBAL_ATOMIC_ID: AtomicU64 = AtomicU64::new(1);
 }
 
+// every connection and every request sent to binance gets its own id
+fn next_id() -> u64 {
+    GLOBAL_ATOMIC_ID.fetch_add(1, Ordering::SeqCst)
+}
+
+const DEFAULT_SOCKET_URI: &str = "wss://stream.binance.com:9443/ws";
+
 enum SocketMethod {
     Subscribe,
     Unsubscribe,
@@ -37,7 +53,7 @@ pub struct SocketReceiverState<O>
 where
     O: DeserializeOwned + Send + 'static + Debug
 {
-    payload_sender: UnboundedSender<BinanceResult<SocketPayloadActor<O>>>,
+    payload_sender: PayloadSender<O>,
     socket_health_sender: Sender<Message>,
 }
 impl<O> SocketReceiverState<O>
@@ -45,7 +61,7 @@ where
     O: DeserializeOwned + Send + 'static + Debug,
 {
     pub fn new(
-        payload_sender: UnboundedSender<BinanceResult<SocketPayloadActor<O>>>,
+        payload_sender: PayloadSender<O>,
         socket_health_sender: Sender<Message>,
     ) -> Self {
         SocketReceiverState {
@@ -56,8 +72,11 @@ where
 }
 pub struct SocketSenderState<I> {
     id: u64,
+    uri: String,
+    socket_config: SocketConfig,
+    socket_item: HashSet<I>,
     socket_item_change_receiver: Receiver<SocketItemChangeActor<I>>,
-    websocket_stream: WebSocketStream<MaybeTlsStream<TcpStream>>,
+    websocket_stream: SocketStream,
     socket_health_receiver: Receiver<Message>,
 }
 
@@ -65,45 +84,98 @@ impl<I> SocketSenderState<I> {
     pub async fn new(
         socket_item_receiver: Receiver<SocketItemChangeActor<I>>,
         socket_health_receiver: Receiver<Message>,
-    ) -> Self {
-        let stream = Self::new_async_connect_default().await;
-        SocketSenderState {
-            id: GLOBAL_ATOMIC_ID.fetch_add(1, Ordering::SeqCst),
-            socket_item_change_receiver: socket_item_receiver,
-            websocket_stream: stream,
+    ) -> BinanceResult<Self> {
+        Self::new_with_uri(
+            DEFAULT_SOCKET_URI,
+            SocketConfig::new_default(),
+            socket_item_receiver,
             socket_health_receiver,
-        }
+        )
+        .await
     }
 
     pub async fn new_with_uri(
         uri: &str,
+        socket_config: SocketConfig,
         socket_item_receiver: Receiver<SocketItemChangeActor<I>>,
         socket_health_receiver: Receiver<Message>,
-    ) -> Self {
-        let stream = Self::new_async_connect(uri).await;
-        SocketSenderState {
-            id: GLOBAL_ATOMIC_ID.fetch_add(1, Ordering::SeqCst),
+    ) -> BinanceResult<Self> {
+        let stream = Self::new_async_connect(uri).await?;
+        Ok(SocketSenderState {
+            id: next_id(),
+            uri: uri.to_string(),
+            socket_config,
+            socket_item: HashSet::new(),
             socket_item_change_receiver: socket_item_receiver,
             websocket_stream: stream,
             socket_health_receiver,
-        }
+        })
     }
 
-    pub async fn new_async_connect_default() -> WebSocketStream<MaybeTlsStream<TcpStream>> {
-        Self::new_async_connect("wss://stream.binance.com:9443/ws").await
+    pub async fn new_async_connect_default() -> BinanceResult<WebSocketStream<MaybeTlsStream<TcpStream>>> {
+        Self::new_async_connect(DEFAULT_SOCKET_URI).await
     }
 
-    pub async fn new_async_connect(uri: &str) -> WebSocketStream<MaybeTlsStream<TcpStream>> {
+    pub async fn new_async_connect(
+        uri: &str,
+    ) -> BinanceResult<WebSocketStream<MaybeTlsStream<TcpStream>>> {
         log::info!("Connecting to {}...", uri);
-        let (socket, response) = connect_async(uri).await.expect("Failed to connect");
+        let (socket, response) = connect_async(uri).await?;
         log::debug!("WebSocket response status: {}", response.status());
         for (ref header, value) in response.headers() {
             log::debug!("WebSocket response header: {}={:#?}", header, value);
         }
-        socket
+        Ok(socket)
     }
 }
 
+type SocketStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+type SocketWriter = SplitSink<SocketStream, Message>;
+type SocketReader = SplitStream<SocketStream>;
+
+// keeps the messages sent on a connection under the message limit of binance
+struct SocketMessageThrottle {
+    interval: Duration,
+    next_at: Instant,
+}
+
+impl SocketMessageThrottle {
+    fn new(interval: Duration) -> Self {
+        SocketMessageThrottle {
+            interval,
+            next_at: Instant::now(),
+        }
+    }
+
+    // wait until the next message can be sent
+    async fn wait(&mut self) {
+        tokio::time::sleep_until(self.next_at).await;
+        self.next_at = Instant::now().max(self.next_at) + self.interval;
+    }
+}
+
+// the reason why a single connection stopped working
+enum SocketExit {
+    // closed on purpose by the client, the socket must not be reopened
+    Close,
+    // lost by a read error or a close frame from binance, the socket should be reopened, with the reason
+    Disconnect(String),
+    // the connection reached its rotation deadline, it should be replaced by a new one
+    Rotate,
+    // nothing was received for too long, the connection is probably half-open, the socket should be reopened
+    Stale(SocketStale),
+}
+
+// the result of handing a connection over to its replacement
+enum RotationResult {
+    // the new connection took over, the old one is closed
+    Replaced(SocketStream),
+    // the new connection failed, the old one is still in use
+    Kept(SocketStream),
+    // both connections failed
+    Lost,
+}
+
 fn build_socket_message(
     id: u64,
     method: &str,
@@ -133,6 +205,10 @@ fn build_socket_message(
 // payload_sender： when accept socket data, it will send those data to consumer, and every client have a consumer handle (P: SocketPayloadProcess), and there is
 //                                              a default processor(DefaultStreamPayloadProcess: it will print all message on console);
 // socket_health_sender: socket heartbeat sender
+// whenever the connection is lost, it will be reopened with exponential backoff (see ReconnectConfig), and every subscribed item will be subscribed again.
+// before a connection reaches the 24 hours limit of Binance, it will be replaced by a new connection (see RotationConfig).
+// a connection receiving nothing for too long is treated as lost, and pings are sent to keep it busy (see HeartbeatConfig).
+// every connection, disconnection, rotation and subscription answer is reported to the consumer with SocketPayloadActor::Lifecycle.
 pub async fn stream<I, O>(
     socket_sender_state: SocketSenderState<I>,
     socket_receiver_state: SocketReceiverState<O>,
@@ -140,136 +216,472 @@ pub async fn stream<I, O>(
     I: StreamNameFormat + Clone + Hash + Eq + Send,
     O: DeserializeOwned + 'static + Send + Debug,
 {
-    let (writer_socket, reader_socket) = socket_sender_state.websocket_stream.split();
-    let socket_item_receiver = socket_sender_state.socket_item_change_receiver;
-    let socket_health_receiver = socket_sender_state.socket_health_receiver;
-    tokio::select! {
-        _ = run_socket_receive(reader_socket, socket_receiver_state.payload_sender, socket_receiver_state.socket_health_sender) => {}
-        _ = run_socket_sender(writer_socket, socket_item_receiver, socket_sender_state.id, socket_health_receiver) => {}
+    let SocketSenderState {
+        id,
+        uri,
+        socket_config,
+        socket_item,
+        mut socket_item_change_receiver,
+        mut websocket_stream,
+        mut socket_health_receiver,
+    } = socket_sender_state;
+    let rotation_config = socket_config.rotation_config();
+    let mut rotate_at = rotation_config.interval().map(|interval| Instant::now() + interval);
+    let mut rotation_attempt = 0;
+    let mut deduplicator: Option<SocketPayloadDeduplicator> = None;
+    let pending = SocketPendingRequests::new(socket_config.request_timeout(), socket_item);
+    let mut throttle = SocketMessageThrottle::new(socket_config.limit_config().message_interval());
+    let payload_sender = &socket_receiver_state.payload_sender;
+    let heartbeat_config = socket_config.heartbeat_config();
+    let mut lifecycle = SocketLifecycleReporter::new(id);
+    lifecycle.set_connected();
+    lifecycle.report(payload_sender, SocketLifecycle::Connected, format!("connected to {}", uri)).await;
+    loop {
+        let (mut writer_socket, mut reader_socket) = websocket_stream.split();
+        let exit = tokio::select! {
+            exit = run_socket_receive(&mut reader_socket, &mut deduplicator, &pending, payload_sender, &socket_receiver_state.socket_health_sender, &lifecycle, &heartbeat_config, rotate_at) => exit,
+            exit = run_socket_sender(&mut writer_socket, &mut socket_item_change_receiver, &pending, id, &mut socket_health_receiver, &mut throttle, &heartbeat_config) => exit,
+        };
+        let stream_names = pending.stream_names();
+        let next_stream = match exit {
+            SocketExit::Close => {
+                pending.fail_all(|| tungstenite::Error::AlreadyClosed);
+                lifecycle.report(payload_sender, SocketLifecycle::Closed, "closed by the client").await;
+                break;
+            }
+            SocketExit::Disconnect(reason) => {
+                lifecycle.report(payload_sender, SocketLifecycle::Disconnected, reason).await;
+                lifecycle.set_disconnected();
+                None
+            }
+            SocketExit::Stale(stale) => {
+                log::warn!("Socket {} is stale, it will reconnect: {}", id, stale.reason);
+                lifecycle.report(payload_sender, SocketLifecycle::Stale { stream: stale.stream }, stale.reason).await;
+                lifecycle.set_disconnected();
+                None
+            }
+            SocketExit::Rotate => {
+                let mut handover = SocketPayloadDeduplicator::new(Instant::now() + rotation_config.overlap());
+                let rotation = rotate(
+                    writer_socket,
+                    reader_socket,
+                    &uri,
+                    &socket_config,
+                    &stream_names,
+                    id,
+                    &mut handover,
+                    &pending,
+                    payload_sender,
+                    &lifecycle,
+                )
+                .await;
+                match rotation {
+                    RotationResult::Replaced(stream) => {
+                        // the new connection may still deliver events the old one already delivered
+                        handover.set_active_until(Instant::now() + rotation_config.overlap());
+                        deduplicator = Some(handover);
+                        rotation_attempt = 0;
+                        rotate_at = rotation_config.interval().map(|interval| Instant::now() + interval);
+                        lifecycle.set_connected();
+                        lifecycle.report(payload_sender, SocketLifecycle::Rotated, "rotated to a new connection").await;
+                        Some(stream)
+                    }
+                    RotationResult::Kept(stream) => {
+                        rotation_attempt += 1;
+                        let backoff = socket_config.reconnect_config().backoff(rotation_attempt);
+                        rotate_at = Some(Instant::now() + backoff);
+                        let reason = format!("replacement connection failed, retry rotation after {:?}", backoff);
+                        lifecycle.report(payload_sender, SocketLifecycle::RotationFailed, reason).await;
+                        Some(stream)
+                    }
+                    RotationResult::Lost => {
+                        let reason = "both connections failed during rotation";
+                        lifecycle.report(payload_sender, SocketLifecycle::Disconnected, reason).await;
+                        lifecycle.set_disconnected();
+                        None
+                    }
+                }
+            }
+        };
+        if let Some(stream) = next_stream {
+            websocket_stream = stream;
+            continue;
+        }
+        // the requests are sent again with the subscription of the new connection
+        pending.fail_all(|| tungstenite::Error::ConnectionClosed);
+        match reconnect(&uri, &socket_config, stream_names, id, payload_sender, &mut lifecycle).await {
+            Some(stream) => {
+                rotation_attempt = 0;
+                rotate_at = rotation_config.interval().map(|interval| Instant::now() + interval);
+                websocket_stream = stream;
+            }
+            None => {
+                log::error!("Socket reconnect attempts exhausted, the socket will close: {}", id);
+                lifecycle.report(payload_sender, SocketLifecycle::Closed, "reconnect attempts exhausted").await;
+                let _ = payload_sender.send(Ok(SocketPayloadActor::Close(1)), None).await;
+                break;
+            }
+        }
+    }
+}
+
+async fn wait_rotation(rotate_at: Option<Instant>) {
+    match rotate_at {
+        Some(rotate_at) => tokio::time::sleep_until(rotate_at).await,
+        None => std::future::pending::<()>().await,
+    }
+}
+
+// the combined stream endpoint, every payload is wrapped with the name of its stream
+fn is_combined_uri(uri: &str) -> bool {
+    uri.trim_end_matches('/').ends_with("/stream")
+}
+
+// open a new connection to uri and subscribe stream_names on it,
+// the combined stream endpoint takes the streams from the query string(/stream?streams=a/b/c).
+async fn connect_and_subscribe(
+    uri: &str,
+    stream_names: &[String],
+    id: u64,
+) -> BinanceResult<SocketStream> {
+    if is_combined_uri(uri) && !stream_names.is_empty() {
+        let combined_uri = format!(
+            "{}?streams={}",
+            uri.trim_end_matches('/'),
+            stream_names.join("/")
+        );
+        return SocketSenderState::<()>::new_async_connect(&combined_uri).await;
+    }
+    let mut stream = SocketSenderState::<()>::new_async_connect(uri).await?;
+    if !stream_names.is_empty() {
+        log::debug!("Socket {} subscribe streams of the new connection", id);
+        let message = build_socket_message(
+            next_id(),
+            SocketMethod::Subscribe.as_str(),
+            stream_names.iter().cloned(),
+        );
+        stream.send(message).await?;
+    }
+    Ok(stream)
+}
+
+// open a new connection to uri, waiting a growing backoff delay before every attempt,
+// when connected, all the stream_names subscribed on the lost connection will be subscribed again.
+// every attempt is reported to the consumer with SocketLifecycle::Reconnecting.
+async fn reconnect<O>(
+    uri: &str,
+    socket_config: &SocketConfig,
+    stream_names: Vec<String>,
+    id: u64,
+    payload_sender: &PayloadSender<O>,
+    lifecycle: &mut SocketLifecycleReporter,
+) -> Option<SocketStream>
+where
+    O: DeserializeOwned + 'static + Send + Debug,
+{
+    let reconnect_config = socket_config.reconnect_config();
+    let mut attempt = 1;
+    while !reconnect_config.is_exhausted(attempt) {
+        let backoff = reconnect_config.backoff(attempt);
+        log::warn!("Socket {} disconnected, reconnect attempt {} after {:?}", id, attempt, backoff);
+        if payload_sender.is_closed() {
+            log::info!("Socket payload consumer is gone, stop reconnecting: {}", id);
+            return None;
+        }
+        let reason = format!("reconnect attempt {} after {:?}", attempt, backoff);
+        lifecycle.report(payload_sender, SocketLifecycle::Reconnecting { attempt, backoff }, reason).await;
+        tokio::time::sleep(backoff).await;
+        match connect_and_subscribe(uri, &stream_names, id).await {
+            Ok(stream) => {
+                log::info!("Socket {} reconnected after {} attempts", id, attempt);
+                lifecycle.set_connected();
+                let reason = format!("reconnected after {} attempts", attempt);
+                lifecycle.report(payload_sender, SocketLifecycle::Connected, reason).await;
+                return Some(stream);
+            }
+            Err(err) => {
+                log::error!("Socket {} reconnect attempt {} failed: {}", id, attempt, err);
+                lifecycle.report(payload_sender, SocketLifecycle::ReconnectFailed { attempt }, err.to_string()).await;
+                attempt += 1;
+            }
+        }
+    }
+    None
+}
+
+// replace the connection made of writer_socket and reader_socket by a new one:
+// 1. open a new connection and subscribe stream_names on it,
+// 2. during the overlap of RotationConfig, deliver payloads from both connections, the deduplicator drops the second copy of every event,
+// 3. close the old connection.
+// subscription changes wait in the item channel until the rotation is done.
+#[allow(clippy::too_many_arguments)]
+async fn rotate<I, O>(
+    mut writer_socket: SocketWriter,
+    mut reader_socket: SocketReader,
+    uri: &str,
+    socket_config: &SocketConfig,
+    stream_names: &[String],
+    id: u64,
+    deduplicator: &mut SocketPayloadDeduplicator,
+    pending: &SocketPendingRequests<I>,
+    payload_sender: &PayloadSender<O>,
+    lifecycle: &SocketLifecycleReporter,
+) -> RotationResult
+where
+    I: StreamNameFormat + Clone + Hash + Eq,
+    O: DeserializeOwned + 'static + Send + Debug,
+{
+    log::info!("Socket {} reached its rotation deadline, open a replacement connection", id);
+    let new_stream = match connect_and_subscribe(uri, stream_names, id).await {
+        Ok(stream) => stream,
+        Err(err) => {
+            log::error!("Socket {} failed to open replacement connection: {}", id, err);
+            return match reader_socket.reunite(writer_socket) {
+                Ok(stream) => RotationResult::Kept(stream),
+                Err(_) => RotationResult::Lost,
+            };
+        }
+    };
+    let (mut new_writer_socket, mut new_reader_socket) = new_stream.split();
+    let overlap = tokio::time::sleep(socket_config.rotation_config().overlap());
+    tokio::pin!(overlap);
+    let mut old_alive = true;
+    loop {
+        tokio::select! {
+            _ = &mut overlap => break,
+            message = reader_socket.next(), if old_alive => {
+                old_alive = handover_message(message, HandoverConnection::Old, &mut writer_socket, deduplicator, pending, payload_sender, lifecycle).await;
+                if !old_alive {
+                    deliver_handed_over(deduplicator, pending, payload_sender, lifecycle).await;
+                }
+            }
+            message = new_reader_socket.next() => {
+                if !handover_message(message, HandoverConnection::New, &mut new_writer_socket, deduplicator, pending, payload_sender, lifecycle).await {
+                    log::error!("Socket {} replacement connection failed during rotation", id);
+                    return match reader_socket.reunite(writer_socket) {
+                        Ok(stream) if old_alive => RotationResult::Kept(stream),
+                        _ => RotationResult::Lost,
+                    };
+                }
+            }
+        }
+    }
+    if old_alive {
+        let _ = writer_socket.send(Message::Close(None)).await;
+        deliver_handed_over(deduplicator, pending, payload_sender, lifecycle).await;
+    }
+    log::info!("Socket {} rotated to a new connection", id);
+    match new_reader_socket.reunite(new_writer_socket) {
+        Ok(stream) => RotationResult::Replaced(stream),
+        Err(_) => RotationResult::Lost,
+    }
+}
+
+// handle a message received while two connections are alive, pings are answered directly on the same connection.
+// return false when the connection is closed or broken.
+#[allow(clippy::too_many_arguments)]
+async fn handover_message<I, O>(
+    message: Option<Result<Message, tungstenite::Error>>,
+    connection: HandoverConnection,
+    writer_socket: &mut SocketWriter,
+    deduplicator: &mut SocketPayloadDeduplicator,
+    pending: &SocketPendingRequests<I>,
+    payload_sender: &PayloadSender<O>,
+    lifecycle: &SocketLifecycleReporter,
+) -> bool
+where
+    I: StreamNameFormat + Clone + Hash + Eq,
+    O: DeserializeOwned + 'static + Send + Debug,
+{
+    match message {
+        Some(Ok(Message::Text(data))) => {
+            for text in deduplicator.accept(data.as_str(), connection) {
+                deliver_text_message(text.as_str(), pending, payload_sender, lifecycle).await;
+            }
+            true
+        }
+        Some(Ok(Message::Ping(data))) => writer_socket.send(Message::Pong(data)).await.is_ok(),
+        Some(Ok(Message::Close(_))) | Some(Err(_)) | None => false,
+        Some(Ok(_)) => true,
+    }
+}
+
+// the old connection is gone, deliver the events the new connection delivered ahead of it
+async fn deliver_handed_over<I, O>(
+    deduplicator: &mut SocketPayloadDeduplicator,
+    pending: &SocketPendingRequests<I>,
+    payload_sender: &PayloadSender<O>,
+    lifecycle: &SocketLifecycleReporter,
+) where
+    I: StreamNameFormat + Clone + Hash + Eq,
+    O: DeserializeOwned + 'static + Send + Debug,
+{
+    for text in deduplicator.hand_over() {
+        deliver_text_message(text.as_str(), pending, payload_sender, lifecycle).await;
     }
 }
 
 // writer_socket: The actual socket connection for sending messages to Binance.
 // socket_receiver: Accept subscription or unsubscription message , when accept a message, wrapper the message and send to binance by writer_socket,
+// pending: all the items subscribed on this socket and the requests waiting for the answer of binance, every request gets its own id,
 // id: socket id
 // socket_health_receiver: Accept pong message, and send to Binance by writer_socket.
+// throttle: every message except close waits until it can be sent without exceeding the message limit of binance.
+// heartbeat_config: a ping is sent to binance every ping interval.
 async fn run_socket_sender<I>(
-    mut writer_socket: SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>,
-    mut socket_receiver: Receiver<SocketItemChangeActor<I>>,
+    writer_socket: &mut SocketWriter,
+    socket_receiver: &mut Receiver<SocketItemChangeActor<I>>,
+    pending: &SocketPendingRequests<I>,
     id: u64,
-    mut socket_health_receiver: Receiver<Message>,
-) where
+    socket_health_receiver: &mut Receiver<Message>,
+    throttle: &mut SocketMessageThrottle,
+    heartbeat_config: &HeartbeatConfig,
+) -> SocketExit
+where
     I: StreamNameFormat + Clone + Hash + Eq + Send,
 {
+    let mut ping_interval = new_ping_interval(heartbeat_config);
     loop {
         let message = tokio::select! {
-            operator_message = async {
-                let message = if let Some(message) = socket_receiver.recv().await {
-                    let msg = match message {
-                        SocketItemChangeActor::Add(data) => {
-                            let params = data.iter()
-                                    .map(|req_item| req_item.stream_name())
-                                    .collect::<Vec<_>>();
-                            log::debug!("Accept add sub message, the message data is: {:?}", params);
-                            Some(build_socket_message(id, SocketMethod::Subscribe.as_str(), params))
-                        }
-                        SocketItemChangeActor::Remove(data) => {
-                            let params = data.iter()
-                                            .map(|req_item| req_item.stream_name())
-                                            .collect::<Vec<_>>();
-                            log::debug!("Accept remove sub message, the message data is: {:?}", params);
-                            Some(build_socket_message(id, SocketMethod::Unsubscribe.as_str(), params))
-                        }
-                        SocketItemChangeActor::Close => {
-                            log::info!("Accept socket close signal, the socket will close: {}", id);
-                            Some(Message::Close(None))
-                        }
-                    };
-                    msg
-                } else {
-                    None
+            operator_message = socket_receiver.recv() => {
+                let msg = match operator_message {
+                    Some(SocketItemChangeActor::Add(data, responder)) => {
+                        let request_id = next_id();
+                        let params = pending.add(request_id, data, responder);
+                        log::debug!("Accept add sub message, the message data is: {:?}", params);
+                        build_socket_message(request_id, SocketMethod::Subscribe.as_str(), params)
+                    }
+                    Some(SocketItemChangeActor::Remove(data, responder)) => {
+                        let request_id = next_id();
+                        let params = pending.remove(request_id, data, responder);
+                        log::debug!("Accept remove sub message, the message data is: {:?}", params);
+                        build_socket_message(request_id, SocketMethod::Unsubscribe.as_str(), params)
+                    }
+                    Some(SocketItemChangeActor::Close) => {
+                        log::info!("Accept socket close signal, the socket will close: {}", id);
+                        Message::Close(None)
+                    }
+                    None => {
+                        log::info!("Socket client handle dropped, the socket will close: {}", id);
+                        Message::Close(None)
+                    }
                 };
-                log::info!("send socket message: {:?}", message);
-                message
-            } => operator_message,
-            pong_message = async {
-                let msg = socket_health_receiver.recv().await.unwrap();
+                log::info!("send socket message: {:?}", msg);
+                msg
+            },
+            pong_message = socket_health_receiver.recv() => {
                 log::debug!("Accept ping message, will send pong to binance.");
-                Some(msg)
-            } => pong_message,
-        };
-        match message {
-            None => {}
-            Some(item) => {
-                let break_signal = match &item {
-                    Message::Close(_) => Some(1),
-                    _ => None,
-                };
-                log::debug!("Send message: {}", item);
-                writer_socket
-                    .send(item)
-                    .await
-                    .expect("Failed to send message");
-                if let Some(_) = break_signal {
-                    log::info!("Socket close signal, the socket will close: {}", id);
-                    break;
+                match pong_message {
+                    Some(pong_message) => pong_message,
+                    None => continue,
                 }
-            }
+            },
+            _ = wait_ping(&mut ping_interval) => {
+                log::debug!("Send ping message to binance: {}", id);
+                Message::Ping(Vec::new())
+            },
+        };
+        let break_signal = matches!(&message, Message::Close(_));
+        if !break_signal {
+            throttle.wait().await;
+        }
+        log::debug!("Send message: {}", message);
+        if let Err(err) = writer_socket.send(message).await {
+            log::error!("Failed to send message: {}, the socket will reconnect: {}", err, id);
+            return if break_signal {
+                SocketExit::Close
+            } else {
+                SocketExit::Disconnect(format!("failed to send message: {}", err))
+            };
+        }
+        if break_signal {
+            log::info!("Socket close signal, the socket will close: {}", id);
+            return SocketExit::Close;
         }
     }
 }
 // 1. reader_socket: receive binance data. including subscription data and heartbeat data, when receive heartbeat data,
 //  use socket_health_sender send pong message to socket_health_receiver, and then send pong message to binance
-// 2. payload_sender: when receive binance subscription data , It will send the data to the actual consumers through the payload_sender.
-// 3. socket_health_sender: whenever a ping message is received, a pong message will be send to the socket_health_receiver
-async fn run_socket_receive<O>(
-    mut reader_socket: SplitStream<WebSocketStream<MaybeTlsStream<TcpStream>>>,
-    payload_sender: UnboundedSender<BinanceResult<SocketPayloadActor<O>>>,
-    socket_health_sender: Sender<Message>,
-) where
+// 2. deduplicator: after a rotation, drop the events which were already delivered by the replaced connection
+// 3. pending: when receive the answer of a subscription or unsubscription request, complete the request waiting for it
+// 4. payload_sender: when receive binance subscription data , It will send the data to the actual consumers through the payload_sender.
+// 5. socket_health_sender: whenever a ping message is received, a pong message will be send to the socket_health_receiver
+// 6. lifecycle: reports the answers of subscription and unsubscription requests to the consumer
+// 7. heartbeat_config: the connection is stale when nothing is received for too long, see SocketWatchdog
+// 8. rotate_at: when the connection is rotated, it is only checked between messages so that a payload already read
+//    is always delivered before the rotation
+#[allow(clippy::too_many_arguments)]
+async fn run_socket_receive<I, O>(
+    reader_socket: &mut SocketReader,
+    deduplicator: &mut Option<SocketPayloadDeduplicator>,
+    pending: &SocketPendingRequests<I>,
+    payload_sender: &PayloadSender<O>,
+    socket_health_sender: &Sender<Message>,
+    lifecycle: &SocketLifecycleReporter,
+    heartbeat_config: &HeartbeatConfig,
+    rotate_at: Option<Instant>,
+) -> SocketExit
+where
+    I: StreamNameFormat + Clone + Hash + Eq,
     O: DeserializeOwned + 'static + Send + Debug,
 {
-    while let Some(message) = reader_socket.next().await {
+    let mut watchdog = SocketWatchdog::new(heartbeat_config.clone());
+    // the streams are subscribed with the connection, the combined endpoint takes them from the query string
+    watchdog.watch_streams(pending.stream_names());
+    loop {
+        let message = tokio::select! {
+            message = reader_socket.next() => message,
+            _ = wait_rotation(rotate_at) => return SocketExit::Rotate,
+            _ = wait_deadline(watchdog.next_deadline()) => {
+                match watchdog.check(Instant::now()) {
+                    Some(stale) => return SocketExit::Stale(stale),
+                    None => continue,
+                }
+            }
+        };
+        let Some(message) = message else {
+            break;
+        };
+        watchdog.on_message();
         match message {
             Ok(message) => match message {
                 Message::Ping(data) => {
                     log::debug!("Received ping message: {}", String::from_utf8_lossy(&data));
-                    socket_health_sender
-                        .send(Message::Pong(data))
-                        .await
-                        .expect("Failed send pong to receiver");
+                    if socket_health_sender.send(Message::Pong(data)).await.is_err() {
+                        log::error!("Failed send pong to the socket sender, socket will reconnect");
+                        return SocketExit::Disconnect("failed to send pong".to_string());
+                    }
                 }
-                Message::Close(_) => {
-                    log::info!("Accept socket close message, socket will close");
-                    payload_sender.send(Ok(SocketPayloadActor::Close(1))).unwrap();
-                    break;
+                Message::Close(frame) => {
+                    log::info!("Accept socket close message, socket will reconnect");
+                    let reason = match frame {
+                        Some(frame) => format!("closed by binance: {} {}", frame.code, frame.reason),
+                        None => "closed by binance".to_string(),
+                    };
+                    return SocketExit::Disconnect(reason);
                 }
                 Message::Text(data) => {
                     log::debug!("Received text message: {}", data);
-                    let response = serde_json::from_str::<O>(data.as_str());
-                    let response = match response {
-                        Ok(response) => Some(Ok(SocketPayloadActor::Payload(response))),
-                        Err(e) => {
-                            match serde_json::from_str::<SocketOperationResp>(data.as_str()) {
-                                Ok(operator) => {
-                                    log::info!("Operator success response: {:?}", operator);
-                                    None
-                                }
-                                _ => Some(Err(map_deserialization_error(e, data.as_bytes()))),
-                            }
-                        }
+                    if deduplicator.as_ref().is_some_and(|dedup| !dedup.is_active()) {
+                        *deduplicator = None;
+                    }
+                    let texts = match deduplicator.as_mut() {
+                        Some(dedup) => dedup.accept(data.as_str(), HandoverConnection::New),
+                        None => vec![data.to_string()],
                     };
-                    match response {
-                        Some(resp) => {
-                            if let Err(e) = payload_sender.send(resp) {
-                                log::error!("Failed to send response: {}, body={}", e, data);
-                            }
-                        }
-                        None => {}
+                    let mut subscription_changed = false;
+                    for text in texts {
+                        subscription_changed |= deliver_text_message(text.as_str(), pending, payload_sender, lifecycle).await;
+                    }
+                    if subscription_changed {
+                        // a confirmed stream is watched from now, an unsubscribed one is forgotten
+                        watchdog.watch_streams(pending.stream_names());
+                    } else {
+                        watchdog.on_payload(data.as_str());
                     }
                 }
                 Message::Pong(data) => {
-                    log::warn!("Received pong message: {}", String::from_utf8_lossy(&data));
+                    log::debug!("Received pong message: {}", String::from_utf8_lossy(&data));
                 }
                 Message::Binary(data) => {
                     log::warn!(
@@ -282,9 +694,59 @@ async fn run_socket_receive<O>(
                 }
             },
             Err(err) => {
-                log::error!("websocket_accept_error: {}, the streaming will break;", err);
-                break;
+                log::error!("websocket_accept_error: {}, the streaming will reconnect;", err);
+                return SocketExit::Disconnect(format!("read error: {}", err));
             }
         }
     }
+    SocketExit::Disconnect("connection ended".to_string())
+}
+
+// deserialize a text message and send it to the consumer, the result of subscription or unsubscription completes its pending request
+// and is reported with SocketLifecycle::SubscriptionConfirmed or SocketLifecycle::SubscriptionRejected.
+// return true when the message is the answer of a request.
+async fn deliver_text_message<I, O>(
+    data: &str,
+    pending: &SocketPendingRequests<I>,
+    payload_sender: &PayloadSender<O>,
+    lifecycle: &SocketLifecycleReporter,
+) -> bool
+where
+    I: StreamNameFormat + Clone + Hash + Eq,
+    O: DeserializeOwned + 'static + Send + Debug,
+{
+    let response = serde_json::from_str::<O>(data);
+    let response = match response {
+        Ok(response) => Some(Ok(SocketPayloadActor::Payload(response))),
+        Err(e) => {
+            match serde_json::from_str::<SocketOperationResp>(data) {
+                Ok(operator) => {
+                    log::info!("Operator response: {:?}", operator);
+                    let request_id = operator.id;
+                    let (event, reason) = match &operator.error {
+                        Some(error) => (
+                            SocketLifecycle::SubscriptionRejected { request_id, code: error.code },
+                            error.msg.clone(),
+                        ),
+                        None => (SocketLifecycle::SubscriptionConfirmed { request_id }, "accepted".to_string()),
+                    };
+                    if pending.resolve(operator) {
+                        lifecycle.report(payload_sender, event, reason).await;
+                    } else {
+                        log::debug!("No request is waiting for the response: {}", request_id);
+                    }
+                    None
+                }
+                _ => Some(Err(map_deserialization_error(e, data.as_bytes()))),
+            }
+        }
+    };
+    let Some(resp) = response else {
+        return true;
+    };
+    let key = if payload_sender.needs_key() { stream_key(data) } else { None };
+    if payload_sender.send(resp, key).await.is_err() {
+        log::error!("Failed to send response, the consumer is gone, body={}", data);
+    }
+    false
 }
@@ -6,9 +6,19 @@ use serde::de::DeserializeOwned;
 use std::fmt::Debug;
 use std::marker::PhantomData;
 use std::pin::Pin;
+use tokio::sync::mpsc::{channel, Sender};
 
 pub trait StreamNameFormat {
     fn stream_name(&self) -> String;
+
+    // the reverse of stream_name, parse the stream key of a combined stream payload,
+    // a stream which is never combined keeps the default
+    fn from_stream_name(_stream_name: &str) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        None
+    }
 }
 
 #[async_trait]
@@ -53,3 +63,45 @@ where
         }
     }
 }
+
+// ChannelStreamPayloadProcess forwards every payload to a futures Stream, so that the payloads can be consumed
+// with StreamExt combinators or select! instead of a SocketPayloadProcess implementation.
+// a payload is only taken from the payload buffer when the stream is polled, so the backpressure policy still applies.
+#[derive(Debug)]
+pub struct ChannelStreamPayloadProcess<I> {
+    payload_sender: Sender<BinanceResult<SocketPayloadActor<I>>>,
+}
+
+impl<I> ChannelStreamPayloadProcess<I>
+where
+    I: Send + 'static,
+{
+    // the stream ends when the socket is closed and every payload is consumed
+    pub fn new() -> (
+        Self,
+        impl Stream<Item = BinanceResult<SocketPayloadActor<I>>> + Send + Unpin + 'static,
+    ) {
+        let (payload_sender, mut payload_receiver) = channel(1);
+        let payload_stream =
+            futures_util::stream::poll_fn(move |cx| payload_receiver.poll_recv(cx));
+        (ChannelStreamPayloadProcess { payload_sender }, payload_stream)
+    }
+}
+
+#[async_trait]
+impl<I> SocketPayloadProcess<I> for ChannelStreamPayloadProcess<I>
+where
+    I: Send + 'static,
+{
+    async fn process(
+        &mut self,
+        mut stream: Pin<Box<dyn Stream<Item = BinanceResult<SocketPayloadActor<I>>> + Send>>,
+    ) {
+        while let Some(data) = stream.next().await {
+            if self.payload_sender.send(data).await.is_err() {
+                log::info!("Payload stream is dropped, stop forwarding payloads");
+                break;
+            }
+        }
+    }
+}
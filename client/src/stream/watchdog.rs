@@ -0,0 +1,226 @@
+use crate::stream::config::HeartbeatConfig;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::time::Instant;
+
+// SocketWatchdog watches a single connection, a half-open connection leaves the socket reader waiting forever:
+// 1. the connection is stale when no message at all is received within the idle timeout,
+// 2. the connection is stale when a subscribed stream delivers nothing within its stream idle timeout, counted from
+//    its last payload or from when it was subscribed. a stream without a stream idle timeout is not watched.
+// the streams are the binance stream names, e.g. btcusdt@trade, the same on the raw and the combined endpoint.
+#[derive(Debug)]
+pub struct SocketWatchdog {
+    config: HeartbeatConfig,
+    last_message: Instant,
+    last_payload: HashMap<String, Instant>,
+}
+
+// why a connection is stale
+#[derive(Debug, Eq, PartialEq)]
+pub struct SocketStale {
+    // the stream which stopped delivering, None when the whole connection is idle
+    pub stream: Option<String>,
+    pub reason: String,
+}
+
+impl SocketWatchdog {
+    pub fn new(config: HeartbeatConfig) -> Self {
+        SocketWatchdog {
+            config,
+            last_message: Instant::now(),
+            last_payload: HashMap::new(),
+        }
+    }
+
+    // any message, including ping and pong, proves the connection is alive
+    pub fn on_message(&mut self) {
+        self.last_message = Instant::now();
+    }
+
+    // watch the streams subscribed now, a new stream is watched from now and an unsubscribed one is forgotten
+    pub fn watch_streams(&mut self, streams: Vec<String>) {
+        let now = Instant::now();
+        self.last_payload.retain(|stream, _| streams.contains(stream));
+        for stream in streams {
+            if self.config.stream_idle_timeout_of(&stream).is_some() {
+                self.last_payload.entry(stream).or_insert(now);
+            }
+        }
+    }
+
+    // a text message is a payload of its stream. a combined stream payload names its stream, a payload of the raw
+    // endpoint counts for every watched stream of its symbol and kline interval, or for all of them without symbol
+    pub fn on_payload(&mut self, text: &str) {
+        let Ok(payload) = serde_json::from_str::<Value>(text) else {
+            return;
+        };
+        let now = Instant::now();
+        match payload.get("stream").and_then(Value::as_str) {
+            Some(stream) => {
+                if let Some(last) = self.last_payload.get_mut(stream) {
+                    *last = now;
+                }
+            }
+            None => self
+                .last_payload
+                .iter_mut()
+                .filter(|(stream, _)| is_payload_of(stream, &payload))
+                .for_each(|(_, last)| *last = now),
+        }
+    }
+
+    // the next time the connection may become stale, None when nothing is watched
+    pub fn next_deadline(&self) -> Option<Instant> {
+        let connection = self.config.idle_timeout().map(|timeout| self.last_message + timeout);
+        let stream = self.stream_deadlines().map(|(_, deadline, _)| deadline).min();
+        connection.into_iter().chain(stream).min()
+    }
+
+    pub fn check(&self, now: Instant) -> Option<SocketStale> {
+        if let Some(timeout) = self.config.idle_timeout()
+            && now >= self.last_message + timeout
+        {
+            return Some(SocketStale {
+                stream: None,
+                reason: format!("no message received for {:?}", timeout),
+            });
+        }
+        self.stream_deadlines()
+            .filter(|(_, deadline, _)| now >= *deadline)
+            .min_by_key(|(_, deadline, _)| *deadline)
+            .map(|(stream, _, timeout)| SocketStale {
+                stream: Some(stream.clone()),
+                reason: format!("no payload of {} received for {:?}", stream, timeout),
+            })
+    }
+
+    // every watched stream with the time it becomes stale and its timeout
+    fn stream_deadlines(&self) -> impl Iterator<Item = (&String, Instant, Duration)> {
+        self.last_payload.iter().filter_map(|(stream, last)| {
+            let timeout = self.config.stream_idle_timeout_of(stream)?;
+            Some((stream, *last + timeout, timeout))
+        })
+    }
+}
+
+// whether a payload of the raw endpoint may belong to stream, it carries the symbol but not the stream name
+fn is_payload_of(stream: &str, payload: &Value) -> bool {
+    let symbol = payload.get("s").or_else(|| payload.get("ps")).and_then(Value::as_str);
+    if symbol.is_some_and(|symbol| !stream.starts_with(&symbol.to_lowercase())) {
+        return false;
+    }
+    let interval = payload.get("k").and_then(|kline| kline.get("i")).and_then(Value::as_str);
+    interval.is_none_or(|interval| stream.ends_with(&format!("_{}", interval)))
+}
+
+// the interval of the pings sent to binance, the first ping is sent one period after the connection is opened
+pub(crate) fn new_ping_interval(heartbeat_config: &HeartbeatConfig) -> Option<tokio::time::Interval> {
+    heartbeat_config.ping_interval().map(|period| {
+        let mut interval = tokio::time::interval_at(Instant::now() + period, period);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        interval
+    })
+}
+
+// wait for the next ping, forever when no ping is sent
+pub(crate) async fn wait_ping(ping_interval: &mut Option<tokio::time::Interval>) {
+    match ping_interval {
+        Some(ping_interval) => {
+            ping_interval.tick().await;
+        }
+        None => std::future::pending::<()>().await,
+    }
+}
+
+// wait for the deadline of SocketWatchdog::next_deadline, forever when nothing is watched
+pub(crate) async fn wait_deadline(deadline: Option<Instant>) {
+    match deadline {
+        Some(deadline) => tokio::time::sleep_until(deadline).await,
+        None => std::future::pending::<()>().await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn streams(streams: &[&str]) -> Vec<String> {
+        streams.iter().map(|stream| stream.to_string()).collect()
+    }
+
+    #[test]
+    fn test_check() {
+        let config = HeartbeatConfig::new(Some(Duration::from_secs(60)), Some(Duration::from_secs(30)), None);
+        let mut watchdog = SocketWatchdog::new(config);
+        assert!(watchdog.next_deadline().unwrap() > Instant::now() + Duration::from_secs(59));
+
+        // a subscribed stream is watched before it delivers anything
+        watchdog.watch_streams(streams(&["btcusdt@trade", "ethusdt@trade"]));
+        let now = Instant::now();
+        assert!(watchdog.next_deadline().unwrap() <= now + Duration::from_secs(30));
+        assert_eq!(watchdog.check(now), None);
+
+        watchdog.on_message();
+        watchdog.on_payload(r#"{"e":"trade","E":1,"s":"BTCUSDT","t":1}"#);
+        let stale = watchdog.check(now + Duration::from_secs(31)).unwrap();
+        assert_eq!(stale.stream.as_deref(), Some("ethusdt@trade"));
+
+        // an unsubscribed stream never looks stale
+        watchdog.watch_streams(streams(&["btcusdt@trade"]));
+        assert_eq!(watchdog.check(now + Duration::from_secs(29)), None);
+        watchdog.watch_streams(vec![]);
+        assert_eq!(watchdog.check(now + Duration::from_secs(31)), None);
+        assert_eq!(watchdog.check(now + Duration::from_secs(61)).unwrap().stream, None);
+    }
+
+    #[test]
+    fn test_stream_idle_timeout_override() {
+        let config = HeartbeatConfig::new(None, None, None).with_stream_idle_timeout("ethusdt@trade", Duration::from_secs(600));
+        let mut watchdog = SocketWatchdog::new(config);
+        watchdog.watch_streams(streams(&["btcusdt@trade", "ethusdt@trade"]));
+        let now = Instant::now();
+        // only the stream with its own timeout is watched
+        assert!(watchdog.next_deadline().unwrap() <= now + Duration::from_secs(600));
+        assert_eq!(watchdog.check(now + Duration::from_secs(300)), None);
+        assert_eq!(watchdog.check(now + Duration::from_secs(601)).unwrap().stream.as_deref(), Some("ethusdt@trade"));
+
+        // the other streams fall back to the global timeout
+        let config = HeartbeatConfig::new(None, Some(Duration::from_secs(30)), None)
+            .with_stream_idle_timeout("ethusdt@trade", Duration::from_secs(600));
+        let mut watchdog = SocketWatchdog::new(config);
+        watchdog.watch_streams(streams(&["btcusdt@trade", "ethusdt@trade"]));
+        let now = Instant::now();
+        assert_eq!(watchdog.check(now + Duration::from_secs(31)).unwrap().stream.as_deref(), Some("btcusdt@trade"));
+    }
+
+    #[test]
+    fn test_stream_key_of_both_endpoints() {
+        let config = HeartbeatConfig::new(None, None, None)
+            .with_stream_idle_timeout("btcusdt@trade", Duration::from_secs(30))
+            .with_stream_idle_timeout("btcusdt@kline_1m", Duration::from_secs(30))
+            .with_stream_idle_timeout("btcusdt@kline_5m", Duration::from_secs(30));
+        // the streams which did not take the payload, they were subscribed 20 seconds ago
+        let untouched = |payload: &str| {
+            let mut watchdog = SocketWatchdog::new(config.clone());
+            watchdog.watch_streams(streams(&["btcusdt@trade", "btcusdt@kline_1m", "btcusdt@kline_5m"]));
+            watchdog.last_payload.values_mut().for_each(|last| *last -= Duration::from_secs(20));
+            watchdog.on_payload(payload);
+            let later = Instant::now() + Duration::from_secs(20);
+            let mut untouched = watchdog
+                .stream_deadlines()
+                .filter(|(_, deadline, _)| *deadline < later)
+                .map(|(stream, _, _)| stream.clone())
+                .collect::<Vec<_>>();
+            untouched.sort();
+            untouched
+        };
+        // the raw endpoint
+        assert_eq!(untouched(r#"{"e":"kline","s":"BTCUSDT","k":{"i":"1m"}}"#), vec!["btcusdt@kline_5m", "btcusdt@trade"]);
+        // the combined endpoint
+        assert_eq!(
+            untouched(r#"{"stream":"btcusdt@trade","data":{"e":"trade","s":"BTCUSDT"}}"#),
+            vec!["btcusdt@kline_1m", "btcusdt@kline_5m"]
+        );
+    }
+}
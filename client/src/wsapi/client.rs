@@ -0,0 +1,515 @@
+use crate::rest::layer::authorization::types::Certificate;
+use crate::stream::lifecycle::current_timestamp;
+use crate::stream::watchdog::{SocketWatchdog, new_ping_interval, wait_deadline, wait_ping};
+use crate::wsapi::config::WsApiConfig;
+use crate::wsapi::message::{
+    logon_params, sign_params, to_params, WsApiEvent, WsApiEventMessage, WsApiRequest, WsApiResponse, WsApiSessionStatus,
+};
+use futures_util::{SinkExt, StreamExt};
+use general::error::SdkError;
+use general::result::BinanceResult;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio::net::TcpStream;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use tokio::sync::{mpsc, oneshot};
+use tokio::time::Instant;
+use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
+use tungstenite::Message;
+
+type SocketStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+type WsApiResponder = oneshot::Sender<BinanceResult<WsApiResponse>>;
+
+enum WsApiCommand {
+    Request {
+        method: String,
+        params: Map<String, Value>,
+        change: SessionChange,
+        responder: WsApiResponder,
+    },
+    Close,
+}
+
+// how the session of the connection changes when a request succeeds
+enum SessionChange {
+    Keep,
+    Logon(Certificate),
+    Logout,
+    SubscribeUserData(UnboundedSender<WsApiEvent>),
+    UnsubscribeUserData,
+}
+
+// WebsocketApiClient sends requests to the websocket api of binance over a single connection, see
+// https://developers.binance.com/docs/binance-spot-api-docs/web-socket-api/general-api-information
+// every request gets its own id, a request is completed when the response with the same id arrives.
+// the connection is reopened when it is lost, the requests waiting on the lost connection fail.
+// after session.logon, the signed requests of the logged on certificate are sent without signature,
+// and the session is logged on again whenever the connection is reopened.
+// the user data stream of the session is subscribed again after the session is logged on again.
+#[derive(Clone)]
+pub struct WebsocketApiClient {
+    command_sender: mpsc::Sender<WsApiCommand>,
+    request_timeout: Duration,
+    // the api key of the logged on session
+    session_api_key: Arc<RwLock<Option<String>>>,
+}
+
+impl WebsocketApiClient {
+    pub async fn new_with_uri(uri: &str) -> BinanceResult<Self> {
+        Self::new_with_config(uri, WsApiConfig::new_default()).await
+    }
+
+    pub async fn new_with_config(uri: &str, config: WsApiConfig) -> BinanceResult<Self> {
+        let stream = connect(uri).await?;
+        let (command_sender, command_receiver) = mpsc::channel(64);
+        let session_api_key = Arc::new(RwLock::new(None));
+        let request_timeout = config.request_timeout();
+        let connection = WsApiConnection {
+            uri: uri.to_string(),
+            config,
+            command_receiver,
+            pending: HashMap::new(),
+            next_id: 1,
+            session: None,
+            session_api_key: session_api_key.clone(),
+            user_data: None,
+        };
+        tokio::spawn(connection.run(stream));
+        Ok(WebsocketApiClient {
+            command_sender,
+            request_timeout,
+            session_api_key,
+        })
+    }
+
+    // a request without authentication, e.g. market data
+    pub async fn request<P, R>(&self, method: &str, params: &P) -> BinanceResult<R>
+    where
+        P: Serialize + ?Sized,
+        R: DeserializeOwned,
+    {
+        self.send(method, to_params(params)?, SessionChange::Keep).await
+    }
+
+    // a request signed with the certificate, e.g. order placement and account data
+    pub async fn signed_request<P, R>(&self, method: &str, params: &P, certificate: &Certificate) -> BinanceResult<R>
+    where
+        P: Serialize + ?Sized,
+        R: DeserializeOwned,
+    {
+        let mut params = to_params(params)?;
+        if self.is_logged_on_with(certificate) {
+            params.insert("timestamp".to_string(), Value::from(current_timestamp()));
+        } else {
+            sign_params(&mut params, certificate, current_timestamp())?;
+        }
+        self.send(method, params, SessionChange::Keep).await
+    }
+
+    // authenticate the connection with the Ed25519 key of the certificate, see
+    // https://developers.binance.com/docs/binance-spot-api-docs/web-socket-api/session-authentication
+    pub async fn logon(&self, certificate: Certificate) -> BinanceResult<WsApiSessionStatus> {
+        let params = logon_params(&certificate, current_timestamp())?;
+        self.send("session.logon", params, SessionChange::Logon(certificate)).await
+    }
+
+    pub async fn session_status(&self) -> BinanceResult<WsApiSessionStatus> {
+        self.send("session.status", Map::new(), SessionChange::Keep).await
+    }
+
+    // forget the authentication, the signed requests are signed one by one again
+    pub async fn logout(&self) -> BinanceResult<WsApiSessionStatus> {
+        self.send("session.logout", Map::new(), SessionChange::Logout).await
+    }
+
+    // receive the user data events of the logged on session without a listen key, see
+    // https://developers.binance.com/docs/binance-spot-api-docs/web-socket-api/user-data-stream-requests
+    // the events are raw json, the receiver ends when the subscription is removed or the connection is closed.
+    pub async fn subscribe_user_data(&self) -> BinanceResult<UnboundedReceiver<WsApiEvent>> {
+        let (event_sender, event_receiver) = unbounded_channel();
+        self.send::<Value>("userDataStream.subscribe", Map::new(), SessionChange::SubscribeUserData(event_sender))
+            .await?;
+        Ok(event_receiver)
+    }
+
+    pub async fn unsubscribe_user_data(&self) -> BinanceResult<()> {
+        self.send::<Value>("userDataStream.unsubscribe", Map::new(), SessionChange::UnsubscribeUserData)
+            .await
+            .map(|_| ())
+    }
+
+    // the api key of the logged on session, None when the session is not authenticated
+    pub fn get_session_api_key(&self) -> Option<String> {
+        self.session_api_key.read().unwrap().clone()
+    }
+
+    fn is_logged_on_with(&self, certificate: &Certificate) -> bool {
+        self.session_api_key.read().unwrap().as_deref() == Some(certificate.api_key())
+    }
+
+    async fn send<R>(&self, method: &str, params: Map<String, Value>, change: SessionChange) -> BinanceResult<R>
+    where
+        R: DeserializeOwned,
+    {
+        let (responder, response) = oneshot::channel();
+        let command = WsApiCommand::Request {
+            method: method.to_string(),
+            params,
+            change,
+            responder,
+        };
+        if self.command_sender.send(command).await.is_err() {
+            return Err(socket_closed_error());
+        }
+        let response = match tokio::time::timeout(self.request_timeout, response).await {
+            Ok(Ok(response)) => response?,
+            Ok(Err(_)) => return Err(socket_closed_error()),
+            Err(_) => return Err(SdkError::WebsocketRequestTimeout(self.request_timeout)),
+        };
+        if let Some(error) = response.error {
+            return Err(SdkError::WebsocketRequestError(error.code, error.msg));
+        }
+        serde_json::from_value(response.result.unwrap_or(Value::Null)).map_err(SdkError::JSONDeserialize)
+    }
+
+    // close the connection, the requests waiting for an answer fail
+    pub async fn close(&self) {
+        if self.command_sender.send(WsApiCommand::Close).await.is_err() {
+            log::info!("The websocket api connection is already closed");
+        }
+    }
+}
+
+fn socket_closed_error() -> SdkError {
+    SdkError::WebsocketError(tungstenite::Error::AlreadyClosed)
+}
+
+async fn connect(uri: &str) -> BinanceResult<SocketStream> {
+    log::info!("Connecting to {}...", uri);
+    let (socket, response) = connect_async(uri).await?;
+    log::debug!("WebSocket api response status: {}", response.status());
+    Ok(socket)
+}
+
+struct PendingWsApiRequest {
+    change: SessionChange,
+    responder: WsApiResponder,
+    expire_at: Instant,
+}
+
+enum ConnectionExit {
+    Close,
+    Disconnect(String),
+}
+
+struct WsApiConnection {
+    uri: String,
+    config: WsApiConfig,
+    command_receiver: mpsc::Receiver<WsApiCommand>,
+    pending: HashMap<u64, PendingWsApiRequest>,
+    next_id: u64,
+    // the certificate of the logged on session, it logs on again after a reconnect
+    session: Option<Certificate>,
+    session_api_key: Arc<RwLock<Option<String>>>,
+    // the subscriber of the user data stream, it is subscribed again after a reconnect
+    user_data: Option<UnboundedSender<WsApiEvent>>,
+}
+
+impl WsApiConnection {
+    async fn run(mut self, mut stream: SocketStream) {
+        loop {
+            let exit = self.serve(&mut stream).await;
+            self.fail_pending();
+            match exit {
+                ConnectionExit::Close => {
+                    let _ = stream.close(None).await;
+                    log::info!("The websocket api connection is closed: {}", self.uri);
+                    break;
+                }
+                ConnectionExit::Disconnect(reason) => {
+                    log::warn!("The websocket api connection is lost: {}, reconnect it", reason);
+                    stream = match self.reconnect().await {
+                        Some(stream) => stream,
+                        None => break,
+                    };
+                }
+            }
+        }
+        if let Some(user_data) = self.user_data.take() {
+            let _ = user_data.send(WsApiEvent::Close);
+        }
+    }
+
+    async fn reconnect(&mut self) -> Option<SocketStream> {
+        let reconnect_config = self.config.reconnect_config();
+        let mut attempt = 1;
+        while !reconnect_config.is_exhausted(attempt) {
+            let backoff = reconnect_config.backoff(attempt);
+            if let Some(user_data) = &self.user_data
+                && user_data.send(WsApiEvent::Reconnect { attempt, backoff }).is_err()
+            {
+                self.user_data = None;
+            }
+            tokio::time::sleep(backoff).await;
+            // nobody can send a request anymore, there is nothing to reconnect for
+            if self.command_receiver.is_closed() {
+                return None;
+            }
+            match self.connect_and_restore().await {
+                Ok(stream) => {
+                    log::info!("The websocket api reconnected after {} attempts", attempt);
+                    return Some(stream);
+                }
+                Err(err) => {
+                    log::error!("The websocket api reconnect attempt {} failed: {}", attempt, err);
+                    attempt += 1;
+                }
+            }
+        }
+        log::error!("The websocket api gives up reconnecting: {}", self.uri);
+        None
+    }
+
+    // open a new connection, log on the session and subscribe the user data stream again
+    async fn connect_and_restore(&mut self) -> BinanceResult<SocketStream> {
+        let mut stream = connect(&self.uri).await?;
+        if let Err(err) = self.restore(&mut stream).await {
+            let _ = stream.close(None).await;
+            return Err(err);
+        }
+        Ok(stream)
+    }
+
+    // a request binance rejects is given up, any other error fails the connection
+    async fn restore(&mut self, stream: &mut SocketStream) -> BinanceResult<()> {
+        let Some(certificate) = self.session.clone() else {
+            return Ok(());
+        };
+        let params = logon_params(&certificate, current_timestamp())?;
+        match self.request_before_serving(stream, "session.logon", params).await {
+            Ok(()) => log::info!("The websocket api session is logged on again: {}", certificate.api_key()),
+            // the key is not accepted anymore, the requests are signed one by one from now on
+            Err(SdkError::WebsocketRequestError(code, msg)) => {
+                log::error!("The websocket api session cannot log on again: code={}, msg={}", code, msg);
+                self.set_session(None);
+                self.user_data = None;
+                return Ok(());
+            }
+            Err(err) => return Err(err),
+        }
+        if self.user_data.as_ref().is_none_or(|user_data| user_data.is_closed()) {
+            self.user_data = None;
+            return Ok(());
+        }
+        match self.request_before_serving(stream, "userDataStream.subscribe", Map::new()).await {
+            Ok(()) => log::info!("The websocket api user data stream is subscribed again"),
+            Err(SdkError::WebsocketRequestError(code, msg)) => {
+                log::error!("The websocket api user data stream cannot be subscribed again: code={}, msg={}", code, msg);
+                self.user_data = None;
+            }
+            Err(err) => return Err(err),
+        }
+        Ok(())
+    }
+
+    // send a request on a new connection and wait for its answer before any other request is sent on it
+    async fn request_before_serving(
+        &mut self,
+        stream: &mut SocketStream,
+        method: &str,
+        params: Map<String, Value>,
+    ) -> BinanceResult<()> {
+        let id = self.take_id();
+        let request = WsApiRequest {
+            id,
+            method: method.to_string(),
+            params,
+        };
+        stream.send(Message::Text(serde_json::to_string(&request)?)).await?;
+        let timeout = self.config.request_timeout();
+        let response = tokio::time::timeout(timeout, async {
+            loop {
+                match stream.next().await {
+                    Some(Ok(Message::Text(data))) => match serde_json::from_str::<WsApiResponse>(data.as_str()) {
+                        Ok(response) if response.id == Some(id) => return Ok(response),
+                        _ => log::debug!("Ignore websocket api message before {}: {}", method, data),
+                    },
+                    Some(Ok(Message::Ping(data))) => stream.send(Message::Pong(data)).await?,
+                    Some(Ok(Message::Close(_))) | None => return Err(socket_closed_error()),
+                    Some(Ok(_)) => {}
+                    Some(Err(err)) => return Err(SdkError::WebsocketError(err)),
+                }
+            }
+        })
+        .await
+        .map_err(|_| SdkError::WebsocketRequestTimeout(timeout))??;
+        match response.error {
+            Some(error) => Err(SdkError::WebsocketRequestError(error.code, error.msg)),
+            None => Ok(()),
+        }
+    }
+
+    fn set_session(&mut self, certificate: Option<Certificate>) {
+        *self.session_api_key.write().unwrap() = certificate.as_ref().map(|cert| cert.api_key().to_string());
+        self.session = certificate;
+    }
+
+    fn take_id(&mut self) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        id
+    }
+
+    async fn serve(&mut self, stream: &mut SocketStream) -> ConnectionExit {
+        let heartbeat_config = self.config.heartbeat_config();
+        let mut ping_interval = new_ping_interval(&heartbeat_config);
+        let mut watchdog = SocketWatchdog::new(heartbeat_config);
+        loop {
+            let message = tokio::select! {
+                message = stream.next() => match message {
+                    Some(Ok(message)) => {
+                        watchdog.on_message();
+                        match self.on_message(message) {
+                            Ok(Some(reply)) => reply,
+                            Ok(None) => continue,
+                            Err(exit) => return exit,
+                        }
+                    }
+                    Some(Err(err)) => return ConnectionExit::Disconnect(format!("read error: {}", err)),
+                    None => return ConnectionExit::Disconnect("connection ended".to_string()),
+                },
+                command = self.command_receiver.recv() => match command {
+                    Some(WsApiCommand::Request { method, params, change, responder }) => {
+                        match self.register(method, params, change, responder) {
+                            Some(message) => message,
+                            None => continue,
+                        }
+                    }
+                    Some(WsApiCommand::Close) | None => return ConnectionExit::Close,
+                },
+                _ = wait_ping(&mut ping_interval) => Message::Ping(Vec::new()),
+                _ = wait_deadline(watchdog.next_deadline()) => {
+                    match watchdog.check(Instant::now()) {
+                        Some(stale) => return ConnectionExit::Disconnect(stale.reason),
+                        None => continue,
+                    }
+                }
+            };
+            if let Err(err) = stream.send(message).await {
+                return ConnectionExit::Disconnect(format!("failed to send message: {}", err));
+            }
+        }
+    }
+
+    // give the request an id and wait for the response of that id, return the message to send
+    fn register(
+        &mut self,
+        method: String,
+        params: Map<String, Value>,
+        change: SessionChange,
+        responder: WsApiResponder,
+    ) -> Option<Message> {
+        let id = self.take_id();
+        let request = WsApiRequest { id, method, params };
+        let message = match serde_json::to_string(&request) {
+            Ok(message) => message,
+            Err(err) => {
+                let _ = responder.send(Err(SdkError::DeserializeError(err)));
+                return None;
+            }
+        };
+        let now = Instant::now();
+        self.pending
+            .retain(|_, request| request.expire_at > now && !request.responder.is_closed());
+        self.pending.insert(
+            id,
+            PendingWsApiRequest {
+                change,
+                responder,
+                expire_at: now + self.config.request_timeout(),
+            },
+        );
+        log::debug!("Send websocket api request: {} {}", id, request.method);
+        Some(Message::Text(message))
+    }
+
+    // handle a message of binance, return the reply to send, or the reason to leave the connection
+    fn on_message(&mut self, message: Message) -> Result<Option<Message>, ConnectionExit> {
+        match message {
+            Message::Text(data) => {
+                self.on_text(data.as_str());
+                Ok(None)
+            }
+            Message::Ping(data) => Ok(Some(Message::Pong(data))),
+            Message::Close(frame) => {
+                let reason = match frame {
+                    Some(frame) => format!("closed by binance: {} {}", frame.code, frame.reason),
+                    None => "closed by binance".to_string(),
+                };
+                Err(ConnectionExit::Disconnect(reason))
+            }
+            Message::Pong(_) => Ok(None),
+            message => {
+                log::warn!("Received unexpected websocket api message: {}", message);
+                Ok(None)
+            }
+        }
+    }
+
+    fn on_text(&mut self, data: &str) {
+        let response = match serde_json::from_str::<WsApiResponse>(data) {
+            Ok(response) => response,
+            Err(err) => {
+                match serde_json::from_str::<WsApiEventMessage>(data) {
+                    Ok(event) => self.on_event(event),
+                    Err(_) => log::warn!("Received unknown websocket api message: {}, error: {}", data, err),
+                }
+                return;
+            }
+        };
+        let Some(id) = response.id else {
+            log::error!("Binance rejected a websocket api request: {:?}", response.error);
+            return;
+        };
+        match self.pending.remove(&id) {
+            Some(request) => {
+                if response.error.is_none() {
+                    match request.change {
+                        SessionChange::Keep => {}
+                        SessionChange::Logon(certificate) => self.set_session(Some(certificate)),
+                        SessionChange::Logout => {
+                            self.set_session(None);
+                            self.user_data = None;
+                        }
+                        SessionChange::SubscribeUserData(user_data) => self.user_data = Some(user_data),
+                        SessionChange::UnsubscribeUserData => self.user_data = None,
+                    }
+                }
+                let _ = request.responder.send(Ok(response));
+            }
+            None => log::debug!("No request is waiting for the websocket api response: {}", id),
+        }
+    }
+
+    fn on_event(&mut self, event: WsApiEventMessage) {
+        let Some(user_data) = &self.user_data else {
+            log::debug!("No subscriber is waiting for the websocket api event: {}", event.event);
+            return;
+        };
+        if user_data.send(WsApiEvent::Payload(event.event)).is_err() {
+            log::info!("The websocket api user data subscriber is gone");
+            self.user_data = None;
+        }
+    }
+
+    // the requests sent on a lost connection are never answered
+    fn fail_pending(&mut self) {
+        self.pending.drain().for_each(|(_, request)| {
+            let _ = request.responder.send(Err(socket_closed_error()));
+        });
+    }
+}
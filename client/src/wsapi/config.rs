@@ -0,0 +1,42 @@
+use crate::stream::config::{HeartbeatConfig, ReconnectConfig};
+use std::time::Duration;
+
+#[derive(Debug, Clone)]
+pub struct WsApiConfig {
+    reconnect_config: ReconnectConfig,
+    heartbeat_config: HeartbeatConfig,
+    request_timeout: Duration,
+}
+
+impl WsApiConfig {
+    // request_timeout: how long a request waits for the answer of binance
+    pub fn new(
+        reconnect_config: ReconnectConfig,
+        heartbeat_config: HeartbeatConfig,
+        request_timeout: Duration,
+    ) -> Self {
+        WsApiConfig {
+            reconnect_config,
+            heartbeat_config,
+            request_timeout,
+        }
+    }
+
+    pub fn new_default() -> Self {
+        WsApiConfig::new(
+            ReconnectConfig::new_default(),
+            HeartbeatConfig::new_default(),
+            Duration::from_secs(10),
+        )
+    }
+
+    pub fn reconnect_config(&self) -> ReconnectConfig {
+        self.reconnect_config
+    }
+    pub fn heartbeat_config(&self) -> HeartbeatConfig {
+        self.heartbeat_config.clone()
+    }
+    pub fn request_timeout(&self) -> Duration {
+        self.request_timeout
+    }
+}
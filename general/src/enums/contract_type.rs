@@ -27,4 +27,17 @@ impl ContractType {
             ContractType::PerpetualDelivering => "perpetual_delivering",
         }
     }
-}
\ No newline at end of file
+
+    pub fn parse(value: &str) -> Option<ContractType> {
+        [
+            ContractType::Perpetual,
+            ContractType::CurrentQuarter,
+            ContractType::NextQuarter,
+            ContractType::CurrentMonth,
+            ContractType::NextMonth,
+            ContractType::PerpetualDelivering,
+        ]
+        .into_iter()
+        .find(|contract_type| contract_type.as_str() == value)
+    }
+}
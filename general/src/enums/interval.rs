@@ -59,4 +59,27 @@ impl Interval {
             Interval::Month => "1M",
         }
     }
+
+    pub fn parse(value: &str) -> Option<Interval> {
+        [
+            Interval::Second1,
+            Interval::Minute1,
+            Interval::Minute3,
+            Interval::Minute5,
+            Interval::Minute15,
+            Interval::Minute30,
+            Interval::Hour1,
+            Interval::Hour2,
+            Interval::Hour4,
+            Interval::Hour6,
+            Interval::Hour8,
+            Interval::Hour12,
+            Interval::Day1,
+            Interval::Day3,
+            Interval::Week,
+            Interval::Month,
+        ]
+        .into_iter()
+        .find(|interval| interval.as_str() == value)
+    }
 }
@@ -11,4 +11,10 @@ impl Level {
     pub fn value(&self) -> i32 {
         *self as i32
     }
+
+    pub fn parse(value: &str) -> Option<Level> {
+        [Level::L1, Level::L2, Level::L3]
+            .into_iter()
+            .find(|level| level.value().to_string() == value)
+    }
 }
@@ -76,4 +76,19 @@ pub enum OrderResponseType {
 pub enum OrderSide {
     BUY,
     SELL,
-}
\ No newline at end of file
+}
+#[derive(Debug, Copy, Clone, Deserialize, Serialize, Eq, PartialEq, Hash)]
+pub enum PositionSide {
+    BOTH,
+    LONG,
+    SHORT,
+}
+
+// the price which triggers a futures stop order
+#[derive(Debug, Copy, Clone, Deserialize, Serialize, Eq, PartialEq, Hash)]
+pub enum WorkingType {
+    #[serde(rename="MARK_PRICE")]
+    MarkPrice,
+    #[serde(rename="CONTRACT_PRICE")]
+    ContractPrice,
+}
@@ -13,4 +13,10 @@ impl Speed {
             Speed::Ms500 => "500ms",
         }
     }
-}
\ No newline at end of file
+
+    pub fn parse(value: &str) -> Option<Speed> {
+        [Speed::Ms100, Speed::Ms500]
+            .into_iter()
+            .find(|speed| speed.as_str() == value)
+    }
+}
@@ -11,4 +11,10 @@ impl Timezone {
             Timezone::UTC8 => "+08:00",
         }
     }
-}
\ No newline at end of file
+
+    pub fn parse(value: &str) -> Option<Timezone> {
+        [Timezone::UTC8]
+            .into_iter()
+            .find(|timezone| timezone.as_str() == value)
+    }
+}
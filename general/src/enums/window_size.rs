@@ -16,4 +16,10 @@ impl WindowSize {
             Self::FourHours => "1d",
         }
     }
-}
\ No newline at end of file
+
+    pub fn parse(value: &str) -> Option<WindowSize> {
+        [WindowSize::OneHour, WindowSize::FourHours, WindowSize::OneDay]
+            .into_iter()
+            .find(|window_size| window_size.as_str() == value)
+    }
+}
@@ -27,6 +27,14 @@ pub enum SdkError {
     ResponseBodyFrameError(String),
     #[error("parameter error: {0}")]
     ParameterError(String),
+    #[error("websocket request rejected: code={0}, msg={1}")]
+    WebsocketRequestError(i64, String),
+    #[error("websocket request got no response within {0:?}")]
+    WebsocketRequestTimeout(std::time::Duration),
+    #[error("rate limit capacity not available within {0:?}")]
+    RateLimitWaitExceeded(std::time::Duration),
+    #[error("requests to the {0} hosts are stopped after a rate limit ban until {1:?}")]
+    CircuitOpen(String, std::time::SystemTime),
 }
 #[derive(Debug, Error)]
 pub struct ApplicationError(pub String);
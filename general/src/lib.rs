@@ -4,4 +4,5 @@ pub mod serialize_extend;
 pub mod error;
 pub mod result;
 pub mod enums;
-pub mod symbol;
\ No newline at end of file
+pub mod symbol;
+pub mod price_level;
@@ -0,0 +1,59 @@
+use bigdecimal::BigDecimal;
+use serde::de::Error;
+use serde::ser::SerializeTuple;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+// a level of an order book, binance sends it as ["price", "quantity"],
+// a quantity of zero in a diff depth event means the level is removed.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PriceLevel {
+    pub price: BigDecimal,
+    pub quantity: BigDecimal,
+}
+
+impl PriceLevel {
+    pub fn new(price: BigDecimal, quantity: BigDecimal) -> Self {
+        PriceLevel { price, quantity }
+    }
+}
+
+impl<'de> Deserialize<'de> for PriceLevel {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let mut level = Vec::<BigDecimal>::deserialize(deserializer)?.into_iter();
+        match (level.next(), level.next()) {
+            (Some(price), Some(quantity)) => Ok(PriceLevel { price, quantity }),
+            _ => Err(D::Error::custom("a price level needs a price and a quantity")),
+        }
+    }
+}
+
+impl Serialize for PriceLevel {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut level = serializer.serialize_tuple(2)?;
+        level.serialize_element(&self.price.to_string())?;
+        level.serialize_element(&self.quantity.to_string())?;
+        level.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_price_level() {
+        let levels = serde_json::from_str::<Vec<PriceLevel>>(r#"[["0.0024","10"],["0.0026","0.00000000"]]"#).unwrap();
+        assert_eq!(levels[0].price, BigDecimal::from_str("0.0024").unwrap());
+        assert_eq!(levels[0].quantity, BigDecimal::from(10));
+        assert_eq!(levels[1].quantity, BigDecimal::from(0));
+        assert_eq!(serde_json::to_string(&levels[0]).unwrap(), r#"["0.0024","10"]"#);
+        assert!(serde_json::from_str::<PriceLevel>(r#"["0.0024"]"#).is_err());
+    }
+}
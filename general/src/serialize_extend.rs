@@ -50,4 +50,20 @@ where
             serializer.serialize_str(format!("[{}]", result).as_str())
         }
     }
-}
\ No newline at end of file
+}
+// binance sends some timestamps as a number in one market and as a string in another
+pub fn from_str_or_number_to_u64<'de, D>(deserializer: D) -> Result<u64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum StrOrNumber {
+        Number(u64),
+        Str(String),
+    }
+    match StrOrNumber::deserialize(deserializer)? {
+        StrOrNumber::Number(value) => Ok(value),
+        StrOrNumber::Str(value) => u64::from_str(&value).map_err(serde::de::Error::custom),
+    }
+}
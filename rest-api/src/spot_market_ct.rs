@@ -10,6 +10,7 @@ use crate::types::market::trade_list::{
     AggTradeListReq, AggTradeListResp, LookupTradeListReq, TradeListResp,
 };
 use client::rest::client::{BinanceClient, BinanceClientAction};
+use client::rest::config::{RateLimitMarket, RateLimitProfile};
 use client::rest::extension::RequestExtension;
 use general::result::BinanceResult;
 
@@ -23,9 +24,10 @@ where
     T: BinanceClient + BinanceClientAction,
 {
     pub fn new(client: T) -> SpotMarketClient<T> {
+        let domain = client.get_environment().spot_rest_domain().to_string();
         SpotMarketClient {
             client,
-            domain: "api.binance.com".to_string(),
+            domain,
         }
     }
 
@@ -40,6 +42,14 @@ where
             .await
     }
 
+    // take the rateLimits of the exchangeInfo as the spot rate limits of the client
+    pub async fn sync_rate_limits(&self) -> BinanceResult<RateLimitProfile> {
+        let exchange = self.get_exchange(ExchangeReq::new()).await?;
+        let profile = RateLimitProfile::new(exchange.rate_limits);
+        self.client.set_rate_limits(RateLimitMarket::Spot, &profile);
+        Ok(profile)
+    }
+
     pub async fn get_order_book(&self, request: CommonReq) -> BinanceResult<OrderBookResp> {
         let weight = if request.get_limit() <= 100 {
             5
@@ -25,9 +25,10 @@ where
     T: BinanceClient + BinanceClientAction,
 {
     pub fn new(client: T) -> Self {
+        let domain = client.get_environment().spot_rest_domain().to_string();
         SpotOrderClient {
             client,
-            domain: "api.binance.com".to_string(),
+            domain,
         }
     }
 
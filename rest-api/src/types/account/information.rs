@@ -0,0 +1,69 @@
+use bigdecimal::BigDecimal;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Default)]
+pub struct AccountInformationReq {
+    #[serde(rename = "omitZeroBalances")]
+    omit_zero_balances: Option<bool>,
+    #[serde(rename = "recvWindow")]
+    recv_window: Option<u64>,
+}
+
+impl AccountInformationReq {
+    pub fn new() -> Self {
+        Default::default()
+    }
+    pub fn set_omit_zero_balances(&mut self, omit_zero_balances: bool) {
+        self.omit_zero_balances = Some(omit_zero_balances);
+    }
+    pub fn set_recv_window(&mut self, recv_window: u64) {
+        self.recv_window = Some(recv_window);
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CommissionRates {
+    pub maker: BigDecimal,
+    pub taker: BigDecimal,
+    pub buyer: BigDecimal,
+    pub seller: BigDecimal,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AccountBalance {
+    pub asset: String,
+    pub free: BigDecimal,
+    pub locked: BigDecimal,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AccountInformationResp {
+    #[serde(rename = "makerCommission")]
+    pub maker_commission: u64,
+    #[serde(rename = "takerCommission")]
+    pub taker_commission: u64,
+    #[serde(rename = "buyerCommission")]
+    pub buyer_commission: u64,
+    #[serde(rename = "sellerCommission")]
+    pub seller_commission: u64,
+    #[serde(rename = "commissionRates")]
+    pub commission_rates: CommissionRates,
+    #[serde(rename = "canTrade")]
+    pub can_trade: bool,
+    #[serde(rename = "canWithdraw")]
+    pub can_withdraw: bool,
+    #[serde(rename = "canDeposit")]
+    pub can_deposit: bool,
+    pub brokered: bool,
+    #[serde(rename = "requireSelfTradePrevention")]
+    pub require_self_trade_prevention: bool,
+    #[serde(rename = "preventSor")]
+    pub prevent_sor: bool,
+    #[serde(rename = "updateTime")]
+    pub update_time: u64,
+    #[serde(rename = "accountType")]
+    pub account_type: String,
+    pub balances: Vec<AccountBalance>,
+    pub permissions: Vec<String>,
+    pub uid: u64,
+}
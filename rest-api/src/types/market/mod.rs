@@ -5,4 +5,5 @@ pub mod kline;
 pub mod price;
 pub mod symbol_info;
 pub mod future_exchange;
-pub mod asset;
\ No newline at end of file
+pub mod asset;
+pub mod time;
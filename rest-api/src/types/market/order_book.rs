@@ -1,3 +1,4 @@
+use general::price_level::PriceLevel;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize)]
@@ -36,7 +37,7 @@ pub struct OrderBookResp {
     #[serde(rename = "T")]
     pub transaction_time: Option<u64>,
     #[serde(rename = "bids")]
-    pub bids: Vec<Vec<String>>,
+    pub bids: Vec<PriceLevel>,
     #[serde(rename = "asks")]
-    pub asks: Vec<Vec<String>>,
+    pub asks: Vec<PriceLevel>,
 }
@@ -0,0 +1,7 @@
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct ServerTimeResp {
+    #[serde(rename = "serverTime")]
+    pub server_time: u64,
+}
@@ -2,6 +2,7 @@ use crate::types::market::future_exchange::FutureExchangeInfoResp;
 use crate::types::market::order_book::{CommonReq, OrderBookResp};
 use crate::types::market::trade_list::{AggTradeListReq, AggTradeListResp, LookupTradeListReq, TradeListResp};
 use client::rest::client::{BinanceClient, BinanceClientAction};
+use client::rest::config::{RateLimitMarket, RateLimitProfile};
 use client::rest::extension::RequestExtension;
 use general::result::BinanceResult;
 
@@ -15,9 +16,10 @@ where
     T: BinanceClient + BinanceClientAction,
 {
     pub fn new(client: T) -> UsdFutureMarketClient<T> {
+        let domain = client.get_environment().future_rest_domain().to_string();
         UsdFutureMarketClient {
             client,
-            domain: "fapi.binance.com".to_string(),
+            domain,
         }
     }
 
@@ -33,6 +35,14 @@ where
             .await
     }
 
+    /// take the rateLimits of the exchangeInfo as the futures rate limits of the client
+    pub async fn sync_rate_limits(&self) -> BinanceResult<RateLimitProfile> {
+        let exchange = self.exchange_info().await?;
+        let profile = RateLimitProfile::new(exchange.rate_limits);
+        self.client.set_rate_limits(RateLimitMarket::Future, &profile);
+        Ok(profile)
+    }
+
     /// https://developers.binance.com/docs/derivatives/usds-margined-futures/market-data/rest-api/Order-Book
     pub async fn order_book(&self, request: CommonReq) -> BinanceResult<OrderBookResp> {
         let weight = if request.get_limit() <= 100 {
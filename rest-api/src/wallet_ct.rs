@@ -2,9 +2,11 @@ use crate::types::wallet::coins::{GetWalletCoinsReq, GetWalletCoinsResp};
 use crate::types::wallet::withdraw::{
     CreateWithdrawReq, CreateWithdrawResp, GetWithdrawHistoryReq, GetWithdrawHistoryResp,
 };
+use client::environment::SpotEndpoint;
 use client::rest::client::{BinanceClient, BinanceClientAction};
 use client::rest::extension::RequestExtension;
 use client::rest::layer::authorization::types::{AuthType, Certificate};
+use general::error::SdkError;
 use general::result::BinanceResult;
 
 pub struct WalletClient<T> {
@@ -16,11 +18,21 @@ impl<T> WalletClient<T>
 where
     T: BinanceClient + BinanceClientAction,
 {
-    pub fn new(client: T) -> Self {
-        WalletClient {
-            client,
-            domain: "api.binance.com".to_string(),
+    // the wallet lives under /sapi of the production account, the testnet and the market data hosts do not serve it.
+    // it fails with the SdkError every call of the client returns, however large
+    #[allow(clippy::result_large_err)]
+    pub fn new(client: T) -> BinanceResult<Self> {
+        let spot = client.get_environment().get_spot();
+        if matches!(spot, SpotEndpoint::Testnet | SpotEndpoint::MarketData) {
+            return Err(SdkError::ParameterError(format!(
+                "the wallet api is not served by {}, a production or cluster spot endpoint is required",
+                spot.rest_domain()
+            )));
         }
+        Ok(WalletClient {
+            client,
+            domain: spot.rest_domain().to_string(),
+        })
     }
     // link: https://developers.binance.com/docs/wallet/capital
     async fn get_all_wallet_coins(
@@ -75,6 +87,7 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
+    use client::environment::Environment;
     use client::rest::config::Config;
     use client::rest::rest_client::BinanceRestClient;
     use env_logger::Builder;
@@ -83,7 +96,7 @@ mod tests {
 
     lazy_static! {
         static ref CLIENT: WalletClient<BinanceRestClient> =
-            WalletClient::new(BinanceRestClient::build_client(Config::new_default()));
+            WalletClient::new(BinanceRestClient::build_client(Config::new_default())).unwrap();
         static ref CERTIFICATE: Certificate = Certificate::new(
             "",
             ""
@@ -110,4 +123,14 @@ mod tests {
         let resp = CLIENT.get_withdraw_history(req, CERTIFICATE.clone()).await.unwrap();
         println!("{:?}", resp);
     }
+
+    #[tokio::test]
+    async fn test_new_without_wallet_endpoint() {
+        let mut config = Config::new_default();
+        config.set_environment(Environment::testnet());
+        assert!(matches!(
+            WalletClient::new(BinanceRestClient::build_client(config)),
+            Err(SdkError::ParameterError(_))
+        ));
+    }
 }
@@ -0,0 +1 @@
+pub mod spot_api;
@@ -0,0 +1,130 @@
+use crate::userdata::types::event_type::UserDataEventPayload;
+use client::rest::layer::authorization::types::Certificate;
+use client::stream::lifecycle::{SocketLifecycle, SocketLifecycleEvent};
+use client::stream::payload::SocketPayloadActor;
+use client::stream::stream::SocketPayloadProcess;
+use client::wsapi::client::WebsocketApiClient;
+use client::wsapi::message::{WsApiEvent, WsApiSessionStatus};
+use futures_util::StreamExt;
+use general::error::SdkError;
+use general::result::BinanceResult;
+use rest_api::types::account::information::{AccountInformationReq, AccountInformationResp};
+use rest_api::types::market::order_book::{CommonReq, OrderBookResp};
+use rest_api::types::market::price::{AvgPriceReq, AvgPriceResp, SymbolPriceTickerResp, SymbolReq};
+use rest_api::types::market::time::ServerTimeResp;
+use rest_api::types::order::general::cancel::{CancelOrderReq, CancelOrderResp};
+use rest_api::types::order::general::create::{CreateOrderReq, CreateOrderResp};
+use rest_api::types::order::general::query::{QueryOrderReq, QueryOrderResp};
+use serde_json::Value;
+
+// the spot trading and market data methods of the websocket api, see
+// https://developers.binance.com/docs/binance-spot-api-docs/web-socket-api/general-api-information
+// the requests are the same as the REST ones, the signed ones are signed with the certificate,
+// or only carry a timestamp when the session is logged on with the certificate.
+#[derive(Clone)]
+pub struct SpotWebsocketApiClient {
+    client: WebsocketApiClient,
+}
+
+impl SpotWebsocketApiClient {
+    pub fn new(client: WebsocketApiClient) -> Self {
+        SpotWebsocketApiClient { client }
+    }
+
+    pub fn get_client(&self) -> &WebsocketApiClient {
+        &self.client
+    }
+
+    // log on the session with the Ed25519 key of the certificate, it is logged on again after every reconnect
+    pub async fn logon(&self, certificate: Certificate) -> BinanceResult<WsApiSessionStatus> {
+        self.client.logon(certificate).await
+    }
+
+    pub async fn get_session_status(&self) -> BinanceResult<WsApiSessionStatus> {
+        self.client.session_status().await
+    }
+
+    pub async fn logout(&self) -> BinanceResult<WsApiSessionStatus> {
+        self.client.logout().await
+    }
+
+    // deliver the user data events of the logged on session to process, the same events as the listen key stream.
+    // the subscription is renewed after every reconnect, a SocketLifecycle::Reconnecting event tells that events may be lost.
+    // the session has a single connection, the connection id of its lifecycle events is 0.
+    pub async fn subscribe_user_data<P>(&self, mut process: P) -> BinanceResult<()>
+    where
+        P: SocketPayloadProcess<UserDataEventPayload> + Send + 'static,
+    {
+        let mut event_receiver = self.client.subscribe_user_data().await?;
+        // the items of the stream are the BinanceResult every payload processor takes
+        #[allow(clippy::result_large_err)]
+        let stream = futures_util::stream::poll_fn(move |cx| event_receiver.poll_recv(cx)).map(|event| match event {
+            WsApiEvent::Payload(event) => serde_json::from_value::<UserDataEventPayload>(event)
+                .map(SocketPayloadActor::Payload)
+                .map_err(SdkError::JSONDeserialize),
+            WsApiEvent::Reconnect { attempt, backoff } => Ok(SocketPayloadActor::Lifecycle(SocketLifecycleEvent::new(
+                0,
+                SocketLifecycle::Reconnecting { attempt, backoff },
+                format!("reconnect attempt {} after {:?}", attempt, backoff),
+                None,
+            ))),
+            WsApiEvent::Close => Ok(SocketPayloadActor::Close(1)),
+        });
+        tokio::spawn(async move { process.process(Box::pin(stream)).await });
+        Ok(())
+    }
+
+    // stop the user data events, the stream of the processor ends
+    pub async fn unsubscribe_user_data(&self) -> BinanceResult<()> {
+        self.client.unsubscribe_user_data().await
+    }
+
+    pub async fn ping(&self) -> BinanceResult<()> {
+        self.client.request::<_, Value>("ping", &()).await.map(|_| ())
+    }
+
+    pub async fn get_server_time(&self) -> BinanceResult<ServerTimeResp> {
+        self.client.request("time", &()).await
+    }
+
+    pub async fn get_order_book(&self, request: CommonReq) -> BinanceResult<OrderBookResp> {
+        self.client.request("depth", &request).await
+    }
+
+    pub async fn get_avg_price(&self, request: AvgPriceReq) -> BinanceResult<AvgPriceResp> {
+        self.client.request("avgPrice", &request).await
+    }
+
+    pub async fn get_symbol_price_ticker(&self, request: SymbolReq) -> BinanceResult<SymbolPriceTickerResp> {
+        if !request.symbols_is_none() || request.symbol_is_none() {
+            return Err(SdkError::ParameterError(
+                "params symbols must be none, and param symbol need a value".to_string(),
+            ));
+        }
+        self.client.request("ticker.price", &request).await
+    }
+
+    pub async fn create_order(&self, request: CreateOrderReq, certificate: Certificate) -> BinanceResult<CreateOrderResp> {
+        self.client.signed_request("order.place", &request, &certificate).await
+    }
+
+    pub async fn cancel_order(&self, request: CancelOrderReq, certificate: Certificate) -> BinanceResult<CancelOrderResp> {
+        self.client.signed_request("order.cancel", &request, &certificate).await
+    }
+
+    pub async fn get_order(&self, request: QueryOrderReq, certificate: Certificate) -> BinanceResult<QueryOrderResp> {
+        self.client.signed_request("order.status", &request, &certificate).await
+    }
+
+    pub async fn get_account_information(
+        &self,
+        request: AccountInformationReq,
+        certificate: Certificate,
+    ) -> BinanceResult<AccountInformationResp> {
+        self.client.signed_request("account.status", &request, &certificate).await
+    }
+
+    pub async fn close(&self) {
+        self.client.close().await
+    }
+}
@@ -1,7 +1,10 @@
 pub use client::stream;
 pub use general::*;
+pub mod api;
 pub mod market;
 pub mod spot_market_socket_ct;
 pub mod spot_user_data_stream_ct;
+pub mod spot_websocket_api_ct;
 pub mod usd_future_market_socket_ct;
+pub mod usd_future_user_data_stream_ct;
 pub mod userdata;
@@ -1,9 +1,10 @@
 use crate::market::types::agg_trade::{AggTradeStream, AggTradeStreamPayload};
 use async_trait::async_trait;
 use client::stream::adaptor::BinanceWebsocketAdaptor;
-use client::stream::client::WebsocketClient;
+use client::stream::pool::WebsocketPool;
 use client::stream::payload::SocketPayloadActor;
 use client::stream::stream::SocketPayloadProcess;
+use client::stream::config::SocketConfig;
 use futures_util::Stream;
 use general::result::BinanceResult;
 use general::symbol::Symbol;
@@ -13,7 +14,14 @@ pub type AggTradeResponseStream =
     Pin<Box<dyn Stream<Item = BinanceResult<SocketPayloadActor<AggTradeStreamPayload>>> + Send>>;
 
 pub struct AggTradeClient {
-    websocket_client: WebsocketClient<AggTradeStream>,
+    websocket_client: WebsocketPool<AggTradeStream, AggTradeStreamPayload>,
+}
+
+impl AggTradeClient {
+    // the number of payloads dropped by the backpressure policy of the payload buffer
+    pub fn get_dropped_count(&self) -> u64 {
+        self.websocket_client.get_dropped_count()
+    }
 }
 
 #[async_trait]
@@ -22,33 +30,30 @@ impl BinanceWebsocketAdaptor for AggTradeClient {
     type INPUT = Symbol;
     type OUTPUT = AggTradeStreamPayload;
 
-    async fn create_client<P>(process: P, uri: &str) -> Self::CLIENT
+    async fn create_client<P>(process: P, uri: &str, socket_config: SocketConfig) -> BinanceResult<Self::CLIENT>
     where
         P: SocketPayloadProcess<Self::OUTPUT> + Send + 'static ,
     {
         let (client, payload_receiver) =
-            WebsocketClient::<AggTradeStream>::new_with_uri::<AggTradeStreamPayload>(uri).await;
-        let trade_stream = Box::pin(tokio_stream::wrappers::UnboundedReceiverStream::new(
-            payload_receiver,
-        ));
+            WebsocketPool::<AggTradeStream, AggTradeStreamPayload>::new_with_config(uri, socket_config).await?;
+        let trade_stream = Box::pin(payload_receiver);
         tokio::spawn(agg_trade_payload_process(trade_stream, process));
-        AggTradeClient {
+        Ok(AggTradeClient {
             websocket_client: client,
-        }
+        })
     }
 
     async fn close(self) {
         self.websocket_client.close().await;
     }
 
-    async fn subscribe_item(&mut self, input: Self::INPUT) {
+    async fn subscribe_item(&mut self, input: Self::INPUT) -> BinanceResult<()> {
         self.websocket_client
             .subscribe_single(AggTradeStream::new(input))
             .await
-            .unwrap();
     }
 
-    async fn subscribe_items(&mut self, input: Vec<Self::INPUT>) {
+    async fn subscribe_items(&mut self, input: Vec<Self::INPUT>) -> BinanceResult<()> {
         let params = input
             .into_iter()
             .map(|item| AggTradeStream::new(item))
@@ -56,17 +61,15 @@ impl BinanceWebsocketAdaptor for AggTradeClient {
         self.websocket_client
             .subscribe_multiple(params)
             .await
-            .unwrap()
     }
 
-    async fn unsubscribe_item(&mut self, input: Self::INPUT) {
+    async fn unsubscribe_item(&mut self, input: Self::INPUT) -> BinanceResult<()> {
         self.websocket_client
             .unsubscribe_single(AggTradeStream::new(input))
             .await
-            .unwrap();
     }
 
-    async fn unsubscribe_items(&mut self, input: Vec<Self::INPUT>) {
+    async fn unsubscribe_items(&mut self, input: Vec<Self::INPUT>) -> BinanceResult<()> {
         let params = input
             .into_iter()
             .map(|symbol| AggTradeStream::new(symbol))
@@ -74,7 +77,6 @@ impl BinanceWebsocketAdaptor for AggTradeClient {
         self.websocket_client
             .unsubscribe_multiple(params)
             .await
-            .unwrap();
     }
 
     fn get_subscribe_items(&self) -> Vec<Self::INPUT> {
@@ -1,9 +1,10 @@
 use crate::market::types::average_price::{AveragePricePayload, AveragePriceStream};
 use async_trait::async_trait;
 use client::stream::adaptor::BinanceWebsocketAdaptor;
-use client::stream::client::WebsocketClient;
+use client::stream::pool::WebsocketPool;
 use client::stream::payload::SocketPayloadActor;
 use client::stream::stream::SocketPayloadProcess;
+use client::stream::config::SocketConfig;
 use futures_util::Stream;
 use general::result::BinanceResult;
 use general::symbol::Symbol;
@@ -13,7 +14,14 @@ pub type AvgPriceResponseStream =
     Pin<Box<dyn Stream<Item = BinanceResult<SocketPayloadActor<AveragePricePayload>>> + Send>>;
 
 pub struct AveragePriceClient {
-    websocket_client: WebsocketClient<AveragePriceStream>,
+    websocket_client: WebsocketPool<AveragePriceStream, AveragePricePayload>,
+}
+
+impl AveragePriceClient {
+    // the number of payloads dropped by the backpressure policy of the payload buffer
+    pub fn get_dropped_count(&self) -> u64 {
+        self.websocket_client.get_dropped_count()
+    }
 }
 
 #[async_trait]
@@ -22,20 +30,18 @@ impl BinanceWebsocketAdaptor for AveragePriceClient {
     type INPUT = Symbol;
     type OUTPUT = AveragePricePayload;
 
-    async fn create_client<P>(process: P, uri: &str) -> Self::CLIENT
+    async fn create_client<P>(process: P, uri: &str, socket_config: SocketConfig) -> BinanceResult<Self::CLIENT>
     where
         P: SocketPayloadProcess<Self::OUTPUT> + Send + 'static ,
     {
 
         let (client, payload_receiver)
-            = WebsocketClient::<AveragePriceStream>::new_with_uri::<AveragePricePayload>(uri).await;
-        let trade_stream = Box::pin(tokio_stream::wrappers::UnboundedReceiverStream::new(
-            payload_receiver,
-        ));
+            = WebsocketPool::<AveragePriceStream, AveragePricePayload>::new_with_config(uri, socket_config).await?;
+        let trade_stream = Box::pin(payload_receiver);
         tokio::spawn(avg_price_payload_process(trade_stream, process));
-        AveragePriceClient {
+        Ok(AveragePriceClient {
             websocket_client: client,
-        }
+        })
     }
 
 
@@ -43,14 +49,13 @@ impl BinanceWebsocketAdaptor for AveragePriceClient {
         self.websocket_client.close().await;
     }
 
-    async fn subscribe_item(&mut self, input: Self::INPUT) {
+    async fn subscribe_item(&mut self, input: Self::INPUT) -> BinanceResult<()> {
         self.websocket_client
             .subscribe_single(AveragePriceStream::new(input))
             .await
-            .unwrap();
     }
 
-    async fn subscribe_items(&mut self, input: Vec<Self::INPUT>) {
+    async fn subscribe_items(&mut self, input: Vec<Self::INPUT>) -> BinanceResult<()> {
         let params = input
             .into_iter()
             .map(|item| AveragePriceStream::new(item))
@@ -58,17 +63,15 @@ impl BinanceWebsocketAdaptor for AveragePriceClient {
         self.websocket_client
             .subscribe_multiple(params)
             .await
-            .unwrap()
     }
 
-    async fn unsubscribe_item(&mut self, input: Self::INPUT) {
+    async fn unsubscribe_item(&mut self, input: Self::INPUT) -> BinanceResult<()> {
         self.websocket_client
             .unsubscribe_single(AveragePriceStream::new(input))
             .await
-            .unwrap();
     }
 
-    async fn unsubscribe_items(&mut self, input: Vec<Self::INPUT>) {
+    async fn unsubscribe_items(&mut self, input: Vec<Self::INPUT>) -> BinanceResult<()> {
         let params = input
             .into_iter()
             .map(|symbol| AveragePriceStream::new(symbol))
@@ -76,7 +79,6 @@ impl BinanceWebsocketAdaptor for AveragePriceClient {
         self.websocket_client
             .unsubscribe_multiple(params)
             .await
-            .unwrap();
     }
 
     fn get_subscribe_items(&self) -> Vec<Self::INPUT> {
@@ -0,0 +1,88 @@
+use crate::market::types::combined::{MarketStream, MarketStreamPayload};
+use async_trait::async_trait;
+use client::stream::adaptor::BinanceWebsocketAdaptor;
+use client::stream::pool::WebsocketPool;
+use client::stream::payload::{CombinedStreamPayload, SocketPayloadActor};
+use client::stream::stream::SocketPayloadProcess;
+use client::stream::config::SocketConfig;
+use futures_util::Stream;
+use general::result::BinanceResult;
+use std::pin::Pin;
+
+pub type CombinedMarketPayload = CombinedStreamPayload<MarketStream, MarketStreamPayload>;
+
+pub type CombinedMarketResponseStream =
+    Pin<Box<dyn Stream<Item = BinanceResult<SocketPayloadActor<CombinedMarketPayload>>> + Send>>;
+
+// a single combined stream connection carrying trades, depth, klines and tickers of many symbols,
+// every payload is delivered with the MarketStream it came from,
+// use client::stream::router::CombinedStreamRouter as processor to handle every stream separately.
+pub struct CombinedMarketClient {
+    websocket_client: WebsocketPool<MarketStream, CombinedMarketPayload>,
+}
+
+impl CombinedMarketClient {
+    // the number of payloads dropped by the backpressure policy of the payload buffer
+    pub fn get_dropped_count(&self) -> u64 {
+        self.websocket_client.get_dropped_count()
+    }
+}
+
+#[async_trait]
+impl BinanceWebsocketAdaptor for CombinedMarketClient {
+    type CLIENT = CombinedMarketClient;
+    type INPUT = MarketStream;
+    type OUTPUT = CombinedMarketPayload;
+
+    async fn create_client<P>(process: P, uri: &str, socket_config: SocketConfig) -> BinanceResult<Self::CLIENT>
+    where
+        P: SocketPayloadProcess<Self::OUTPUT> + Send + 'static,
+    {
+        let (client, payload_receiver) =
+            WebsocketPool::<MarketStream, CombinedMarketPayload>::new_with_config(uri, socket_config).await?;
+        let combined_stream = Box::pin(payload_receiver);
+        tokio::spawn(combined_payload_process(combined_stream, process));
+        Ok(CombinedMarketClient {
+            websocket_client: client,
+        })
+    }
+
+    async fn close(self) {
+        self.websocket_client.close().await
+    }
+
+    async fn subscribe_item(&mut self, input: Self::INPUT) -> BinanceResult<()> {
+        self.websocket_client.subscribe_single(input).await
+    }
+
+    async fn subscribe_items(&mut self, input: Vec<Self::INPUT>) -> BinanceResult<()> {
+        self.websocket_client.subscribe_multiple(input).await
+    }
+
+    async fn unsubscribe_item(&mut self, input: Self::INPUT) -> BinanceResult<()> {
+        self.websocket_client.unsubscribe_single(input).await
+    }
+
+    async fn unsubscribe_items(&mut self, input: Vec<Self::INPUT>) -> BinanceResult<()> {
+        self.websocket_client
+            .unsubscribe_multiple(input)
+            .await
+    }
+
+    fn get_subscribe_items(&self) -> Vec<Self::INPUT> {
+        self.websocket_client
+            .get_all_subscribers()
+            .iter()
+            .cloned()
+            .collect()
+    }
+}
+
+pub(crate) async fn combined_payload_process<P>(
+    combined_response_stream: CombinedMarketResponseStream,
+    mut processor: P,
+) where
+    P: SocketPayloadProcess<CombinedMarketPayload> + Send + 'static,
+{
+    processor.process(combined_response_stream).await;
+}
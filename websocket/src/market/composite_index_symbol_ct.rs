@@ -2,9 +2,10 @@ use std::pin::Pin;
 use async_trait::async_trait;
 use futures_util::Stream;
 use client::stream::adaptor::BinanceWebsocketAdaptor;
-use client::stream::client::WebsocketClient;
+use client::stream::pool::WebsocketPool;
 use client::stream::payload::SocketPayloadActor;
 use client::stream::stream::SocketPayloadProcess;
+use client::stream::config::SocketConfig;
 use general::result::BinanceResult;
 use general::symbol::Symbol;
 use crate::market::types::composite_index_symbol::{CompositionIndexSymbolStream, CompositionIndexSymbolStreamPayload};
@@ -13,7 +14,14 @@ pub type CompositeIndexSymbolResponseStream =
 Pin<Box<dyn Stream<Item=BinanceResult<SocketPayloadActor<CompositionIndexSymbolStreamPayload>>> + Send>>;
 
 pub struct CompositeIndexSymbolClient {
-    websocket_client: WebsocketClient<CompositionIndexSymbolStream>,
+    websocket_client: WebsocketPool<CompositionIndexSymbolStream, CompositionIndexSymbolStreamPayload>,
+}
+
+impl CompositeIndexSymbolClient {
+    // the number of payloads dropped by the backpressure policy of the payload buffer
+    pub fn get_dropped_count(&self) -> u64 {
+        self.websocket_client.get_dropped_count()
+    }
 }
 #[async_trait]
 impl BinanceWebsocketAdaptor for CompositeIndexSymbolClient {
@@ -21,33 +29,30 @@ impl BinanceWebsocketAdaptor for CompositeIndexSymbolClient {
     type INPUT = Symbol;
     type OUTPUT = CompositionIndexSymbolStreamPayload;
 
-    async fn create_client<P>(process: P, uri: &str) -> Self::CLIENT
+    async fn create_client<P>(process: P, uri: &str, socket_config: SocketConfig) -> BinanceResult<Self::CLIENT>
     where
         P: SocketPayloadProcess<Self::OUTPUT> + Send + 'static
     {
         let (client, payload_receiver) =
-            WebsocketClient::<CompositionIndexSymbolStream>::new_with_uri::<CompositionIndexSymbolStreamPayload>(uri).await;
-        let trade_stream = Box::pin(tokio_stream::wrappers::UnboundedReceiverStream::new(
-            payload_receiver,
-        ));
+            WebsocketPool::<CompositionIndexSymbolStream, CompositionIndexSymbolStreamPayload>::new_with_config(uri, socket_config).await?;
+        let trade_stream = Box::pin(payload_receiver);
         tokio::spawn(composite_index_symbol_payload_process(trade_stream, process));
-        CompositeIndexSymbolClient {
+        Ok(CompositeIndexSymbolClient {
             websocket_client: client,
-        }
+        })
     }
 
     async fn close(self) {
         self.websocket_client.close().await
     }
 
-    async fn subscribe_item(&mut self, input: Self::INPUT) {
+    async fn subscribe_item(&mut self, input: Self::INPUT) -> BinanceResult<()> {
         self.websocket_client
             .subscribe_single(CompositionIndexSymbolStream::new(input))
             .await
-            .unwrap();
     }
 
-    async fn subscribe_items(&mut self, input: Vec<Self::INPUT>) {
+    async fn subscribe_items(&mut self, input: Vec<Self::INPUT>) -> BinanceResult<()> {
         let params = input
             .into_iter()
             .map(|symbol| CompositionIndexSymbolStream::new(symbol))
@@ -55,17 +60,15 @@ impl BinanceWebsocketAdaptor for CompositeIndexSymbolClient {
         self.websocket_client
             .subscribe_multiple(params)
             .await
-            .unwrap()
     }
 
-    async fn unsubscribe_item(&mut self, input: Self::INPUT) {
+    async fn unsubscribe_item(&mut self, input: Self::INPUT) -> BinanceResult<()> {
         self.websocket_client
             .unsubscribe_single(CompositionIndexSymbolStream::new(input))
             .await
-            .unwrap();
     }
 
-    async fn unsubscribe_items(&mut self, input: Vec<Self::INPUT>) {
+    async fn unsubscribe_items(&mut self, input: Vec<Self::INPUT>) -> BinanceResult<()> {
         let params = input
             .into_iter()
             .map(|symbol| CompositionIndexSymbolStream::new(symbol))
@@ -73,7 +76,6 @@ impl BinanceWebsocketAdaptor for CompositeIndexSymbolClient {
         self.websocket_client
             .unsubscribe_multiple(params)
             .await
-            .unwrap();
     }
 
     fn get_subscribe_items(&self) -> Vec<Self::INPUT> {
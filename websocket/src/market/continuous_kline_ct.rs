@@ -1,8 +1,9 @@
 use async_trait::async_trait;
 use client::stream::adaptor::BinanceWebsocketAdaptor;
-use client::stream::client::WebsocketClient;
+use client::stream::pool::WebsocketPool;
 use client::stream::payload::SocketPayloadActor;
 use client::stream::stream::SocketPayloadProcess;
+use client::stream::config::SocketConfig;
 use futures_util::Stream;
 use general::enums::interval::Interval;
 use general::result::BinanceResult;
@@ -15,7 +16,14 @@ pub type CompositionIndexSymbolResponseStream =
 Pin<Box<dyn Stream<Item=BinanceResult<SocketPayloadActor<ContinuousKlineStreamPayload>>> + Send>>;
 
 pub struct ContinuousKlineClient {
-    websocket_client: WebsocketClient<ContinuousKlineStream>,
+    websocket_client: WebsocketPool<ContinuousKlineStream, ContinuousKlineStreamPayload>,
+}
+
+impl ContinuousKlineClient {
+    // the number of payloads dropped by the backpressure policy of the payload buffer
+    pub fn get_dropped_count(&self) -> u64 {
+        self.websocket_client.get_dropped_count()
+    }
 }
 #[async_trait]
 impl BinanceWebsocketAdaptor for ContinuousKlineClient {
@@ -23,19 +31,17 @@ impl BinanceWebsocketAdaptor for ContinuousKlineClient {
     type INPUT = (Symbol, ContractType, Interval);
     type OUTPUT = ContinuousKlineStreamPayload;
 
-    async fn create_client<P>(process: P, uri: &str) -> Self::CLIENT
+    async fn create_client<P>(process: P, uri: &str, socket_config: SocketConfig) -> BinanceResult<Self::CLIENT>
     where
         P: SocketPayloadProcess<Self::OUTPUT> + Send + 'static,
     {
         let (client, payload_receiver) =
-            WebsocketClient::<ContinuousKlineStream>::new_with_uri::<ContinuousKlineStreamPayload>(uri).await;
-        let trade_stream = Box::pin(tokio_stream::wrappers::UnboundedReceiverStream::new(
-            payload_receiver,
-        ));
+            WebsocketPool::<ContinuousKlineStream, ContinuousKlineStreamPayload>::new_with_config(uri, socket_config).await?;
+        let trade_stream = Box::pin(payload_receiver);
         tokio::spawn(continuous_kline_payload_process(trade_stream, process));
-        ContinuousKlineClient {
+        Ok(ContinuousKlineClient {
             websocket_client: client,
-        }
+        })
     }
 
 
@@ -43,14 +49,13 @@ impl BinanceWebsocketAdaptor for ContinuousKlineClient {
         self.websocket_client.close().await
     }
 
-    async fn subscribe_item(&mut self, input: Self::INPUT) {
+    async fn subscribe_item(&mut self, input: Self::INPUT) -> BinanceResult<()> {
         self.websocket_client
             .subscribe_single(ContinuousKlineStream::new(input.0, input.1, input.2))
             .await
-            .unwrap();
     }
 
-    async fn subscribe_items(&mut self, input: Vec<Self::INPUT>) {
+    async fn subscribe_items(&mut self, input: Vec<Self::INPUT>) -> BinanceResult<()> {
         let params = input
             .into_iter()
             .map(|(symbol, contract_type, interval)| ContinuousKlineStream::new(symbol, contract_type, interval))
@@ -58,17 +63,15 @@ impl BinanceWebsocketAdaptor for ContinuousKlineClient {
         self.websocket_client
             .subscribe_multiple(params)
             .await
-            .unwrap()
     }
 
-    async fn unsubscribe_item(&mut self, input: Self::INPUT) {
+    async fn unsubscribe_item(&mut self, input: Self::INPUT) -> BinanceResult<()> {
         self.websocket_client
             .unsubscribe_single(ContinuousKlineStream::new(input.0, input.1, input.2))
             .await
-            .unwrap();
     }
 
-    async fn unsubscribe_items(&mut self, input: Vec<Self::INPUT>) {
+    async fn unsubscribe_items(&mut self, input: Vec<Self::INPUT>) -> BinanceResult<()> {
         let params = input
             .into_iter()
             .map(|(symbol, contract_type, interval)| ContinuousKlineStream::new(symbol, contract_type, interval))
@@ -76,7 +79,6 @@ impl BinanceWebsocketAdaptor for ContinuousKlineClient {
         self.websocket_client
             .unsubscribe_multiple(params)
             .await
-            .unwrap();
     }
 
     fn get_subscribe_items(&self) -> Vec<Self::INPUT> {
@@ -1,8 +1,9 @@
 use async_trait::async_trait;
 use client::stream::adaptor::BinanceWebsocketAdaptor;
-use client::stream::client::WebsocketClient;
+use client::stream::pool::WebsocketPool;
 use client::stream::payload::SocketPayloadActor;
 use client::stream::stream::SocketPayloadProcess;
+use client::stream::config::SocketConfig;
 use futures_util::Stream;
 use general::result::BinanceResult;
 use std::pin::Pin;
@@ -12,7 +13,14 @@ pub type ContractInfoResponseStream =
     Pin<Box<dyn Stream<Item = BinanceResult<SocketPayloadActor<ContractInfoStreamPayload>>> + Send>>;
 
 pub struct ContractInfoClient {
-    websocket_client: WebsocketClient<ContractInfoStream>,
+    websocket_client: WebsocketPool<ContractInfoStream, ContractInfoStreamPayload>,
+}
+
+impl ContractInfoClient {
+    // the number of payloads dropped by the backpressure policy of the payload buffer
+    pub fn get_dropped_count(&self) -> u64 {
+        self.websocket_client.get_dropped_count()
+    }
 }
 #[async_trait]
 impl BinanceWebsocketAdaptor for ContractInfoClient
@@ -21,51 +29,45 @@ impl BinanceWebsocketAdaptor for ContractInfoClient
     type INPUT = ContractInfoStream;
     type OUTPUT = ContractInfoStreamPayload;
 
-    async fn create_client<P>(process: P, uri: &str) -> Self::CLIENT
+    async fn create_client<P>(process: P, uri: &str, socket_config: SocketConfig) -> BinanceResult<Self::CLIENT>
     where
         P: SocketPayloadProcess<Self::OUTPUT> + Send + 'static ,
     {
         let (client, payload_receiver) =
-            WebsocketClient::<ContractInfoStream>::new_with_uri::<ContractInfoStreamPayload>(uri).await;
-        let trade_stream = Box::pin(tokio_stream::wrappers::UnboundedReceiverStream::new(
-            payload_receiver,
-        ));
+            WebsocketPool::<ContractInfoStream, ContractInfoStreamPayload>::new_with_config(uri, socket_config).await?;
+        let trade_stream = Box::pin(payload_receiver);
         tokio::spawn(contract_info_payload_process(trade_stream, process));
-        ContractInfoClient {
+        Ok(ContractInfoClient {
             websocket_client: client,
-        }
+        })
     }
 
     async fn close(self) {
         self.websocket_client.close().await
     }
 
-    async fn subscribe_item(&mut self, input: Self::INPUT) {
+    async fn subscribe_item(&mut self, input: Self::INPUT) -> BinanceResult<()> {
         self.websocket_client
             .subscribe_single(input)
             .await
-            .unwrap();
     }
 
-    async fn subscribe_items(&mut self, input: Vec<Self::INPUT>) {
+    async fn subscribe_items(&mut self, input: Vec<Self::INPUT>) -> BinanceResult<()> {
         self.websocket_client
             .subscribe_multiple(input)
             .await
-            .unwrap()
     }
 
-    async fn unsubscribe_item(&mut self, input: Self::INPUT) {
+    async fn unsubscribe_item(&mut self, input: Self::INPUT) -> BinanceResult<()> {
         self.websocket_client
             .unsubscribe_single(input)
             .await
-            .unwrap();
     }
 
-    async fn unsubscribe_items(&mut self, input: Vec<Self::INPUT>) {
+    async fn unsubscribe_items(&mut self, input: Vec<Self::INPUT>) -> BinanceResult<()> {
         self.websocket_client
             .unsubscribe_multiple(input)
             .await
-            .unwrap();
     }
 
     fn get_subscribe_items(&self) -> Vec<Self::INPUT> {
@@ -1,9 +1,10 @@
 use crate::market::types::kline::{KlineStream, KlineStreamPayload};
 use async_trait::async_trait;
 use client::stream::adaptor::BinanceWebsocketAdaptor;
-use client::stream::client::WebsocketClient;
+use client::stream::pool::WebsocketPool;
 use client::stream::payload::SocketPayloadActor;
 use client::stream::stream::SocketPayloadProcess;
+use client::stream::config::SocketConfig;
 use futures_util::Stream;
 use general::enums::interval::Interval;
 use general::enums::timezone::Timezone;
@@ -15,7 +16,14 @@ pub type KlineResponseStream =
     Pin<Box<dyn Stream<Item = BinanceResult<SocketPayloadActor<KlineStreamPayload>>> + Send>>;
 
 pub struct KlineClient {
-    websocket_client: WebsocketClient<KlineStream>,
+    websocket_client: WebsocketPool<KlineStream, KlineStreamPayload>,
+}
+
+impl KlineClient {
+    // the number of payloads dropped by the backpressure policy of the payload buffer
+    pub fn get_dropped_count(&self) -> u64 {
+        self.websocket_client.get_dropped_count()
+    }
 }
 #[async_trait]
 impl BinanceWebsocketAdaptor for KlineClient {
@@ -23,19 +31,17 @@ impl BinanceWebsocketAdaptor for KlineClient {
     type INPUT = (Symbol, Interval, Option<Timezone>);
     type OUTPUT = KlineStreamPayload;
 
-    async fn create_client<P>(process: P, uri: &str) -> Self::CLIENT
+    async fn create_client<P>(process: P, uri: &str, socket_config: SocketConfig) -> BinanceResult<Self::CLIENT>
     where
         P: SocketPayloadProcess<Self::OUTPUT> + Send + 'static ,
     {
         let (client, payload_receiver) =
-            WebsocketClient::<KlineStream>::new_with_uri::<KlineStreamPayload>(uri).await;
-        let trade_stream = Box::pin(tokio_stream::wrappers::UnboundedReceiverStream::new(
-            payload_receiver,
-        ));
+            WebsocketPool::<KlineStream, KlineStreamPayload>::new_with_config(uri, socket_config).await?;
+        let trade_stream = Box::pin(payload_receiver);
         tokio::spawn(kline_payload_process(trade_stream, process));
-        KlineClient {
+        Ok(KlineClient {
             websocket_client: client,
-        }
+        })
     }
 
 
@@ -43,14 +49,13 @@ impl BinanceWebsocketAdaptor for KlineClient {
         self.websocket_client.close().await
     }
 
-    async fn subscribe_item(&mut self, input: Self::INPUT) {
+    async fn subscribe_item(&mut self, input: Self::INPUT) -> BinanceResult<()> {
         self.websocket_client
             .subscribe_single(KlineStream::new(input.0, input.1, input.2))
             .await
-            .unwrap();
     }
 
-    async fn subscribe_items(&mut self, input: Vec<Self::INPUT>) {
+    async fn subscribe_items(&mut self, input: Vec<Self::INPUT>) -> BinanceResult<()> {
         let params = input
             .into_iter()
             .map(|(symbol, interval, timezone)| KlineStream::new(symbol, interval, timezone))
@@ -58,17 +63,15 @@ impl BinanceWebsocketAdaptor for KlineClient {
         self.websocket_client
             .subscribe_multiple(params)
             .await
-            .unwrap()
     }
 
-    async fn unsubscribe_item(&mut self, input: Self::INPUT) {
+    async fn unsubscribe_item(&mut self, input: Self::INPUT) -> BinanceResult<()> {
         self.websocket_client
             .unsubscribe_single(KlineStream::new(input.0, input.1, input.2))
             .await
-            .unwrap();
     }
 
-    async fn unsubscribe_items(&mut self, input: Vec<Self::INPUT>) {
+    async fn unsubscribe_items(&mut self, input: Vec<Self::INPUT>) -> BinanceResult<()> {
         let params = input
             .into_iter()
             .map(|(symbol, interval, timezone)| KlineStream::new(symbol, interval, timezone))
@@ -76,7 +79,6 @@ impl BinanceWebsocketAdaptor for KlineClient {
         self.websocket_client
             .unsubscribe_multiple(params)
             .await
-            .unwrap();
     }
 
     fn get_subscribe_items(&self) -> Vec<Self::INPUT> {
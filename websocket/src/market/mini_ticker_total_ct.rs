@@ -1,9 +1,10 @@
 use crate::market::types::symbol_mini_ticker::{TotalSymbolMiniTickerPayload, TotalSymbolMiniTickerStream};
 use async_trait::async_trait;
 use client::stream::adaptor::BinanceWebsocketAdaptor;
-use client::stream::client::WebsocketClient;
+use client::stream::pool::WebsocketPool;
 use client::stream::payload::SocketPayloadActor;
 use client::stream::stream::SocketPayloadProcess;
+use client::stream::config::SocketConfig;
 use futures_util::Stream;
 use general::result::BinanceResult;
 use std::pin::Pin;
@@ -12,7 +13,14 @@ pub type TotalMiniTickerResponseStream =
     Pin<Box<dyn Stream<Item = BinanceResult<SocketPayloadActor<TotalSymbolMiniTickerPayload>>> + Send>>;
 
 pub struct TotalSymbolMiniTickerClient {
-    websocket_client: WebsocketClient<TotalSymbolMiniTickerStream>,
+    websocket_client: WebsocketPool<TotalSymbolMiniTickerStream, TotalSymbolMiniTickerPayload>,
+}
+
+impl TotalSymbolMiniTickerClient {
+    // the number of payloads dropped by the backpressure policy of the payload buffer
+    pub fn get_dropped_count(&self) -> u64 {
+        self.websocket_client.get_dropped_count()
+    }
 }
 #[async_trait]
 impl BinanceWebsocketAdaptor for TotalSymbolMiniTickerClient {
@@ -20,19 +28,17 @@ impl BinanceWebsocketAdaptor for TotalSymbolMiniTickerClient {
     type INPUT = TotalSymbolMiniTickerStream;
     type OUTPUT = TotalSymbolMiniTickerPayload;
 
-    async fn create_client<P>(process: P, uri: &str) -> Self::CLIENT
+    async fn create_client<P>(process: P, uri: &str, socket_config: SocketConfig) -> BinanceResult<Self::CLIENT>
     where
         P: SocketPayloadProcess<Self::OUTPUT> + Send + 'static ,
     {
         let (client, payload_receiver) =
-            WebsocketClient::<TotalSymbolMiniTickerStream>::new_with_uri::<TotalSymbolMiniTickerPayload>(uri).await;
-        let trade_stream = Box::pin(tokio_stream::wrappers::UnboundedReceiverStream::new(
-            payload_receiver,
-        ));
+            WebsocketPool::<TotalSymbolMiniTickerStream, TotalSymbolMiniTickerPayload>::new_with_config(uri, socket_config).await?;
+        let trade_stream = Box::pin(payload_receiver);
         tokio::spawn(all_symbol_mini_ticker_payload_process(trade_stream, process));
-        TotalSymbolMiniTickerClient {
+        Ok(TotalSymbolMiniTickerClient {
             websocket_client: client,
-        }
+        })
     }
 
 
@@ -40,32 +46,28 @@ impl BinanceWebsocketAdaptor for TotalSymbolMiniTickerClient {
         self.websocket_client.close().await
     }
 
-    async fn subscribe_item(&mut self, input: Self::INPUT) {
+    async fn subscribe_item(&mut self, input: Self::INPUT) -> BinanceResult<()> {
         self.websocket_client
             .subscribe_single(input)
             .await
-            .unwrap();
     }
 
-    async fn subscribe_items(&mut self, input: Vec<Self::INPUT>) {
+    async fn subscribe_items(&mut self, input: Vec<Self::INPUT>) -> BinanceResult<()> {
         self.websocket_client
             .subscribe_multiple(input)
             .await
-            .unwrap()
     }
 
-    async fn unsubscribe_item(&mut self, input: Self::INPUT) {
+    async fn unsubscribe_item(&mut self, input: Self::INPUT) -> BinanceResult<()> {
         self.websocket_client
             .unsubscribe_single(input)
             .await
-            .unwrap();
     }
 
-    async fn unsubscribe_items(&mut self, input: Vec<Self::INPUT>) {
+    async fn unsubscribe_items(&mut self, input: Vec<Self::INPUT>) -> BinanceResult<()> {
         self.websocket_client
             .unsubscribe_multiple(input)
             .await
-            .unwrap();
     }
 
     fn get_subscribe_items(&self) -> Vec<Self::INPUT> {
@@ -20,4 +20,5 @@ pub mod liquidation_order_ct;
 pub mod partial_depth_ct;
 pub mod composite_index_symbol_ct;
 pub mod contract_info_ct;
-pub mod rolling_total_ct;
\ No newline at end of file
+pub mod rolling_total_ct;pub mod combined_ct;
+pub mod order_book_ct;
@@ -0,0 +1,331 @@
+use crate::market::types::depth::DepthStreamPayload;
+use crate::market::types::order_book::{LocalOrderBook, OrderBookMarket};
+use async_trait::async_trait;
+use client::rest::client::{BinanceClient, BinanceClientAction};
+use client::stream::lifecycle::SocketLifecycle;
+use client::stream::payload::SocketPayloadActor;
+use client::stream::stream::SocketPayloadProcess;
+use futures_util::{Stream, StreamExt};
+use general::price_level::PriceLevel;
+use general::result::BinanceResult;
+use rest_api::spot_market_ct::SpotMarketClient;
+use rest_api::types::market::order_book::{CommonReq, OrderBookResp};
+use rest_api::usd_future_market_ct::UsdFutureMarketClient;
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+
+// the diff depth events buffered for a symbol while its snapshot is fetched, the oldest events are dropped beyond it
+const MAX_BUFFERED_EVENTS: usize = 10_000;
+// the delay before a snapshot is fetched again after a failure
+const SNAPSHOT_RETRY_DELAY: Duration = Duration::from_secs(1);
+const CHANGE_CHANNEL_CAPACITY: usize = 1024;
+
+// the REST order book snapshot of a market, it decides the sequencing rule of the diff depth events
+#[async_trait]
+pub trait OrderBookSnapshotProvider: Send + Sync + 'static {
+    fn market(&self) -> OrderBookMarket;
+
+    async fn get_snapshot(&self, symbol: &str, limit: u16) -> BinanceResult<OrderBookResp>;
+}
+
+#[async_trait]
+impl<T> OrderBookSnapshotProvider for SpotMarketClient<T>
+where
+    T: BinanceClient + BinanceClientAction + Send + Sync + 'static,
+{
+    fn market(&self) -> OrderBookMarket {
+        OrderBookMarket::Spot
+    }
+
+    async fn get_snapshot(&self, symbol: &str, limit: u16) -> BinanceResult<OrderBookResp> {
+        self.get_order_book(CommonReq::new(symbol, limit)).await
+    }
+}
+
+#[async_trait]
+impl<T> OrderBookSnapshotProvider for UsdFutureMarketClient<T>
+where
+    T: BinanceClient + BinanceClientAction + Send + Sync + 'static,
+{
+    fn market(&self) -> OrderBookMarket {
+        OrderBookMarket::UsdFuture
+    }
+
+    async fn get_snapshot(&self, symbol: &str, limit: u16) -> BinanceResult<OrderBookResp> {
+        self.order_book(CommonReq::new(symbol, limit)).await
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum OrderBookChangeKind {
+    // the book is built from a snapshot, the first one or after a gap
+    Synced,
+    // a diff depth event is applied
+    Updated,
+    // a gap or a reconnect is detected, the book is unavailable until the next snapshot
+    Desynced,
+}
+
+#[derive(Debug, Clone)]
+pub struct OrderBookChange {
+    pub symbol: String,
+    pub kind: OrderBookChangeKind,
+    pub last_update_id: u64,
+}
+
+// the best levels of a book
+#[derive(Debug, Clone)]
+pub struct OrderBookDepth {
+    pub last_update_id: u64,
+    pub bids: Vec<PriceLevel>,
+    pub asks: Vec<PriceLevel>,
+}
+
+// OrderBookHandle reads the books maintained by an OrderBookManager, only synced books are visible.
+// symbols are matched case-insensitively.
+#[derive(Clone)]
+pub struct OrderBookHandle {
+    books: Arc<RwLock<HashMap<String, LocalOrderBook>>>,
+    change_sender: broadcast::Sender<OrderBookChange>,
+}
+
+impl OrderBookHandle {
+    fn new() -> Self {
+        let (change_sender, _) = broadcast::channel(CHANGE_CHANNEL_CAPACITY);
+        OrderBookHandle {
+            books: Arc::new(RwLock::new(HashMap::new())),
+            change_sender,
+        }
+    }
+
+    fn read<R>(&self, symbol: &str, read: impl FnOnce(&LocalOrderBook) -> R) -> Option<R> {
+        self.books.read().unwrap().get(&symbol.to_uppercase()).map(read)
+    }
+
+    pub fn is_synced(&self, symbol: &str) -> bool {
+        self.read(symbol, |_| ()).is_some()
+    }
+
+    pub fn get_best_bid(&self, symbol: &str) -> Option<PriceLevel> {
+        self.read(symbol, LocalOrderBook::best_bid).flatten()
+    }
+
+    pub fn get_best_ask(&self, symbol: &str) -> Option<PriceLevel> {
+        self.read(symbol, LocalOrderBook::best_ask).flatten()
+    }
+
+    // the best `levels` bids and asks of symbol
+    pub fn get_depth(&self, symbol: &str, levels: usize) -> Option<OrderBookDepth> {
+        self.read(symbol, |book| OrderBookDepth {
+            last_update_id: book.get_last_update_id(),
+            bids: book.bids(levels),
+            asks: book.asks(levels),
+        })
+    }
+
+    pub fn get_book(&self, symbol: &str) -> Option<LocalOrderBook> {
+        self.read(symbol, LocalOrderBook::clone)
+    }
+
+    // every change of every book, a slow receiver misses the oldest changes, see broadcast::Receiver
+    pub fn subscribe_changes(&self) -> broadcast::Receiver<OrderBookChange> {
+        self.change_sender.subscribe()
+    }
+
+    fn notify(&self, symbol: &str, kind: OrderBookChangeKind, last_update_id: u64) {
+        let _ = self.change_sender.send(OrderBookChange {
+            symbol: symbol.to_string(),
+            kind,
+            last_update_id,
+        });
+    }
+}
+
+enum SymbolSyncState {
+    // the snapshot of generation is being fetched, the events are buffered until it arrives
+    Syncing {
+        generation: u64,
+        buffer: Vec<DepthStreamPayload>,
+    },
+    Synced,
+}
+
+struct SnapshotResult {
+    symbol: String,
+    generation: u64,
+    result: BinanceResult<OrderBookResp>,
+}
+
+// OrderBookManager is the consumer of a diff depth client, it maintains a local order book of every symbol:
+// 1. the events of a symbol are buffered while its REST snapshot is fetched,
+// 2. the buffered events older than the snapshot are dropped, the others are applied on the snapshot,
+// 3. every following event is applied on the book, a gap in the events, or a reconnect, fetches a new snapshot.
+// the books are read through the OrderBookHandle returned with the manager.
+pub struct OrderBookManager<S> {
+    provider: Arc<S>,
+    limit: u16,
+    handle: OrderBookHandle,
+    states: HashMap<String, SymbolSyncState>,
+    snapshot_sender: UnboundedSender<SnapshotResult>,
+    snapshot_receiver: UnboundedReceiver<SnapshotResult>,
+    next_generation: u64,
+}
+
+impl<S> OrderBookManager<S>
+where
+    S: OrderBookSnapshotProvider,
+{
+    // limit: the depth of the REST snapshot, the book only knows the levels of the snapshot and the levels updated since then
+    pub fn new(provider: S, limit: u16) -> (Self, OrderBookHandle) {
+        let handle = OrderBookHandle::new();
+        let (snapshot_sender, snapshot_receiver) = unbounded_channel();
+        let manager = OrderBookManager {
+            provider: Arc::new(provider),
+            limit,
+            handle: handle.clone(),
+            states: HashMap::new(),
+            snapshot_sender,
+            snapshot_receiver,
+            next_generation: 0,
+        };
+        (manager, handle)
+    }
+
+    fn on_event(&mut self, event: DepthStreamPayload) {
+        let symbol = event.symbol.to_uppercase();
+        match self.states.get_mut(&symbol) {
+            Some(SymbolSyncState::Syncing { buffer, .. }) => {
+                if buffer.len() >= MAX_BUFFERED_EVENTS {
+                    buffer.remove(0);
+                }
+                buffer.push(event);
+            }
+            Some(SymbolSyncState::Synced) => {
+                let mut books = self.handle.books.write().unwrap();
+                let Some(book) = books.get_mut(&symbol) else {
+                    drop(books);
+                    self.start_sync(symbol, vec![event], Duration::ZERO);
+                    return;
+                };
+                match book.apply(&event) {
+                    Ok(true) => {
+                        let last_update_id = book.get_last_update_id();
+                        drop(books);
+                        self.handle.notify(&symbol, OrderBookChangeKind::Updated, last_update_id);
+                    }
+                    Ok(false) => {}
+                    Err(gap) => {
+                        log::warn!("Order book of {} has a gap: {}, fetch a new snapshot", symbol, gap);
+                        books.remove(&symbol);
+                        drop(books);
+                        self.handle.notify(&symbol, OrderBookChangeKind::Desynced, gap.last_update_id);
+                        self.start_sync(symbol, vec![event], Duration::ZERO);
+                    }
+                }
+            }
+            None => self.start_sync(symbol, vec![event], Duration::ZERO),
+        }
+    }
+
+    fn start_sync(&mut self, symbol: String, buffer: Vec<DepthStreamPayload>, delay: Duration) {
+        self.next_generation += 1;
+        let generation = self.next_generation;
+        let provider = self.provider.clone();
+        let limit = self.limit;
+        let snapshot_sender = self.snapshot_sender.clone();
+        let snapshot_symbol = symbol.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(delay).await;
+            let result = provider.get_snapshot(&snapshot_symbol, limit).await;
+            let _ = snapshot_sender.send(SnapshotResult {
+                symbol: snapshot_symbol,
+                generation,
+                result,
+            });
+        });
+        self.states.insert(symbol, SymbolSyncState::Syncing { generation, buffer });
+    }
+
+    fn on_snapshot(&mut self, snapshot: SnapshotResult) {
+        let SnapshotResult {
+            symbol,
+            generation,
+            result,
+        } = snapshot;
+        let buffer = match self.states.get_mut(&symbol) {
+            Some(SymbolSyncState::Syncing {
+                generation: expected,
+                buffer,
+            }) if *expected == generation => std::mem::take(buffer),
+            // the symbol was resynced again while this snapshot was fetched
+            _ => return,
+        };
+        let snapshot = match result {
+            Ok(snapshot) => snapshot,
+            Err(err) => {
+                log::error!("Failed to fetch order book snapshot of {}: {}", symbol, err);
+                self.start_sync(symbol, buffer, SNAPSHOT_RETRY_DELAY);
+                return;
+            }
+        };
+        let mut book = LocalOrderBook::from_snapshot(&symbol, self.provider.market(), &snapshot);
+        for (index, event) in buffer.iter().enumerate() {
+            if let Err(gap) = book.apply(event) {
+                // either the snapshot is older than the buffered events, or the events have a gap,
+                // the events from the failing one on are kept for the next snapshot.
+                log::warn!("Order book snapshot of {} does not match the events: {}", symbol, gap);
+                let buffer = buffer.into_iter().skip(index).collect();
+                self.start_sync(symbol, buffer, SNAPSHOT_RETRY_DELAY);
+                return;
+            }
+        }
+        let last_update_id = book.get_last_update_id();
+        self.handle.books.write().unwrap().insert(symbol.clone(), book);
+        self.states.insert(symbol.clone(), SymbolSyncState::Synced);
+        self.handle.notify(&symbol, OrderBookChangeKind::Synced, last_update_id);
+    }
+
+    // events may be lost while the connection is down, every book is rebuilt from the next event
+    fn desync_all(&mut self) {
+        self.states.clear();
+        let books = std::mem::take(&mut *self.handle.books.write().unwrap());
+        for (symbol, book) in books {
+            self.handle.notify(&symbol, OrderBookChangeKind::Desynced, book.get_last_update_id());
+        }
+    }
+}
+
+#[async_trait]
+impl<S> SocketPayloadProcess<DepthStreamPayload> for OrderBookManager<S>
+where
+    S: OrderBookSnapshotProvider,
+{
+    async fn process(
+        &mut self,
+        mut stream: Pin<Box<dyn Stream<Item = BinanceResult<SocketPayloadActor<DepthStreamPayload>>> + Send>>,
+    ) {
+        loop {
+            tokio::select! {
+                data = stream.next() => match data {
+                    Some(Ok(SocketPayloadActor::Payload(event))) => self.on_event(event),
+                    Some(Ok(SocketPayloadActor::Close(_))) => self.desync_all(),
+                    // the events between the lost connection and the new one are missing
+                    Some(Ok(SocketPayloadActor::Lifecycle(event)))
+                        if matches!(event.lifecycle, SocketLifecycle::Reconnecting { .. }) =>
+                    {
+                        self.desync_all()
+                    }
+                    Some(Ok(SocketPayloadActor::Lifecycle(_))) => {}
+                    Some(Err(e)) => log::error!("Accept depth payload error: error message is: {}", e),
+                    None => break,
+                },
+                Some(snapshot) = self.snapshot_receiver.recv() => self.on_snapshot(snapshot),
+            }
+        }
+        self.desync_all();
+    }
+}
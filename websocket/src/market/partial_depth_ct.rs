@@ -1,9 +1,10 @@
 use crate::market::types::depth::DepthStreamPayload;
 use async_trait::async_trait;
 use client::stream::adaptor::BinanceWebsocketAdaptor;
-use client::stream::client::WebsocketClient;
+use client::stream::pool::WebsocketPool;
 use client::stream::payload::SocketPayloadActor;
 use client::stream::stream::SocketPayloadProcess;
+use client::stream::config::SocketConfig;
 use futures_util::Stream;
 use general::enums::speed::Speed;
 use general::result::BinanceResult;
@@ -16,7 +17,14 @@ pub type PartialDepthResponseStream =
     Pin<Box<dyn Stream<Item = BinanceResult<SocketPayloadActor<DepthStreamPayload>>> + Send>>;
 
 pub struct PartialDepthClient {
-    websocket_client: WebsocketClient<BookDepthStream>,
+    websocket_client: WebsocketPool<BookDepthStream, DepthStreamPayload>,
+}
+
+impl PartialDepthClient {
+    // the number of payloads dropped by the backpressure policy of the payload buffer
+    pub fn get_dropped_count(&self) -> u64 {
+        self.websocket_client.get_dropped_count()
+    }
 }
 #[async_trait]
 impl BinanceWebsocketAdaptor for PartialDepthClient {
@@ -24,33 +32,30 @@ impl BinanceWebsocketAdaptor for PartialDepthClient {
     type INPUT = (Symbol, Level, Option<Speed>);
     type OUTPUT = DepthStreamPayload;
 
-    async fn create_client<P>(process: P, uri: &str) -> Self::CLIENT
+    async fn create_client<P>(process: P, uri: &str, socket_config: SocketConfig) -> BinanceResult<Self::CLIENT>
     where
         P: SocketPayloadProcess<Self::OUTPUT> + Send + 'static ,
     {
         let (client, payload_receiver) =
-            WebsocketClient::<BookDepthStream>::new_with_uri::<DepthStreamPayload>(uri).await;
-        let trade_stream = Box::pin(tokio_stream::wrappers::UnboundedReceiverStream::new(
-            payload_receiver,
-        ));
+            WebsocketPool::<BookDepthStream, DepthStreamPayload>::new_with_config(uri, socket_config).await?;
+        let trade_stream = Box::pin(payload_receiver);
         tokio::spawn(partial_depth_payload_process(trade_stream, process));
-        PartialDepthClient {
+        Ok(PartialDepthClient {
             websocket_client: client,
-        }
+        })
     }
 
     async fn close(self) {
         self.websocket_client.close().await
     }
 
-    async fn subscribe_item(&mut self, input: Self::INPUT) {
+    async fn subscribe_item(&mut self, input: Self::INPUT) -> BinanceResult<()> {
         self.websocket_client
             .subscribe_single(BookDepthStream::new(input.0, input.1, input.2))
             .await
-            .unwrap();
     }
 
-    async fn subscribe_items(&mut self, input: Vec<Self::INPUT>) {
+    async fn subscribe_items(&mut self, input: Vec<Self::INPUT>) -> BinanceResult<()> {
         let params = input
             .into_iter()
             .map(|(symbol,level, speed)| BookDepthStream::new(symbol, level, speed))
@@ -58,17 +63,15 @@ impl BinanceWebsocketAdaptor for PartialDepthClient {
         self.websocket_client
             .subscribe_multiple(params)
             .await
-            .unwrap()
     }
 
-    async fn unsubscribe_item(&mut self, input: Self::INPUT) {
+    async fn unsubscribe_item(&mut self, input: Self::INPUT) -> BinanceResult<()> {
         self.websocket_client
             .unsubscribe_single(BookDepthStream::new(input.0, input.1, input.2))
             .await
-            .unwrap();
     }
 
-    async fn unsubscribe_items(&mut self, input: Vec<Self::INPUT>) {
+    async fn unsubscribe_items(&mut self, input: Vec<Self::INPUT>) -> BinanceResult<()> {
         let params = input
             .into_iter()
             .map(|(symbol, level, speed)| BookDepthStream::new(symbol, level,  speed))
@@ -76,7 +79,6 @@ impl BinanceWebsocketAdaptor for PartialDepthClient {
         self.websocket_client
             .unsubscribe_multiple(params)
             .await
-            .unwrap();
     }
 
     fn get_subscribe_items(&self) -> Vec<Self::INPUT> {
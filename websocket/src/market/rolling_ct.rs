@@ -1,9 +1,10 @@
 use crate::market::types::symbol_rolling::{SymbolRollingPayload, SymbolRollingWindowStream};
 use async_trait::async_trait;
 use client::stream::adaptor::BinanceWebsocketAdaptor;
-use client::stream::client::WebsocketClient;
+use client::stream::pool::WebsocketPool;
 use client::stream::payload::SocketPayloadActor;
 use client::stream::stream::SocketPayloadProcess;
+use client::stream::config::SocketConfig;
 use futures_util::Stream;
 use general::enums::window_size::WindowSize;
 use general::result::BinanceResult;
@@ -14,7 +15,14 @@ pub type SymbolRollingResponseStream =
     Pin<Box<dyn Stream<Item = BinanceResult<SocketPayloadActor<SymbolRollingPayload>>> + Send>>;
 
 pub struct SymbolRollingClient {
-    websocket_client: WebsocketClient<SymbolRollingWindowStream>,
+    websocket_client: WebsocketPool<SymbolRollingWindowStream, SymbolRollingPayload>,
+}
+
+impl SymbolRollingClient {
+    // the number of payloads dropped by the backpressure policy of the payload buffer
+    pub fn get_dropped_count(&self) -> u64 {
+        self.websocket_client.get_dropped_count()
+    }
 }
 #[async_trait]
 impl BinanceWebsocketAdaptor for SymbolRollingClient {
@@ -22,33 +30,30 @@ impl BinanceWebsocketAdaptor for SymbolRollingClient {
     type INPUT = (Symbol, WindowSize);
     type OUTPUT = SymbolRollingPayload;
 
-    async fn create_client<P>(process: P, uri: &str) -> Self::CLIENT
+    async fn create_client<P>(process: P, uri: &str, socket_config: SocketConfig) -> BinanceResult<Self::CLIENT>
     where
         P: SocketPayloadProcess<Self::OUTPUT> + Send + 'static ,
     {
         let (client, payload_receiver) =
-            WebsocketClient::<SymbolRollingWindowStream>::new_with_uri::<SymbolRollingPayload>(uri).await;
-        let trade_stream = Box::pin(tokio_stream::wrappers::UnboundedReceiverStream::new(
-            payload_receiver,
-        ));
+            WebsocketPool::<SymbolRollingWindowStream, SymbolRollingPayload>::new_with_config(uri, socket_config).await?;
+        let trade_stream = Box::pin(payload_receiver);
         tokio::spawn(symbol_rolling_payload_process(trade_stream, process));
-        SymbolRollingClient {
+        Ok(SymbolRollingClient {
             websocket_client: client,
-        }
+        })
     }
 
     async fn close(self) {
         self.websocket_client.close().await
     }
 
-    async fn subscribe_item(&mut self, input: Self::INPUT) {
+    async fn subscribe_item(&mut self, input: Self::INPUT) -> BinanceResult<()> {
         self.websocket_client
             .subscribe_single(SymbolRollingWindowStream::new(input.0, input.1))
             .await
-            .unwrap();
     }
 
-    async fn subscribe_items(&mut self, input: Vec<Self::INPUT>) {
+    async fn subscribe_items(&mut self, input: Vec<Self::INPUT>) -> BinanceResult<()> {
         let params = input
             .into_iter()
             .map(|(symbol, window_size)| SymbolRollingWindowStream::new(symbol, window_size))
@@ -56,17 +61,15 @@ impl BinanceWebsocketAdaptor for SymbolRollingClient {
         self.websocket_client
             .subscribe_multiple(params)
             .await
-            .unwrap()
     }
 
-    async fn unsubscribe_item(&mut self, input: Self::INPUT) {
+    async fn unsubscribe_item(&mut self, input: Self::INPUT) -> BinanceResult<()> {
         self.websocket_client
             .unsubscribe_single(SymbolRollingWindowStream::new(input.0, input.1))
             .await
-            .unwrap();
     }
 
-    async fn unsubscribe_items(&mut self, input: Vec<Self::INPUT>) {
+    async fn unsubscribe_items(&mut self, input: Vec<Self::INPUT>) -> BinanceResult<()> {
         let params = input
             .into_iter()
             .map(|(symbol, interval)| SymbolRollingWindowStream::new(symbol, interval))
@@ -74,7 +77,6 @@ impl BinanceWebsocketAdaptor for SymbolRollingClient {
         self.websocket_client
             .unsubscribe_multiple(params)
             .await
-            .unwrap();
     }
 
     fn get_subscribe_items(&self) -> Vec<Self::INPUT> {
@@ -1,9 +1,10 @@
 use crate::market::types::symbol_rolling::{TotalSymbolRollingPayload, TotalSymbolRollingStream};
 use async_trait::async_trait;
 use client::stream::adaptor::BinanceWebsocketAdaptor;
-use client::stream::client::WebsocketClient;
+use client::stream::pool::WebsocketPool;
 use client::stream::payload::SocketPayloadActor;
 use client::stream::stream::SocketPayloadProcess;
+use client::stream::config::SocketConfig;
 use futures_util::Stream;
 use general::enums::window_size::WindowSize;
 use general::result::BinanceResult;
@@ -13,7 +14,14 @@ pub type TotalSymbolRollingResponseStream =
     Pin<Box<dyn Stream<Item = BinanceResult<SocketPayloadActor<TotalSymbolRollingPayload>>> + Send>>;
 
 pub struct TotalSymbolRollingClient {
-    websocket_client: WebsocketClient<TotalSymbolRollingStream>,
+    websocket_client: WebsocketPool<TotalSymbolRollingStream, TotalSymbolRollingPayload>,
+}
+
+impl TotalSymbolRollingClient {
+    // the number of payloads dropped by the backpressure policy of the payload buffer
+    pub fn get_dropped_count(&self) -> u64 {
+        self.websocket_client.get_dropped_count()
+    }
 }
 #[async_trait]
 impl BinanceWebsocketAdaptor for TotalSymbolRollingClient {
@@ -21,33 +29,30 @@ impl BinanceWebsocketAdaptor for TotalSymbolRollingClient {
     type INPUT = WindowSize;
     type OUTPUT = TotalSymbolRollingPayload;
 
-    async fn create_client<P>(process: P, uri: &str) -> Self::CLIENT
+    async fn create_client<P>(process: P, uri: &str, socket_config: SocketConfig) -> BinanceResult<Self::CLIENT>
     where
         P: SocketPayloadProcess<Self::OUTPUT> + Send + 'static ,
     {
         let (client, payload_receiver) =
-            WebsocketClient::<TotalSymbolRollingStream>::new_with_uri::<TotalSymbolRollingPayload>(uri).await;
-        let trade_stream = Box::pin(tokio_stream::wrappers::UnboundedReceiverStream::new(
-            payload_receiver,
-        ));
+            WebsocketPool::<TotalSymbolRollingStream, TotalSymbolRollingPayload>::new_with_config(uri, socket_config).await?;
+        let trade_stream = Box::pin(payload_receiver);
         tokio::spawn(total_symbol_rolling_payload_process(trade_stream, process));
-        TotalSymbolRollingClient {
+        Ok(TotalSymbolRollingClient {
             websocket_client: client,
-        }
+        })
     }
 
     async fn close(self) {
         self.websocket_client.close().await
     }
 
-    async fn subscribe_item(&mut self, input: Self::INPUT) {
+    async fn subscribe_item(&mut self, input: Self::INPUT) -> BinanceResult<()> {
         self.websocket_client
             .subscribe_single(TotalSymbolRollingStream::new(input))
             .await
-            .unwrap();
     }
 
-    async fn subscribe_items(&mut self, input: Vec<Self::INPUT>) {
+    async fn subscribe_items(&mut self, input: Vec<Self::INPUT>) -> BinanceResult<()> {
         let params = input
             .into_iter()
             .map(|window_size| TotalSymbolRollingStream::new(window_size))
@@ -55,17 +60,15 @@ impl BinanceWebsocketAdaptor for TotalSymbolRollingClient {
         self.websocket_client
             .subscribe_multiple(params)
             .await
-            .unwrap()
     }
 
-    async fn unsubscribe_item(&mut self, input: Self::INPUT) {
+    async fn unsubscribe_item(&mut self, input: Self::INPUT) -> BinanceResult<()> {
         self.websocket_client
             .unsubscribe_single(TotalSymbolRollingStream::new(input))
             .await
-            .unwrap();
     }
 
-    async fn unsubscribe_items(&mut self, input: Vec<Self::INPUT>) {
+    async fn unsubscribe_items(&mut self, input: Vec<Self::INPUT>) -> BinanceResult<()> {
         let params = input
             .into_iter()
             .map(|window_size| TotalSymbolRollingStream::new(window_size))
@@ -73,7 +76,6 @@ impl BinanceWebsocketAdaptor for TotalSymbolRollingClient {
         self.websocket_client
             .unsubscribe_multiple(params)
             .await
-            .unwrap();
     }
 
     fn get_subscribe_items(&self) -> Vec<Self::INPUT> {
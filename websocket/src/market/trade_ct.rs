@@ -1,9 +1,10 @@
 use crate::market::types::trade::{TradeStream, TradeStreamPayload};
 use async_trait::async_trait;
 use client::stream::adaptor::BinanceWebsocketAdaptor;
-use client::stream::client::WebsocketClient;
+use client::stream::pool::WebsocketPool;
 use client::stream::payload::SocketPayloadActor;
 use client::stream::stream::SocketPayloadProcess;
+use client::stream::config::SocketConfig;
 use futures_util::Stream;
 use general::result::BinanceResult;
 use general::symbol::Symbol;
@@ -13,7 +14,14 @@ pub type TradeResponseStream =
     Pin<Box<dyn Stream<Item = BinanceResult<SocketPayloadActor<TradeStreamPayload>>> + Send>>;
 
 pub struct TradeClient {
-    websocket_client: WebsocketClient<TradeStream>,
+    websocket_client: WebsocketPool<TradeStream, TradeStreamPayload>,
+}
+
+impl TradeClient {
+    // the number of payloads dropped by the backpressure policy of the payload buffer
+    pub fn get_dropped_count(&self) -> u64 {
+        self.websocket_client.get_dropped_count()
+    }
 }
 #[async_trait]
 impl BinanceWebsocketAdaptor for TradeClient
@@ -22,33 +30,30 @@ impl BinanceWebsocketAdaptor for TradeClient
     type INPUT = Symbol;
     type OUTPUT = TradeStreamPayload;
 
-    async fn create_client<P>(process: P, uri: &str) -> Self::CLIENT
+    async fn create_client<P>(process: P, uri: &str, socket_config: SocketConfig) -> BinanceResult<Self::CLIENT>
     where
         P: SocketPayloadProcess<Self::OUTPUT> + Send + 'static ,
     {
         let (client, payload_receiver) =
-            WebsocketClient::<TradeStream>::new_with_uri::<TradeStreamPayload>(uri).await;
-        let trade_stream = Box::pin(tokio_stream::wrappers::UnboundedReceiverStream::new(
-            payload_receiver,
-        ));
+            WebsocketPool::<TradeStream, TradeStreamPayload>::new_with_config(uri, socket_config).await?;
+        let trade_stream = Box::pin(payload_receiver);
         tokio::spawn(trade_payload_process(trade_stream, process));
-        TradeClient {
+        Ok(TradeClient {
             websocket_client: client,
-        }
+        })
     }
 
     async fn close(self) {
         self.websocket_client.close().await
     }
 
-    async fn subscribe_item(&mut self, input: Self::INPUT) {
+    async fn subscribe_item(&mut self, input: Self::INPUT) -> BinanceResult<()> {
         self.websocket_client
             .subscribe_single(TradeStream::new(input))
             .await
-            .unwrap();
     }
 
-    async fn subscribe_items(&mut self, input: Vec<Self::INPUT>) {
+    async fn subscribe_items(&mut self, input: Vec<Self::INPUT>) -> BinanceResult<()> {
         let params = input
             .into_iter()
             .map(|item| TradeStream::new(item))
@@ -56,17 +61,15 @@ impl BinanceWebsocketAdaptor for TradeClient
         self.websocket_client
             .subscribe_multiple(params)
             .await
-            .unwrap()
     }
 
-    async fn unsubscribe_item(&mut self, input: Self::INPUT) {
+    async fn unsubscribe_item(&mut self, input: Self::INPUT) -> BinanceResult<()> {
         self.websocket_client
             .unsubscribe_single(TradeStream::new(input))
             .await
-            .unwrap();
     }
 
-    async fn unsubscribe_items(&mut self, input: Vec<Self::INPUT>) {
+    async fn unsubscribe_items(&mut self, input: Vec<Self::INPUT>) -> BinanceResult<()> {
         let params = input
             .into_iter()
             .map(|symbol| TradeStream::new(symbol))
@@ -74,7 +77,6 @@ impl BinanceWebsocketAdaptor for TradeClient
         self.websocket_client
             .unsubscribe_multiple(params)
             .await
-            .unwrap();
     }
 
     fn get_subscribe_items(&self) -> Vec<Self::INPUT> {
@@ -93,4 +95,74 @@ pub(crate) async fn trade_payload_process<P>(
     P: SocketPayloadProcess<TradeStreamPayload> + Send + 'static,
 {
     processor.process(trade_response_stream).await;
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use client::stream::buffer::{BackpressurePolicy, PayloadBufferConfig};
+    use client::stream::config::{HeartbeatConfig, ReconnectConfig, RotationConfig, SocketLimitConfig};
+    use futures_util::{SinkExt, StreamExt};
+    use serde_json::Value;
+    use std::time::Duration;
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::tungstenite::Message;
+
+    // a consumer which never takes a payload
+    struct StalledProcess;
+
+    #[async_trait]
+    impl SocketPayloadProcess<TradeStreamPayload> for StalledProcess {
+        async fn process(&mut self, _stream: TradeResponseStream) {
+            std::future::pending::<()>().await;
+        }
+    }
+
+    // a binance market endpoint which answers every request and then sends the trades
+    async fn fake_binance(trades: usize) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let uri = format!("ws://{}/ws", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut socket = tokio_tungstenite::accept_async(stream).await.unwrap();
+            while let Some(Ok(message)) = socket.next().await {
+                let Message::Text(text) = message else { continue };
+                let request = serde_json::from_str::<Value>(&text).unwrap();
+                let response = format!(r#"{{"result":null,"id":{}}}"#, request["id"]);
+                socket.send(Message::Text(response)).await.unwrap();
+                for trade_id in 0..trades {
+                    let trade = format!(
+                        r#"{{"e":"trade","E":1,"s":"BTCUSDT","t":{},"p":"1.0","q":"1.0","T":1,"m":true,"M":true}}"#,
+                        trade_id
+                    );
+                    socket.send(Message::Text(trade)).await.unwrap();
+                }
+            }
+        });
+        uri
+    }
+
+    #[tokio::test]
+    async fn test_create_client_with_backpressure_policy() {
+        let uri = fake_binance(5).await;
+        let socket_config = SocketConfig::new(
+            ReconnectConfig::disabled(),
+            RotationConfig::disabled(),
+            SocketLimitConfig::new_default(),
+            PayloadBufferConfig::new(1, BackpressurePolicy::DropNewest),
+            HeartbeatConfig::disabled(),
+            Duration::from_secs(10),
+        );
+        let mut client = TradeClient::create_client(StalledProcess, &uri, socket_config).await.unwrap();
+        client.subscribe_item(Symbol::new("btcusdt")).await.unwrap();
+        for _ in 0..100 {
+            if client.get_dropped_count() >= 5 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        // the lifecycle events of the connection fill the buffer of the stalled consumer,
+        // every trade is dropped instead of blocking the socket
+        assert_eq!(client.get_dropped_count(), 5);
+        client.close().await;
+    }
+}
@@ -22,6 +22,11 @@ impl StreamNameFormat for AggTradeStream {
     fn stream_name(&self) -> String {
         format!("{}@aggTrade", self.symbol.name)
     }
+
+    fn from_stream_name(stream_name: &str) -> Option<Self> {
+        let symbol = stream_name.strip_suffix("@aggTrade")?;
+        Some(AggTradeStream::new(Symbol::new(symbol)))
+    }
 }
 #[derive(Serialize, Deserialize, Debug)]
 pub struct AggTradeStreamPayload {
@@ -24,6 +24,11 @@ impl StreamNameFormat for AveragePriceStream {
     fn stream_name(&self) -> String {
         format!("{}@avgPrice", self.symbol.name)
     }
+
+    fn from_stream_name(stream_name: &str) -> Option<Self> {
+        let symbol = stream_name.strip_suffix("@avgPrice")?;
+        Some(AveragePriceStream::new(Symbol::new(symbol)))
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -1,3 +1,4 @@
+use general::price_level::PriceLevel;
 use serde::{Deserialize, Serialize};
 use client::stream::stream::StreamNameFormat;
 use general::enums::level::Level;
@@ -34,17 +35,27 @@ impl StreamNameFormat for BookDepthStream {
             Some(speed) => format!("{}@depth{}@{}", self.symbol.name, self.level.value(), speed.as_str())
         }
     }
+
+    fn from_stream_name(stream_name: &str) -> Option<Self> {
+        let (symbol, depth) = stream_name.split_once('@')?;
+        let depth = depth.strip_prefix("depth")?;
+        let (level, speed) = match depth.split_once('@') {
+            None => (depth, None),
+            Some((level, speed)) => (level, Some(Speed::parse(speed)?)),
+        };
+        Some(BookDepthStream::new(Symbol::new(symbol), Level::parse(level)?, speed))
+    }
 }
 
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct BookDepthStreamPayload {
     #[serde(rename = "lastUpdateId")]
-    last_update_id: u64,
+    pub last_update_id: u64,
     #[serde(rename = "bids")]
-    bids: Vec<Vec<String>>,
+    pub bids: Vec<PriceLevel>,
     #[serde(rename = "asks")]
-    asks: Vec<Vec<String>>,
+    pub asks: Vec<PriceLevel>,
 }
 
 
@@ -54,7 +65,7 @@ mod tests {
 
     #[test]
     fn test_level() {
-        let mut stream = BookDepthStream::new(Symbol::new("S"), Level::L1, Some(Speed::Ms1000));
+        let mut stream = BookDepthStream::new(Symbol::new("S"), Level::L1, Some(Speed::Ms100));
         println!("{}", stream.stream_name());
     }
 
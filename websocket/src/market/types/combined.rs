@@ -0,0 +1,140 @@
+use crate::market::types::agg_trade::{AggTradeStream, AggTradeStreamPayload};
+use crate::market::types::book_depth::{BookDepthStream, BookDepthStreamPayload};
+use crate::market::types::depth::{DepthStream, DepthStreamPayload};
+use crate::market::types::kline::{KlineStream, KlineStreamPayload};
+use crate::market::types::symbol_book_ticker::{SymbolBookTickerPayload, SymbolBookTickerStream};
+use crate::market::types::symbol_mini_ticker::{SymbolMiniTickerPayload, SymbolMiniTickerStream};
+use crate::market::types::symbol_ticker::{SymbolTickerPayload, SymbolTickerStream};
+use crate::market::types::trade::{TradeStream, TradeStreamPayload};
+use client::stream::stream::StreamNameFormat;
+use serde::de::{DeserializeOwned, Error};
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+
+// one of the market streams which can share a single combined stream connection
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub enum MarketStream {
+    Trade(TradeStream),
+    AggTrade(AggTradeStream),
+    Depth(DepthStream),
+    BookDepth(BookDepthStream),
+    Kline(KlineStream),
+    BookTicker(SymbolBookTickerStream),
+    MiniTicker(SymbolMiniTickerStream),
+    Ticker(SymbolTickerStream),
+}
+
+impl StreamNameFormat for MarketStream {
+    fn stream_name(&self) -> String {
+        match self {
+            MarketStream::Trade(stream) => stream.stream_name(),
+            MarketStream::AggTrade(stream) => stream.stream_name(),
+            MarketStream::Depth(stream) => stream.stream_name(),
+            MarketStream::BookDepth(stream) => stream.stream_name(),
+            MarketStream::Kline(stream) => stream.stream_name(),
+            MarketStream::BookTicker(stream) => stream.stream_name(),
+            MarketStream::MiniTicker(stream) => stream.stream_name(),
+            MarketStream::Ticker(stream) => stream.stream_name(),
+        }
+    }
+
+    fn from_stream_name(stream_name: &str) -> Option<Self> {
+        None.or_else(|| TradeStream::from_stream_name(stream_name).map(MarketStream::Trade))
+            .or_else(|| AggTradeStream::from_stream_name(stream_name).map(MarketStream::AggTrade))
+            .or_else(|| DepthStream::from_stream_name(stream_name).map(MarketStream::Depth))
+            .or_else(|| BookDepthStream::from_stream_name(stream_name).map(MarketStream::BookDepth))
+            .or_else(|| KlineStream::from_stream_name(stream_name).map(MarketStream::Kline))
+            .or_else(|| SymbolBookTickerStream::from_stream_name(stream_name).map(MarketStream::BookTicker))
+            .or_else(|| SymbolMiniTickerStream::from_stream_name(stream_name).map(MarketStream::MiniTicker))
+            .or_else(|| SymbolTickerStream::from_stream_name(stream_name).map(MarketStream::Ticker))
+    }
+}
+
+// the payload of a MarketStream, the variant is chosen by the event type("e") of the payload,
+// partial book depth and spot book ticker have no event type and are recognized by their fields.
+#[derive(Debug)]
+pub enum MarketStreamPayload {
+    Trade(TradeStreamPayload),
+    AggTrade(AggTradeStreamPayload),
+    Depth(DepthStreamPayload),
+    BookDepth(BookDepthStreamPayload),
+    Kline(KlineStreamPayload),
+    BookTicker(SymbolBookTickerPayload),
+    MiniTicker(SymbolMiniTickerPayload),
+    Ticker(SymbolTickerPayload),
+}
+
+impl<'de> Deserialize<'de> for MarketStreamPayload {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        fn parse<T: DeserializeOwned, E: Error>(value: Value) -> Result<T, E> {
+            serde_json::from_value(value).map_err(E::custom)
+        }
+        let value = Value::deserialize(deserializer)?;
+        let event_type = value.get("e").and_then(Value::as_str).map(str::to_string);
+        match event_type.as_deref() {
+            Some("trade") => parse(value).map(MarketStreamPayload::Trade),
+            Some("aggTrade") => parse(value).map(MarketStreamPayload::AggTrade),
+            Some("depthUpdate") => parse(value).map(MarketStreamPayload::Depth),
+            Some("kline") => parse(value).map(MarketStreamPayload::Kline),
+            Some("bookTicker") => parse(value).map(MarketStreamPayload::BookTicker),
+            Some("24hrMiniTicker") => parse(value).map(MarketStreamPayload::MiniTicker),
+            Some("24hrTicker") => parse(value).map(MarketStreamPayload::Ticker),
+            Some(event_type) => Err(D::Error::custom(format!("unsupported event type: {}", event_type))),
+            None if value.get("lastUpdateId").is_some() => parse(value).map(MarketStreamPayload::BookDepth),
+            None => parse(value).map(MarketStreamPayload::BookTicker),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use client::stream::payload::CombinedStreamPayload;
+    use general::enums::interval::Interval;
+    use general::enums::level::Level;
+    use general::enums::speed::Speed;
+    use general::symbol::Symbol;
+
+    #[test]
+    fn test_stream_name_round_trip() {
+        let streams = vec![
+            MarketStream::Trade(TradeStream::new(Symbol::new("BTCUSDT"))),
+            MarketStream::AggTrade(AggTradeStream::new(Symbol::new("BTCUSDT"))),
+            MarketStream::Depth(DepthStream::new(Symbol::new("BTCUSDT"), None)),
+            MarketStream::Depth(DepthStream::new(Symbol::new("BTCUSDT"), Some(Speed::Ms100))),
+            MarketStream::BookDepth(BookDepthStream::new(Symbol::new("BTCUSDT"), Level::L1, None)),
+            MarketStream::Kline(KlineStream::new(Symbol::new("ETHUSDT"), Interval::Minute1, None)),
+            MarketStream::BookTicker(SymbolBookTickerStream::new(Symbol::new("BTCUSDT"))),
+            MarketStream::MiniTicker(SymbolMiniTickerStream::new(Symbol::new("BTCUSDT"))),
+            MarketStream::Ticker(SymbolTickerStream::new(Symbol::new("BTCUSDT"))),
+        ];
+        for stream in streams {
+            assert_eq!(MarketStream::from_stream_name(&stream.stream_name()), Some(stream));
+        }
+        assert_eq!(MarketStream::from_stream_name("btcusdt@unknown"), None);
+    }
+
+    #[test]
+    fn test_combined_payload_deserialize() {
+        let payload = r#"
+            {
+                "stream": "bnbbtc@depth5",
+                "data": {
+                    "lastUpdateId": 160,
+                    "bids": [["0.0024", "10"]],
+                    "asks": [["0.0026", "100"]]
+                }
+            }
+        "#;
+        let payload =
+            serde_json::from_str::<CombinedStreamPayload<MarketStream, MarketStreamPayload>>(payload).unwrap();
+        assert_eq!(
+            payload.stream,
+            MarketStream::BookDepth(BookDepthStream::new(Symbol::new("bnbbtc"), Level::L1, None))
+        );
+        assert!(matches!(payload.data, MarketStreamPayload::BookDepth(_)));
+    }
+}
@@ -25,6 +25,11 @@ impl StreamNameFormat for CompositionIndexSymbolStream {
     fn stream_name(&self) -> String {
         format!("{}@compositeIndex", self.symbol.name)
     }
+
+    fn from_stream_name(stream_name: &str) -> Option<Self> {
+        let symbol = stream_name.strip_suffix("@compositeIndex")?;
+        Some(CompositionIndexSymbolStream::new(Symbol::new(symbol)))
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug)]
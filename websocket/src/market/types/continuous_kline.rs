@@ -35,6 +35,16 @@ impl StreamNameFormat for ContinuousKlineStream {
     fn stream_name(&self) -> String {
         format!("{}_{}@continuousKline_{}", self.symbol.name, self.contract_type.as_str(), self.interval.as_str())
     }
+
+    fn from_stream_name(stream_name: &str) -> Option<Self> {
+        let (pair, interval) = stream_name.split_once("@continuousKline_")?;
+        let (symbol, contract_type) = pair.split_once('_')?;
+        Some(ContinuousKlineStream::new(
+            Symbol::new(symbol),
+            ContractType::parse(contract_type)?,
+            Interval::parse(interval)?,
+        ))
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug)]
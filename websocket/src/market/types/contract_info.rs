@@ -12,6 +12,10 @@ impl StreamNameFormat for ContractInfoStream {
     fn stream_name(&self) -> String {
         "!contractInfo".to_string()
     }
+
+    fn from_stream_name(stream_name: &str) -> Option<Self> {
+        (stream_name == "!contractInfo").then_some(ContractInfoStream)
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug)]
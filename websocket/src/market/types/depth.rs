@@ -1,3 +1,4 @@
+use general::price_level::PriceLevel;
 use serde::{Deserialize, Serialize};
 use client::stream::stream::StreamNameFormat;
 use crate::market::types::event_type::EventType;
@@ -30,6 +31,15 @@ impl StreamNameFormat for DepthStream {
             Some(speed) => format!("{}@depth@{}", self.symbol.name, speed.as_str())
         }
     }
+
+    fn from_stream_name(stream_name: &str) -> Option<Self> {
+        let (symbol, depth) = stream_name.split_once('@')?;
+        let speed = match depth {
+            "depth" => None,
+            _ => Some(Speed::parse(depth.strip_prefix("depth@")?)?),
+        };
+        Some(DepthStream::new(Symbol::new(symbol), speed))
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -49,9 +59,9 @@ pub struct DepthStreamPayload {
     #[serde(rename = "u")]
     pub final_update_id: u64,
     #[serde(rename = "b")]
-    pub bids: Vec<Vec<String>>,
+    pub bids: Vec<PriceLevel>,
     #[serde(rename = "a")]
-    pub asks: Vec<Vec<String>>,
+    pub asks: Vec<PriceLevel>,
 }
 
 
@@ -39,6 +39,16 @@ impl StreamNameFormat for KlineStream {
         }
 
     }
+
+    fn from_stream_name(stream_name: &str) -> Option<Self> {
+        let (symbol, kline) = stream_name.split_once('@')?;
+        let kline = kline.strip_prefix("kline_")?;
+        let (interval, timezone) = match kline.split_once('@') {
+            None => (kline, None),
+            Some((interval, timezone)) => (interval, Some(Timezone::parse(timezone)?)),
+        };
+        Some(KlineStream::new(Symbol::new(symbol), Interval::parse(interval)?, timezone))
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug)]
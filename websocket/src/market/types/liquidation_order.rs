@@ -25,6 +25,11 @@ impl StreamNameFormat for LiquidationOrderStream {
     fn stream_name(&self) -> String {
         format!("{}@forceOrder", self.symbol.name)
     }
+
+    fn from_stream_name(stream_name: &str) -> Option<Self> {
+        let symbol = stream_name.strip_suffix("@forceOrder")?;
+        Some(LiquidationOrderStream::new(Symbol::new(symbol)))
+    }
 }
 #[derive(Debug, PartialEq, Eq, Clone, Hash, Default)]
 pub struct TotalLiquidationOrderStream;
@@ -33,6 +38,10 @@ impl StreamNameFormat for TotalLiquidationOrderStream {
     fn stream_name(&self) -> String {
         "!forceOrder@arr".to_string()
     }
+
+    fn from_stream_name(stream_name: &str) -> Option<Self> {
+        (stream_name == "!forceOrder@arr").then_some(TotalLiquidationOrderStream)
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug)]
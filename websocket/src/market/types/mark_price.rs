@@ -32,6 +32,14 @@ impl StreamNameFormat for MarkPriceStream {
             format!("{}@markPrice@1s", self.symbol.name)
         }
     }
+
+    fn from_stream_name(stream_name: &str) -> Option<Self> {
+        if let Some(symbol) = stream_name.strip_suffix("@markPrice@1s") {
+            return Some(MarkPriceStream::new(Symbol::new(symbol), false));
+        }
+        let symbol = stream_name.strip_suffix("@markPrice")?;
+        Some(MarkPriceStream::new(Symbol::new(symbol), true))
+    }
 }
 
 
@@ -58,6 +66,14 @@ impl StreamNameFormat for TotalMarkPriceStream {
             "!markPrice@arr@1s".to_string()
         }
     }
+
+    fn from_stream_name(stream_name: &str) -> Option<Self> {
+        match stream_name {
+            "!markPrice@arr" => Some(TotalMarkPriceStream::new(true)),
+            "!markPrice@arr@1s" => Some(TotalMarkPriceStream::new(false)),
+            _ => None,
+        }
+    }
 }
 
 
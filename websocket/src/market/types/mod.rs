@@ -13,4 +13,5 @@ pub mod mark_price;
 pub mod continuous_kline;
 pub mod liquidation_order;
 pub mod composite_index_symbol;
-pub mod contract_info;
\ No newline at end of file
+pub mod contract_info;pub mod combined;
+pub mod order_book;
@@ -0,0 +1,203 @@
+use crate::market::types::depth::DepthStreamPayload;
+use bigdecimal::{BigDecimal, Zero};
+use general::price_level::PriceLevel;
+use rest_api::types::market::order_book::OrderBookResp;
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+
+// the sequencing rule of diff depth events, see
+// https://developers.binance.com/docs/binance-spot-api-docs/web-socket-streams#how-to-manage-a-local-order-book-correctly
+// https://developers.binance.com/docs/derivatives/usds-margined-futures/websocket-market-streams/How-to-manage-a-local-order-book-correctly
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum OrderBookMarket {
+    // every event starts right after the previous one: U == previous u + 1
+    Spot,
+    // every event points to the previous one: pu == previous u
+    UsdFuture,
+}
+
+// a diff depth event does not follow the book, the book must be rebuilt from a new snapshot
+#[derive(Debug, Eq, PartialEq)]
+pub struct OrderBookGap {
+    pub last_update_id: u64,
+    pub first_update_id: u64,
+    pub final_update_id: u64,
+}
+
+impl Display for OrderBookGap {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "depth event {}..={} does not follow the book at {}",
+            self.first_update_id, self.final_update_id, self.last_update_id
+        )
+    }
+}
+
+// LocalOrderBook is an order book built from a REST snapshot and kept up to date by diff depth events
+#[derive(Debug, Clone)]
+pub struct LocalOrderBook {
+    symbol: String,
+    market: OrderBookMarket,
+    last_update_id: u64,
+    // false until the first event after the snapshot is applied, the first event follows a looser rule
+    chained: bool,
+    bids: BTreeMap<Reverse<BigDecimal>, BigDecimal>,
+    asks: BTreeMap<BigDecimal, BigDecimal>,
+}
+
+impl LocalOrderBook {
+    pub fn from_snapshot(symbol: &str, market: OrderBookMarket, snapshot: &OrderBookResp) -> Self {
+        let mut book = LocalOrderBook {
+            symbol: symbol.to_string(),
+            market,
+            last_update_id: snapshot.last_update_id,
+            chained: false,
+            bids: BTreeMap::new(),
+            asks: BTreeMap::new(),
+        };
+        book.update_levels(&snapshot.bids, &snapshot.asks);
+        book
+    }
+
+    pub fn get_symbol(&self) -> &str {
+        &self.symbol
+    }
+    pub fn get_last_update_id(&self) -> u64 {
+        self.last_update_id
+    }
+
+    // apply a diff depth event, return false when the event is older than the book and ignored
+    pub fn apply(&mut self, event: &DepthStreamPayload) -> Result<bool, OrderBookGap> {
+        let last = self.last_update_id;
+        let outdated = match self.market {
+            OrderBookMarket::Spot => event.final_update_id <= last,
+            OrderBookMarket::UsdFuture => event.final_update_id < last,
+        };
+        if outdated {
+            return Ok(false);
+        }
+        let follows = match (self.market, self.chained) {
+            (OrderBookMarket::Spot, false) => event.first_update_id <= last + 1,
+            (OrderBookMarket::Spot, true) => event.first_update_id == last + 1,
+            (OrderBookMarket::UsdFuture, false) => event.first_update_id <= last,
+            (OrderBookMarket::UsdFuture, true) => event.final_update_id_in_stream == Some(last),
+        };
+        if !follows {
+            return Err(OrderBookGap {
+                last_update_id: last,
+                first_update_id: event.first_update_id,
+                final_update_id: event.final_update_id,
+            });
+        }
+        self.update_levels(&event.bids, &event.asks);
+        self.last_update_id = event.final_update_id;
+        self.chained = true;
+        Ok(true)
+    }
+
+    // a level with zero quantity is removed, every other level replaces the quantity at its price
+    fn update_levels(&mut self, bids: &[PriceLevel], asks: &[PriceLevel]) {
+        for PriceLevel { price, quantity } in bids {
+            if quantity.is_zero() {
+                self.bids.remove(&Reverse(price.clone()));
+            } else {
+                self.bids.insert(Reverse(price.clone()), quantity.clone());
+            }
+        }
+        for PriceLevel { price, quantity } in asks {
+            if quantity.is_zero() {
+                self.asks.remove(price);
+            } else {
+                self.asks.insert(price.clone(), quantity.clone());
+            }
+        }
+    }
+
+    pub fn best_bid(&self) -> Option<PriceLevel> {
+        self.bids(1).into_iter().next()
+    }
+
+    pub fn best_ask(&self) -> Option<PriceLevel> {
+        self.asks(1).into_iter().next()
+    }
+
+    // the best `levels` bids, the highest price first
+    pub fn bids(&self, levels: usize) -> Vec<PriceLevel> {
+        self.bids
+            .iter()
+            .take(levels)
+            .map(|(Reverse(price), quantity)| PriceLevel::new(price.clone(), quantity.clone()))
+            .collect()
+    }
+
+    // the best `levels` asks, the lowest price first
+    pub fn asks(&self, levels: usize) -> Vec<PriceLevel> {
+        self.asks
+            .iter()
+            .take(levels)
+            .map(|(price, quantity)| PriceLevel::new(price.clone(), quantity.clone()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+    use crate::market::types::event_type::EventType;
+
+    fn level(price: &str, quantity: &str) -> PriceLevel {
+        PriceLevel::new(BigDecimal::from_str(price).unwrap(), BigDecimal::from_str(quantity).unwrap())
+    }
+
+    fn snapshot(last_update_id: u64) -> OrderBookResp {
+        OrderBookResp {
+            last_update_id,
+            output_time: None,
+            transaction_time: None,
+            bids: vec![level("100", "1"), level("99", "2")],
+            asks: vec![level("101", "1")],
+        }
+    }
+
+    fn event(first: u64, last: u64, previous: Option<u64>, bids: &[(&str, &str)]) -> DepthStreamPayload {
+        DepthStreamPayload {
+            event_type: EventType::DepthUpdate,
+            event_time: 0,
+            symbol: "BNBBTC".to_string(),
+            transaction_time: None,
+            final_update_id_in_stream: previous,
+            first_update_id: first,
+            final_update_id: last,
+            bids: bids.iter().map(|(price, quantity)| level(price, quantity)).collect(),
+            asks: vec![],
+        }
+    }
+
+    #[test]
+    fn test_spot_sequencing() {
+        let mut book = LocalOrderBook::from_snapshot("BNBBTC", OrderBookMarket::Spot, &snapshot(10));
+        assert_eq!(book.apply(&event(5, 10, None, &[])), Ok(false));
+        assert_eq!(book.apply(&event(8, 12, None, &[("100", "0"), ("98", "3")])), Ok(true));
+        assert_eq!(book.best_bid(), Some(level("99", "2")));
+        assert_eq!(book.bids(5).len(), 2);
+        assert_eq!(book.apply(&event(13, 14, None, &[])), Ok(true));
+        assert!(book.apply(&event(16, 17, None, &[])).is_err());
+        assert_eq!(book.get_last_update_id(), 14);
+
+        let mut book = LocalOrderBook::from_snapshot("BNBBTC", OrderBookMarket::Spot, &snapshot(10));
+        assert!(book.apply(&event(12, 13, None, &[])).is_err());
+    }
+
+    #[test]
+    fn test_usd_future_sequencing() {
+        let mut book = LocalOrderBook::from_snapshot("BTCUSDT", OrderBookMarket::UsdFuture, &snapshot(10));
+        assert_eq!(book.apply(&event(5, 9, Some(4), &[])), Ok(false));
+        assert_eq!(book.apply(&event(9, 12, Some(8), &[])), Ok(true));
+        assert_eq!(book.apply(&event(15, 18, Some(12), &[("101", "1")])), Ok(true));
+        assert!(book.apply(&event(20, 22, Some(19), &[])).is_err());
+        assert_eq!(book.best_ask(), Some(level("101", "1")));
+    }
+}
@@ -23,6 +23,11 @@ impl StreamNameFormat for SymbolBookTickerStream {
     fn stream_name(&self) -> String {
         format!("{}@bookTicker", self.symbol.name)
     }
+
+    fn from_stream_name(stream_name: &str) -> Option<Self> {
+        let symbol = stream_name.strip_suffix("@bookTicker")?;
+        Some(SymbolBookTickerStream::new(Symbol::new(symbol)))
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Hash, Default)]
@@ -32,6 +37,10 @@ impl StreamNameFormat for TotalSymbolBookTickerStream {
     fn stream_name(&self) -> String {
         "!bookTicker".to_string()
     }
+
+    fn from_stream_name(stream_name: &str) -> Option<Self> {
+        (stream_name == "!bookTicker").then_some(TotalSymbolBookTickerStream)
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug)]
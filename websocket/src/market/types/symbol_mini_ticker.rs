@@ -24,12 +24,21 @@ impl StreamNameFormat for SymbolMiniTickerStream {
     fn stream_name(&self) -> String {
         format!("{}@miniTicker", self.symbol.name)
     }
+
+    fn from_stream_name(stream_name: &str) -> Option<Self> {
+        let symbol = stream_name.strip_suffix("@miniTicker")?;
+        Some(SymbolMiniTickerStream::new(Symbol::new(symbol)))
+    }
 }
 
 impl StreamNameFormat for TotalSymbolMiniTickerStream {
     fn stream_name(&self) -> String {
         "!miniTicker@arr".to_string()
     }
+
+    fn from_stream_name(stream_name: &str) -> Option<Self> {
+        (stream_name == "!miniTicker@arr").then_some(TotalSymbolMiniTickerStream)
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -44,6 +44,11 @@ impl StreamNameFormat for SymbolRollingWindowStream {
     fn stream_name(&self) -> String {
         format!("{}@ticker_{}", self.symbol.name, self.window_size.as_str())
     }
+
+    fn from_stream_name(stream_name: &str) -> Option<Self> {
+        let (symbol, window_size) = stream_name.split_once("@ticker_")?;
+        Some(SymbolRollingWindowStream::new(Symbol::new(symbol), WindowSize::parse(window_size)?))
+    }
 }
 
 
@@ -51,6 +56,11 @@ impl StreamNameFormat for TotalSymbolRollingStream {
     fn stream_name(&self) -> String {
         format!("!ticker_{}@arr", self.window_size.as_str())
     }
+
+    fn from_stream_name(stream_name: &str) -> Option<Self> {
+        let window_size = stream_name.strip_prefix("!ticker_")?.strip_suffix("@arr")?;
+        Some(TotalSymbolRollingStream::new(WindowSize::parse(window_size)?))
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug)]
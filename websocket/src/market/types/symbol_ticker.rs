@@ -23,6 +23,11 @@ impl StreamNameFormat for SymbolTickerStream {
     fn stream_name(&self) -> String {
         format!("{}@ticker", self.symbol.name)
     }
+
+    fn from_stream_name(stream_name: &str) -> Option<Self> {
+        let symbol = stream_name.strip_suffix("@ticker")?;
+        Some(SymbolTickerStream::new(Symbol::new(symbol)))
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Hash, Default)]
@@ -32,6 +37,10 @@ impl StreamNameFormat for TotalSymbolTickerStream {
     fn stream_name(&self) -> String {
         "!ticker@arr".to_string()
     }
+
+    fn from_stream_name(stream_name: &str) -> Option<Self> {
+        (stream_name == "!ticker@arr").then_some(TotalSymbolTickerStream)
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -23,6 +23,11 @@ impl StreamNameFormat for TradeStream {
     fn stream_name(&self) -> String {
         format!("{}@trade", self.symbol.name)
     }
+
+    fn from_stream_name(stream_name: &str) -> Option<Self> {
+        let symbol = stream_name.strip_suffix("@trade")?;
+        Some(TradeStream::new(Symbol::new(symbol)))
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -1,3 +1,4 @@
+use crate::market::combined_ct::{CombinedMarketClient, CombinedMarketPayload};
 use crate::market::agg_trade_ct::AggTradeClient;
 use crate::market::avg_price_ct::AveragePriceClient;
 use crate::market::book_depth_ct::BookDepthClient;
@@ -25,102 +26,266 @@ use crate::market::types::symbol_rolling::{
 };
 use crate::market::types::symbol_ticker::{SymbolTickerPayload, TotalSymbolTickerPayload};
 use crate::market::types::trade::TradeStreamPayload;
+use crate::market::order_book_ct::{OrderBookHandle, OrderBookManager};
+use client::environment::Environment;
+use client::rest::config::Config;
+use client::rest::client::{BinanceClient, BinanceClientAction};
 use client::stream::adaptor::BinanceWebsocketAdaptor;
-use client::stream::stream::SocketPayloadProcess;
+use client::stream::config::SocketConfig;
+use client::stream::payload::SocketPayloadActor;
+use client::stream::stream::{ChannelStreamPayloadProcess, SocketPayloadProcess};
+use futures_util::Stream;
+use general::result::BinanceResult;
+use rest_api::spot_market_ct::SpotMarketClient;
 
-pub struct BinanceSpotMarketWebsocketClient;
+pub struct BinanceSpotMarketWebsocketClient {
+    environment: Environment,
+    socket_config: SocketConfig,
+}
 
-const SPOT_SOCKET_URI: &str = "wss://stream.binance.com:9443/ws";
 impl BinanceSpotMarketWebsocketClient {
+    // the hosts are taken from the environment of config
+    pub fn new(config: &Config) -> Self {
+        BinanceSpotMarketWebsocketClient {
+            environment: config.environment(),
+            socket_config: SocketConfig::new_default(),
+        }
+    }
+
+    pub fn new_default() -> Self {
+        Self::new(&Config::new_default())
+    }
+
+    // the reconnection, rotation, heartbeat and payload buffer of every connection opened by this client
+    pub fn with_socket_config(mut self, socket_config: SocketConfig) -> Self {
+        self.socket_config = socket_config;
+        self
+    }
+
     /// office doc(https://developers.binance.com/docs/binance-spot-api-docs/web-socket-streams#aggregate-trade-streams)
-    pub async fn trade<P>(process: P) -> TradeClient
+    pub async fn trade<P>(&self, process: P) -> BinanceResult<TradeClient>
     where
         P: SocketPayloadProcess<TradeStreamPayload> + Send + 'static,
     {
-        TradeClient::create_client(process, SPOT_SOCKET_URI).await
+        TradeClient::create_client(process, &self.environment.spot_socket_uri(), self.socket_config.clone()).await
+    }
+
+    pub async fn trade_stream(&self) -> BinanceResult<(
+        TradeClient,
+        impl Stream<Item = BinanceResult<SocketPayloadActor<TradeStreamPayload>>> + use<>,
+    )> {
+        let (process, stream) = ChannelStreamPayloadProcess::new();
+        Ok((self.trade(process).await?, stream))
     }
 
-    pub async fn agg_trade<P>(process: P) -> AggTradeClient
+    pub async fn agg_trade<P>(&self, process: P) -> BinanceResult<AggTradeClient>
     where
         P: SocketPayloadProcess<AggTradeStreamPayload> + Send + 'static,
     {
-        AggTradeClient::create_client(process, SPOT_SOCKET_URI).await
+        AggTradeClient::create_client(process, &self.environment.spot_socket_uri(), self.socket_config.clone()).await
+    }
+
+    pub async fn agg_trade_stream(&self) -> BinanceResult<(
+        AggTradeClient,
+        impl Stream<Item = BinanceResult<SocketPayloadActor<AggTradeStreamPayload>>> + use<>,
+    )> {
+        let (process, stream) = ChannelStreamPayloadProcess::new();
+        Ok((self.agg_trade(process).await?, stream))
     }
 
-    pub async fn average_price<P>(process: P) -> AveragePriceClient
+    pub async fn average_price<P>(&self, process: P) -> BinanceResult<AveragePriceClient>
     where
         P: SocketPayloadProcess<AveragePricePayload> + Send + 'static,
     {
-        AveragePriceClient::create_client(process, SPOT_SOCKET_URI).await
+        AveragePriceClient::create_client(process, &self.environment.spot_socket_uri(), self.socket_config.clone()).await
     }
 
-    pub async fn diff_book_depth<P>(process: P) -> DepthClient
+    pub async fn average_price_stream(&self) -> BinanceResult<(
+        AveragePriceClient,
+        impl Stream<Item = BinanceResult<SocketPayloadActor<AveragePricePayload>>> + use<>,
+    )> {
+        let (process, stream) = ChannelStreamPayloadProcess::new();
+        Ok((self.average_price(process).await?, stream))
+    }
+
+    pub async fn diff_book_depth<P>(&self, process: P) -> BinanceResult<DepthClient>
     where
         P: SocketPayloadProcess<DepthStreamPayload> + Send + 'static,
     {
-        DepthClient::create_client(process, SPOT_SOCKET_URI).await
+        DepthClient::create_client(process, &self.environment.spot_socket_uri(), self.socket_config.clone()).await
+    }
+
+    pub async fn diff_book_depth_stream(&self) -> BinanceResult<(
+        DepthClient,
+        impl Stream<Item = BinanceResult<SocketPayloadActor<DepthStreamPayload>>> + use<>,
+    )> {
+        let (process, stream) = ChannelStreamPayloadProcess::new();
+        Ok((self.diff_book_depth(process).await?, stream))
     }
 
-    pub async fn partial_book_depth<P>(process: P) -> BookDepthClient
+    // a local order book of every subscribed diff depth stream, the snapshots are fetched by market_client,
+    // limit is the depth of the snapshots. subscribe the symbols on the returned client, and read the books by the handle.
+    pub async fn order_book<T>(
+        &self,
+        market_client: SpotMarketClient<T>,
+        limit: u16,
+    ) -> BinanceResult<(DepthClient, OrderBookHandle)>
+    where
+        T: BinanceClient + BinanceClientAction + Send + Sync + 'static,
+    {
+        let (manager, handle) = OrderBookManager::new(market_client, limit);
+        Ok((DepthClient::create_client(manager, &self.environment.spot_socket_uri(), self.socket_config.clone()).await?, handle))
+    }
+
+    pub async fn partial_book_depth<P>(&self, process: P) -> BinanceResult<BookDepthClient>
     where
         P: SocketPayloadProcess<BookDepthStreamPayload> + Send + 'static,
     {
-        BookDepthClient::create_client(process, SPOT_SOCKET_URI).await
+        BookDepthClient::create_client(process, &self.environment.spot_socket_uri(), self.socket_config.clone()).await
     }
 
-    pub async fn kline<P>(process: P) -> KlineClient
+    pub async fn partial_book_depth_stream(&self) -> BinanceResult<(
+        BookDepthClient,
+        impl Stream<Item = BinanceResult<SocketPayloadActor<BookDepthStreamPayload>>> + use<>,
+    )> {
+        let (process, stream) = ChannelStreamPayloadProcess::new();
+        Ok((self.partial_book_depth(process).await?, stream))
+    }
+
+    pub async fn kline<P>(&self, process: P) -> BinanceResult<KlineClient>
     where
         P: SocketPayloadProcess<KlineStreamPayload> + Send + 'static,
     {
-        KlineClient::create_client(process, SPOT_SOCKET_URI).await
+        KlineClient::create_client(process, &self.environment.spot_socket_uri(), self.socket_config.clone()).await
     }
 
-    pub async fn symbol_book_ticker<P>(process: P) -> SymbolBookTickerClient
+    pub async fn kline_stream(&self) -> BinanceResult<(
+        KlineClient,
+        impl Stream<Item = BinanceResult<SocketPayloadActor<KlineStreamPayload>>> + use<>,
+    )> {
+        let (process, stream) = ChannelStreamPayloadProcess::new();
+        Ok((self.kline(process).await?, stream))
+    }
+
+    pub async fn symbol_book_ticker<P>(&self, process: P) -> BinanceResult<SymbolBookTickerClient>
     where
         P: SocketPayloadProcess<SymbolBookTickerPayload> + Send + 'static,
     {
-        SymbolBookTickerClient::create_client(process, SPOT_SOCKET_URI).await
+        SymbolBookTickerClient::create_client(process, &self.environment.spot_socket_uri(), self.socket_config.clone()).await
+    }
+
+    pub async fn symbol_book_ticker_stream(&self) -> BinanceResult<(
+        SymbolBookTickerClient,
+        impl Stream<Item = BinanceResult<SocketPayloadActor<SymbolBookTickerPayload>>> + use<>,
+    )> {
+        let (process, stream) = ChannelStreamPayloadProcess::new();
+        Ok((self.symbol_book_ticker(process).await?, stream))
     }
 
-    pub async fn symbol_mini_ticker<P>(process: P) -> SymbolMiniTickerClient
+    pub async fn symbol_mini_ticker<P>(&self, process: P) -> BinanceResult<SymbolMiniTickerClient>
     where
         P: SocketPayloadProcess<SymbolMiniTickerPayload> + Send + 'static,
     {
-        SymbolMiniTickerClient::create_client(process, SPOT_SOCKET_URI).await
+        SymbolMiniTickerClient::create_client(process, &self.environment.spot_socket_uri(), self.socket_config.clone()).await
     }
 
-    pub async fn symbol_mini_ticker_total<P>(process: P) -> TotalSymbolMiniTickerClient
+    pub async fn symbol_mini_ticker_stream(&self) -> BinanceResult<(
+        SymbolMiniTickerClient,
+        impl Stream<Item = BinanceResult<SocketPayloadActor<SymbolMiniTickerPayload>>> + use<>,
+    )> {
+        let (process, stream) = ChannelStreamPayloadProcess::new();
+        Ok((self.symbol_mini_ticker(process).await?, stream))
+    }
+
+    pub async fn symbol_mini_ticker_total<P>(&self, process: P) -> BinanceResult<TotalSymbolMiniTickerClient>
     where
         P: SocketPayloadProcess<TotalSymbolMiniTickerPayload> + Send + 'static,
     {
-        TotalSymbolMiniTickerClient::create_client(process, SPOT_SOCKET_URI).await
+        TotalSymbolMiniTickerClient::create_client(process, &self.environment.spot_socket_uri(), self.socket_config.clone()).await
     }
 
-    pub async fn symbol_rolling_ticker<P>(process: P) -> SymbolRollingClient
+    pub async fn symbol_mini_ticker_total_stream(&self) -> BinanceResult<(
+        TotalSymbolMiniTickerClient,
+        impl Stream<Item = BinanceResult<SocketPayloadActor<TotalSymbolMiniTickerPayload>>> + use<>,
+    )> {
+        let (process, stream) = ChannelStreamPayloadProcess::new();
+        Ok((self.symbol_mini_ticker_total(process).await?, stream))
+    }
+
+    pub async fn symbol_rolling_ticker<P>(&self, process: P) -> BinanceResult<SymbolRollingClient>
     where
         P: SocketPayloadProcess<SymbolRollingPayload> + Send + 'static,
     {
-        SymbolRollingClient::create_client(process, SPOT_SOCKET_URI).await
+        SymbolRollingClient::create_client(process, &self.environment.spot_socket_uri(), self.socket_config.clone()).await
+    }
+
+    pub async fn symbol_rolling_ticker_stream(&self) -> BinanceResult<(
+        SymbolRollingClient,
+        impl Stream<Item = BinanceResult<SocketPayloadActor<SymbolRollingPayload>>> + use<>,
+    )> {
+        let (process, stream) = ChannelStreamPayloadProcess::new();
+        Ok((self.symbol_rolling_ticker(process).await?, stream))
     }
 
-    pub async fn symbol_rolling_ticker_total<P>(process: P) -> TotalSymbolRollingClient
+    pub async fn symbol_rolling_ticker_total<P>(&self, process: P) -> BinanceResult<TotalSymbolRollingClient>
     where
         P: SocketPayloadProcess<TotalSymbolRollingPayload> + Send + 'static,
     {
-        TotalSymbolRollingClient::create_client(process, SPOT_SOCKET_URI).await
+        TotalSymbolRollingClient::create_client(process, &self.environment.spot_socket_uri(), self.socket_config.clone()).await
+    }
+
+    pub async fn symbol_rolling_ticker_total_stream(&self) -> BinanceResult<(
+        TotalSymbolRollingClient,
+        impl Stream<Item = BinanceResult<SocketPayloadActor<TotalSymbolRollingPayload>>> + use<>,
+    )> {
+        let (process, stream) = ChannelStreamPayloadProcess::new();
+        Ok((self.symbol_rolling_ticker_total(process).await?, stream))
     }
 
-    pub async fn symbol_ticker<P>(process: P) -> SymbolTickerClient
+    pub async fn symbol_ticker<P>(&self, process: P) -> BinanceResult<SymbolTickerClient>
     where
         P: SocketPayloadProcess<SymbolTickerPayload> + Send + 'static,
     {
-        SymbolTickerClient::create_client(process, SPOT_SOCKET_URI).await
+        SymbolTickerClient::create_client(process, &self.environment.spot_socket_uri(), self.socket_config.clone()).await
     }
 
-    pub async fn symbol_ticker_total<P>(process: P) -> TotalSymbolTickerClient
+    pub async fn symbol_ticker_stream(&self) -> BinanceResult<(
+        SymbolTickerClient,
+        impl Stream<Item = BinanceResult<SocketPayloadActor<SymbolTickerPayload>>> + use<>,
+    )> {
+        let (process, stream) = ChannelStreamPayloadProcess::new();
+        Ok((self.symbol_ticker(process).await?, stream))
+    }
+
+    pub async fn symbol_ticker_total<P>(&self, process: P) -> BinanceResult<TotalSymbolTickerClient>
     where
         P: SocketPayloadProcess<TotalSymbolTickerPayload> + Send + 'static,
     {
-        TotalSymbolTickerClient::create_client(process, SPOT_SOCKET_URI).await
+        TotalSymbolTickerClient::create_client(process, &self.environment.spot_socket_uri(), self.socket_config.clone()).await
+    }
+
+    pub async fn symbol_ticker_total_stream(&self) -> BinanceResult<(
+        TotalSymbolTickerClient,
+        impl Stream<Item = BinanceResult<SocketPayloadActor<TotalSymbolTickerPayload>>> + use<>,
+    )> {
+        let (process, stream) = ChannelStreamPayloadProcess::new();
+        Ok((self.symbol_ticker_total(process).await?, stream))
+    }
+
+    /// office doc(https://developers.binance.com/docs/binance-spot-api-docs/web-socket-streams#general-wss-information)
+    /// all the subscribed streams share one combined stream connection, every payload comes with its stream
+    pub async fn combined<P>(&self, process: P) -> BinanceResult<CombinedMarketClient>
+    where
+        P: SocketPayloadProcess<CombinedMarketPayload> + Send + 'static,
+    {
+        CombinedMarketClient::create_client(process, &self.environment.spot_combined_socket_uri(), self.socket_config.clone()).await
+    }
+
+    pub async fn combined_stream(&self) -> BinanceResult<(
+        CombinedMarketClient,
+        impl Stream<Item = BinanceResult<SocketPayloadActor<CombinedMarketPayload>>> + use<>,
+    )> {
+        let (process, stream) = ChannelStreamPayloadProcess::new();
+        Ok((self.combined(process).await?, stream))
     }
 }
@@ -1,17 +1,58 @@
+use client::environment::Environment;
+use client::rest::config::Config;
 use client::stream::adaptor::BinanceWebsocketAdaptor;
+use client::stream::config::SocketConfig;
 use crate::userdata::types::event_type::UserDataEventPayload;
 use client::stream::stream::SocketPayloadProcess;
 use crate::userdata::user_data_socket::UserDataClient;
+use crate::userdata::user_data_rest::UserDataRestClient;
+use crate::userdata::user_data_session::UserDataSession;
+use client::rest::client::{BinanceClient, BinanceClientAction};
+use client::rest::layer::authorization::types::Certificate;
+use general::result::BinanceResult;
 
-pub struct BinanceUserdataWebsocketClient;
-
-const SPOT_SOCKET_URI: &str = "wss://stream.binance.com:9443/ws";
+pub struct BinanceUserdataWebsocketClient {
+    environment: Environment,
+    socket_config: SocketConfig,
+}
 
 impl BinanceUserdataWebsocketClient {
-    pub async fn userdata_client<P>(process: P) -> UserDataClient
+    // the hosts are taken from the environment of config
+    pub fn new(config: &Config) -> Self {
+        BinanceUserdataWebsocketClient {
+            environment: config.environment(),
+            socket_config: SocketConfig::new_default(),
+        }
+    }
+
+    pub fn new_default() -> Self {
+        Self::new(&Config::new_default())
+    }
+
+    // the reconnection, rotation, heartbeat and payload buffer of every connection opened by this client
+    pub fn with_socket_config(mut self, socket_config: SocketConfig) -> Self {
+        self.socket_config = socket_config;
+        self
+    }
+
+    pub async fn userdata_client<P>(&self, process: P) -> BinanceResult<UserDataClient>
+    where
+        P: SocketPayloadProcess<UserDataEventPayload> + Send + 'static,
+    {
+        UserDataClient::create_client(process, &self.environment.spot_socket_uri(), self.socket_config.clone()).await
+    }
+
+    // a user data stream which creates, keeps alive, renews and finally deletes its own listen key
+    pub async fn userdata_session<T, P>(
+        &self,
+        rest_client: UserDataRestClient<T>,
+        certificate: Certificate,
+        process: P,
+    ) -> BinanceResult<UserDataSession>
     where
+        T: BinanceClient + BinanceClientAction + Send + Sync + 'static,
         P: SocketPayloadProcess<UserDataEventPayload> + Send + 'static,
     {
-        UserDataClient::create_client(process, SPOT_SOCKET_URI).await
+        UserDataSession::start(rest_client, certificate, &self.environment.spot_socket_uri(), self.socket_config.clone(), process).await
     }
 }
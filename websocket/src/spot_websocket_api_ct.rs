@@ -0,0 +1,32 @@
+use crate::api::spot_api::SpotWebsocketApiClient;
+use client::environment::Environment;
+use client::rest::config::Config;
+use client::wsapi::client::WebsocketApiClient;
+use client::wsapi::config::WsApiConfig;
+use general::result::BinanceResult;
+
+pub struct BinanceSpotWebsocketApiClient {
+    environment: Environment,
+}
+
+impl BinanceSpotWebsocketApiClient {
+    // the hosts are taken from the environment of config
+    pub fn new(config: &Config) -> Self {
+        BinanceSpotWebsocketApiClient {
+            environment: config.environment(),
+        }
+    }
+
+    pub fn new_default() -> Self {
+        Self::new(&Config::new_default())
+    }
+
+    pub async fn api_client(&self) -> BinanceResult<SpotWebsocketApiClient> {
+        self.api_client_with_config(WsApiConfig::new_default()).await
+    }
+
+    pub async fn api_client_with_config(&self, config: WsApiConfig) -> BinanceResult<SpotWebsocketApiClient> {
+        let client = WebsocketApiClient::new_with_config(self.environment.spot_websocket_api_uri(), config).await?;
+        Ok(SpotWebsocketApiClient::new(client))
+    }
+}
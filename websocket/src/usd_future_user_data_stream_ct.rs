@@ -0,0 +1,59 @@
+use crate::userdata::future_user_data_rest::FutureUserDataRestClient;
+use crate::userdata::future_user_data_socket::FutureUserDataClient;
+use crate::userdata::types::future::event_type::UserDataEventPayload;
+use crate::userdata::user_data_session::UserDataSession;
+use client::environment::Environment;
+use client::rest::config::Config;
+use client::rest::client::{BinanceClient, BinanceClientAction};
+use client::rest::layer::authorization::types::Certificate;
+use client::stream::adaptor::BinanceWebsocketAdaptor;
+use client::stream::config::SocketConfig;
+use client::stream::stream::SocketPayloadProcess;
+use general::result::BinanceResult;
+
+pub struct BinanceUsdFutureUserdataWebsocketClient {
+    environment: Environment,
+    socket_config: SocketConfig,
+}
+
+impl BinanceUsdFutureUserdataWebsocketClient {
+    // the hosts are taken from the environment of config
+    pub fn new(config: &Config) -> Self {
+        BinanceUsdFutureUserdataWebsocketClient {
+            environment: config.environment(),
+            socket_config: SocketConfig::new_default(),
+        }
+    }
+
+    pub fn new_default() -> Self {
+        Self::new(&Config::new_default())
+    }
+
+    // the reconnection, rotation, heartbeat and payload buffer of every connection opened by this client
+    pub fn with_socket_config(mut self, socket_config: SocketConfig) -> Self {
+        self.socket_config = socket_config;
+        self
+    }
+
+    /// office doc(https://developers.binance.com/docs/derivatives/usds-margined-futures/user-data-streams/Connect)
+    pub async fn userdata_client<P>(&self, process: P) -> BinanceResult<FutureUserDataClient>
+    where
+        P: SocketPayloadProcess<UserDataEventPayload> + Send + 'static,
+    {
+        FutureUserDataClient::create_client(process, &self.environment.future_socket_uri(), self.socket_config.clone()).await
+    }
+
+    // a user data stream which creates, keeps alive, renews and finally deletes its own listen key
+    pub async fn userdata_session<T, P>(
+        &self,
+        rest_client: FutureUserDataRestClient<T>,
+        certificate: Certificate,
+        process: P,
+    ) -> BinanceResult<UserDataSession>
+    where
+        T: BinanceClient + BinanceClientAction + Send + Sync + 'static,
+        P: SocketPayloadProcess<UserDataEventPayload> + Send + 'static,
+    {
+        UserDataSession::start(rest_client, certificate, &self.environment.future_socket_uri(), self.socket_config.clone(), process).await
+    }
+}
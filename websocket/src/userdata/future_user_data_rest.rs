@@ -0,0 +1,62 @@
+use crate::userdata::types::listen_key::UserDataStream;
+use client::rest::body::EmptyResponseData;
+use client::rest::client::{BinanceClient, BinanceClientAction};
+use client::rest::extension::RequestExtension;
+use client::rest::layer::authorization::types::{AuthType, Certificate};
+use general::result::BinanceResult;
+
+// the listen key of the USD-M futures user data stream, an account has a single listen key,
+// so the keepalive and the delete requests need no listen key.
+pub struct FutureUserDataRestClient<T> {
+    client: T,
+    domain: String,
+}
+
+impl<T> FutureUserDataRestClient<T>
+where
+    T: BinanceClient + BinanceClientAction,
+{
+    pub fn new(client: T) -> Self {
+        let domain = client.get_environment().future_rest_domain().to_string();
+        FutureUserDataRestClient {
+            client,
+            domain,
+        }
+    }
+
+    /// https://developers.binance.com/docs/derivatives/usds-margined-futures/user-data-streams/Start-User-Data-Stream
+    pub async fn create_listen_key(&self, certificate: Certificate) -> BinanceResult<UserDataStream> {
+        self.client
+            .post(
+                None::<String>,
+                "/fapi/v1/listenKey",
+                self.domain.as_str(),
+                RequestExtension::auth_api(AuthType::UserStream, 1, certificate),
+            )
+            .await
+    }
+
+    /// https://developers.binance.com/docs/derivatives/usds-margined-futures/user-data-streams/Keepalive-User-Data-Stream
+    pub async fn put_listen_key(&self, certificate: Certificate) -> BinanceResult<EmptyResponseData> {
+        self.client
+            .put(
+                None::<String>,
+                "/fapi/v1/listenKey",
+                self.domain.as_str(),
+                RequestExtension::auth_api(AuthType::UserStream, 1, certificate),
+            )
+            .await
+    }
+
+    /// https://developers.binance.com/docs/derivatives/usds-margined-futures/user-data-streams/Close-User-Data-Stream
+    pub async fn delete_listen_key(&self, certificate: Certificate) -> BinanceResult<EmptyResponseData> {
+        self.client
+            .delete(
+                None::<String>,
+                "/fapi/v1/listenKey",
+                self.domain.as_str(),
+                RequestExtension::auth_api(AuthType::UserStream, 1, certificate),
+            )
+            .await
+    }
+}
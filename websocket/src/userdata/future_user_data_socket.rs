@@ -0,0 +1,91 @@
+use crate::userdata::types::future::event_type::UserDataEventPayload;
+use crate::userdata::types::listen_key::UserDataStream;
+use async_trait::async_trait;
+use client::stream::adaptor::BinanceWebsocketAdaptor;
+use client::stream::client::WebsocketClient;
+use client::stream::payload::SocketPayloadActor;
+use client::stream::stream::SocketPayloadProcess;
+use client::stream::config::SocketConfig;
+use futures_util::Stream;
+use general::result::BinanceResult;
+use std::pin::Pin;
+
+pub type FutureUserDataResponseStream =
+    Pin<Box<dyn Stream<Item = BinanceResult<SocketPayloadActor<UserDataEventPayload>>> + Send>>;
+
+pub struct FutureUserDataClient {
+    websocket_client: WebsocketClient<UserDataStream>,
+}
+#[async_trait]
+impl BinanceWebsocketAdaptor for FutureUserDataClient {
+    type CLIENT = FutureUserDataClient;
+    type INPUT = String;
+    type OUTPUT = UserDataEventPayload;
+
+    async fn create_client<P>(process: P, uri: &str, socket_config: SocketConfig) -> BinanceResult<Self::CLIENT>
+    where
+        P: SocketPayloadProcess<Self::OUTPUT> + Send + 'static,
+    {
+        let (client, payload_receiver) =
+            WebsocketClient::<UserDataStream>::new_with_config::<UserDataEventPayload>(uri, socket_config).await?;
+        let trade_stream = Box::pin(payload_receiver);
+        tokio::spawn(future_user_data_payload_process(trade_stream, process));
+        Ok(FutureUserDataClient {
+            websocket_client: client,
+        })
+    }
+
+    async fn close(self) {
+        self.websocket_client.close().await
+    }
+
+    async fn subscribe_item(&mut self, input: Self::INPUT) -> BinanceResult<()> {
+        self.websocket_client
+            .subscribe_single(UserDataStream::new(input.as_str()))
+            .await
+    }
+
+    async fn subscribe_items(&mut self, input: Vec<Self::INPUT>) -> BinanceResult<()> {
+        let items = input
+            .iter()
+            .map(|item| UserDataStream::new(item.as_str()))
+            .collect::<Vec<_>>();
+        self.websocket_client
+            .subscribe_multiple(items)
+            .await
+    }
+
+    async fn unsubscribe_item(&mut self, input: Self::INPUT) -> BinanceResult<()> {
+        self.websocket_client
+            .unsubscribe_single(UserDataStream::new(input.as_str()))
+            .await
+    }
+
+    async fn unsubscribe_items(&mut self, input: Vec<Self::INPUT>) -> BinanceResult<()> {
+        let items = input
+            .iter()
+            .map(|item| UserDataStream::new(item.as_str()))
+            .collect::<Vec<_>>();
+        self.websocket_client
+            .unsubscribe_multiple(items)
+            .await
+    }
+
+    fn get_subscribe_items(&self) -> Vec<Self::INPUT> {
+        self.websocket_client
+            .get_all_subscribers()
+            .iter()
+            .map(|item| item.listen_key.clone())
+            .collect::<Vec<_>>()
+    }
+}
+
+pub(crate) async fn future_user_data_payload_process<P>(
+    user_data_stream: FutureUserDataResponseStream,
+    mut processor: P,
+) where
+    P: SocketPayloadProcess<UserDataEventPayload> + Send + 'static,
+{
+    processor.process(user_data_stream).await;
+}
+
@@ -1,3 +1,6 @@
 pub mod types;
 pub mod user_data_rest;
 pub mod user_data_socket;
+pub mod user_data_session;
+pub mod future_user_data_rest;
+pub mod future_user_data_socket;
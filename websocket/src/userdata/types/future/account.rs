@@ -0,0 +1,126 @@
+use bigdecimal::BigDecimal;
+use general::enums::order::PositionSide;
+use general::serialize_extend::from_str_or_number_to_u64;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct AccountUpdateEvent {
+    #[serde(rename = "E")]
+    pub event_time: u64,
+    #[serde(rename = "T")]
+    pub transaction_time: u64,
+    #[serde(rename = "a")]
+    pub update: AccountUpdate,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AccountUpdate {
+    // the reason of the update, e.g. ORDER, FUNDING_FEE, DEPOSIT
+    #[serde(rename = "m")]
+    pub reason: String,
+    #[serde(rename = "B")]
+    pub balances: Vec<FutureBalance>,
+    #[serde(rename = "P")]
+    pub positions: Vec<FuturePosition>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FutureBalance {
+    #[serde(rename = "a")]
+    pub asset: String,
+    #[serde(rename = "wb")]
+    pub wallet_balance: BigDecimal,
+    #[serde(rename = "cw")]
+    pub cross_wallet_balance: BigDecimal,
+    #[serde(rename = "bc")]
+    pub balance_change: BigDecimal,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FuturePosition {
+    #[serde(rename = "s")]
+    pub symbol: String,
+    #[serde(rename = "pa")]
+    pub position_amount: BigDecimal,
+    #[serde(rename = "ep")]
+    pub entry_price: BigDecimal,
+    #[serde(rename = "bep")]
+    pub breakeven_price: BigDecimal,
+    #[serde(rename = "cr")]
+    pub accumulated_realized: BigDecimal,
+    #[serde(rename = "up")]
+    pub unrealized_profit: BigDecimal,
+    #[serde(rename = "mt")]
+    pub margin_type: String,
+    #[serde(rename = "iw")]
+    pub isolated_wallet: BigDecimal,
+    #[serde(rename = "ps")]
+    pub position_side: PositionSide,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MarginCallEvent {
+    #[serde(rename = "E")]
+    pub event_time: u64,
+    // only for isolated positions
+    #[serde(rename = "cw")]
+    pub cross_wallet_balance: Option<BigDecimal>,
+    #[serde(rename = "p")]
+    pub positions: Vec<MarginCallPosition>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MarginCallPosition {
+    #[serde(rename = "s")]
+    pub symbol: String,
+    #[serde(rename = "ps")]
+    pub position_side: PositionSide,
+    #[serde(rename = "pa")]
+    pub position_amount: BigDecimal,
+    #[serde(rename = "mt")]
+    pub margin_type: String,
+    #[serde(rename = "iw")]
+    pub isolated_wallet: BigDecimal,
+    #[serde(rename = "mp")]
+    pub mark_price: BigDecimal,
+    #[serde(rename = "up")]
+    pub unrealized_profit: BigDecimal,
+    #[serde(rename = "mm")]
+    pub maintenance_margin: BigDecimal,
+}
+
+// either the leverage of a symbol or the multi-assets mode of the account changed
+#[derive(Debug, Deserialize)]
+pub struct AccountConfigUpdateEvent {
+    #[serde(rename = "E")]
+    pub event_time: u64,
+    #[serde(rename = "T")]
+    pub transaction_time: u64,
+    #[serde(rename = "ac")]
+    pub leverage: Option<LeverageUpdate>,
+    #[serde(rename = "ai")]
+    pub multi_assets: Option<MultiAssetsUpdate>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LeverageUpdate {
+    #[serde(rename = "s")]
+    pub symbol: String,
+    #[serde(rename = "l")]
+    pub leverage: u32,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MultiAssetsUpdate {
+    #[serde(rename = "j")]
+    pub multi_assets_mode: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FutureListenKeyExpireEvent {
+    // a string in the futures stream
+    #[serde(rename = "E", deserialize_with = "from_str_or_number_to_u64")]
+    pub event_time: u64,
+    #[serde(rename = "listenKey")]
+    pub listen_key: String,
+}
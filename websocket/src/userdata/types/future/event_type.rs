@@ -0,0 +1,88 @@
+use crate::userdata::types::future::account::{
+    AccountConfigUpdateEvent, AccountUpdateEvent, FutureListenKeyExpireEvent, MarginCallEvent,
+};
+use crate::userdata::types::future::order::{OrderTradeUpdateEvent, TradeLiteEvent};
+use serde::Deserialize;
+
+// the events of the USD-M futures user data stream, see
+// https://developers.binance.com/docs/derivatives/usds-margined-futures/user-data-streams
+#[derive(Debug, Deserialize)]
+#[serde(tag = "e")]
+pub enum UserDataEventPayload {
+    #[serde(rename = "ORDER_TRADE_UPDATE")]
+    OrderTradeUpdate(Box<OrderTradeUpdateEvent>),
+    #[serde(rename = "TRADE_LITE")]
+    TradeLite(TradeLiteEvent),
+    #[serde(rename = "ACCOUNT_UPDATE")]
+    AccountUpdate(Box<AccountUpdateEvent>),
+    #[serde(rename = "MARGIN_CALL")]
+    MarginCall(MarginCallEvent),
+    #[serde(rename = "ACCOUNT_CONFIG_UPDATE")]
+    AccountConfigUpdate(AccountConfigUpdateEvent),
+    #[serde(rename = "listenKeyExpired")]
+    ListenKeyExpired(FutureListenKeyExpireEvent),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bigdecimal::BigDecimal;
+    use general::enums::order::PositionSide;
+    use std::str::FromStr;
+
+    fn parse(payload: &str) -> UserDataEventPayload {
+        serde_json::from_str::<UserDataEventPayload>(payload).unwrap()
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let UserDataEventPayload::OrderTradeUpdate(event) = parse(
+            r#"{"e":"ORDER_TRADE_UPDATE","E":1568879465651,"T":1568879465650,"o":{"s":"BTCUSDT","c":"TEST","S":"SELL","o":"TRAILING_STOP_MARKET","f":"GTC","q":"0.001","p":"0","ap":"0","sp":"7103.04","x":"NEW","X":"NEW","i":8886774,"l":"0","z":"0","L":"0","N":"USDT","n":"0","T":1568879465650,"t":0,"b":"0","a":"9.91","m":false,"R":false,"wt":"CONTRACT_PRICE","ot":"TRAILING_STOP_MARKET","ps":"LONG","cp":false,"AP":"7476.89","cr":"5.0","pP":false,"si":0,"ss":0,"rp":"0","V":"EXPIRE_TAKER","pm":"OPPONENT","gtd":0}}"#,
+        ) else {
+            panic!("ORDER_TRADE_UPDATE expected")
+        };
+        assert_eq!(event.order.order_id, 8886774);
+        assert_eq!(event.order.position_side, PositionSide::LONG);
+
+        let UserDataEventPayload::TradeLite(event) = parse(
+            r#"{"e":"TRADE_LITE","E":1721895408092,"T":1721895408214,"s":"BTCUSDT","q":"0.001","p":"0","m":false,"c":"z8hcUoOsqEdKMeKPSABslD","S":"BUY","L":"64089.20","l":"0.040","t":109100866,"i":8886774}"#,
+        ) else {
+            panic!("TRADE_LITE expected")
+        };
+        assert_eq!(event.trade_id, 109100866);
+        assert_eq!(event.last_filled_price, BigDecimal::from_str("64089.20").unwrap());
+
+        let UserDataEventPayload::AccountUpdate(event) = parse(
+            r#"{"e":"ACCOUNT_UPDATE","E":1564745798939,"T":1564745798938,"a":{"m":"ORDER","B":[{"a":"USDT","wb":"122624.12345678","cw":"100.12345678","bc":"50.12345678"}],"P":[{"s":"BTCUSDT","pa":"0","ep":"0.00000","bep":"0","cr":"200","up":"0","mt":"isolated","iw":"0.00000000","ps":"BOTH"}]}}"#,
+        ) else {
+            panic!("ACCOUNT_UPDATE expected")
+        };
+        assert_eq!(event.update.balances[0].asset, "USDT");
+        assert_eq!(event.update.balances[0].wallet_balance, BigDecimal::from_str("122624.12345678").unwrap());
+        assert_eq!(event.update.positions[0].position_side, PositionSide::BOTH);
+
+        let UserDataEventPayload::MarginCall(event) = parse(
+            r#"{"e":"MARGIN_CALL","E":1587727187525,"cw":"3.16812045","p":[{"s":"ETHUSDT","ps":"LONG","pa":"1.327","mt":"CROSSED","iw":"0","mp":"187.17127","up":"-1.166074","mm":"1.614445"}]}"#,
+        ) else {
+            panic!("MARGIN_CALL expected")
+        };
+        assert_eq!(event.positions[0].symbol, "ETHUSDT");
+        assert_eq!(event.positions[0].maintenance_margin, BigDecimal::from_str("1.614445").unwrap());
+
+        let UserDataEventPayload::AccountConfigUpdate(event) =
+            parse(r#"{"e":"ACCOUNT_CONFIG_UPDATE","E":1611646737479,"T":1611646737476,"ac":{"s":"BTCUSDT","l":25}}"#)
+        else {
+            panic!("ACCOUNT_CONFIG_UPDATE expected")
+        };
+        assert_eq!(event.leverage.unwrap().leverage, 25);
+        assert!(event.multi_assets.is_none());
+
+        let UserDataEventPayload::ListenKeyExpired(event) = parse(
+            r#"{"e":"listenKeyExpired","E":"1736996475556","listenKey":"WsCMN0a4KHUPTQuX6IUnqEZfB1inxmv1qR4kbf1LuEjur5VdbzqvyxqG9TSjVVxv"}"#,
+        ) else {
+            panic!("listenKeyExpired expected")
+        };
+        // the event time of listenKeyExpired is sent as a string
+        assert_eq!(event.event_time, 1736996475556);
+    }
+}
@@ -0,0 +1,3 @@
+pub mod event_type;
+pub mod order;
+pub mod account;
@@ -0,0 +1,117 @@
+use bigdecimal::BigDecimal;
+use general::enums::general::{FutureTimeInForce, STPModel};
+use general::enums::order::{FutureOrderType, OrderSide, OrderStatus, PositionSide, WorkingType};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct OrderTradeUpdateEvent {
+    #[serde(rename = "E")]
+    pub event_time: u64,
+    #[serde(rename = "T")]
+    pub transaction_time: u64,
+    #[serde(rename = "o")]
+    pub order: FutureOrder,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FutureOrder {
+    #[serde(rename = "s")]
+    pub symbol: String,
+    #[serde(rename = "c")]
+    pub client_order_id: String,
+    #[serde(rename = "S")]
+    pub order_side: OrderSide,
+    #[serde(rename = "o")]
+    pub order_type: FutureOrderType,
+    #[serde(rename = "f")]
+    pub time_in_force: FutureTimeInForce,
+    #[serde(rename = "q")]
+    pub quantity: BigDecimal,
+    #[serde(rename = "p")]
+    pub price: BigDecimal,
+    #[serde(rename = "ap")]
+    pub average_price: BigDecimal,
+    #[serde(rename = "sp")]
+    pub stop_price: BigDecimal,
+    #[serde(rename = "x")]
+    pub execution_type: String,
+    #[serde(rename = "X")]
+    pub order_status: OrderStatus,
+    #[serde(rename = "i")]
+    pub order_id: u64,
+    #[serde(rename = "l")]
+    pub last_filled_qty: BigDecimal,
+    #[serde(rename = "z")]
+    pub cumulative_filled_qty: BigDecimal,
+    #[serde(rename = "L")]
+    pub last_filled_price: BigDecimal,
+    #[serde(rename = "N")]
+    pub commission_asset: Option<String>,
+    #[serde(rename = "n")]
+    pub commission: Option<BigDecimal>,
+    #[serde(rename = "T")]
+    pub trade_time: u64,
+    #[serde(rename = "t")]
+    pub trade_id: i64,
+    #[serde(rename = "b")]
+    pub bids_notional: BigDecimal,
+    #[serde(rename = "a")]
+    pub ask_notional: BigDecimal,
+    #[serde(rename = "m")]
+    pub is_maker_side: bool,
+    #[serde(rename = "R")]
+    pub is_reduce_only: bool,
+    #[serde(rename = "wt")]
+    pub working_type: WorkingType,
+    #[serde(rename = "ot")]
+    pub original_order_type: FutureOrderType,
+    #[serde(rename = "ps")]
+    pub position_side: PositionSide,
+    #[serde(rename = "cp")]
+    pub is_close_position: bool,
+    // only for TRAILING_STOP_MARKET orders
+    #[serde(rename = "AP")]
+    pub activation_price: Option<BigDecimal>,
+    // only for TRAILING_STOP_MARKET orders
+    #[serde(rename = "cr")]
+    pub callback_rate: Option<BigDecimal>,
+    #[serde(rename = "pP")]
+    pub is_price_protect: bool,
+    #[serde(rename = "rp")]
+    pub realized_profit: BigDecimal,
+    #[serde(rename = "V")]
+    pub self_trade_prevent_mode: Option<STPModel>,
+    #[serde(rename = "pm")]
+    pub price_match: Option<String>,
+    #[serde(rename = "gtd")]
+    pub good_till_date: Option<u64>,
+}
+
+// a lighter and faster copy of the trade of an ORDER_TRADE_UPDATE
+#[derive(Debug, Deserialize)]
+pub struct TradeLiteEvent {
+    #[serde(rename = "E")]
+    pub event_time: u64,
+    #[serde(rename = "T")]
+    pub transaction_time: u64,
+    #[serde(rename = "s")]
+    pub symbol: String,
+    #[serde(rename = "q")]
+    pub quantity: BigDecimal,
+    #[serde(rename = "p")]
+    pub price: BigDecimal,
+    #[serde(rename = "m")]
+    pub is_maker_side: bool,
+    #[serde(rename = "c")]
+    pub client_order_id: String,
+    #[serde(rename = "S")]
+    pub order_side: OrderSide,
+    #[serde(rename = "L")]
+    pub last_filled_price: BigDecimal,
+    #[serde(rename = "l")]
+    pub last_filled_qty: BigDecimal,
+    #[serde(rename = "t")]
+    pub trade_id: i64,
+    #[serde(rename = "i")]
+    pub order_id: u64,
+}
@@ -20,6 +20,10 @@ impl StreamNameFormat for UserDataStream {
     fn stream_name(&self) -> String {
         self.listen_key.clone()
     }
+
+    fn from_stream_name(stream_name: &str) -> Option<Self> {
+        Some(UserDataStream::new(stream_name))
+    }
 }
 
 #[derive(Debug, Deserialize)]
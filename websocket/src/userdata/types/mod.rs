@@ -3,4 +3,4 @@ pub mod event_type;
 pub mod account;
 pub mod balance;
 pub mod order;
-pub mod order_list;
\ No newline at end of file
+pub mod order_list;pub mod future;
@@ -15,13 +15,14 @@ where
     T: BinanceClient + BinanceClientAction,
 {
     pub fn new(client: T) -> Self {
+        let domain = client.get_environment().spot_rest_domain().to_string();
         UserDataRestClient {
             client,
-            domain: "api.binance.com".to_string(),
+            domain,
         }
     }
 
-    async fn create_listen_key(&self, certificate: Certificate) -> BinanceResult<UserDataStream> {
+    pub async fn create_listen_key(&self, certificate: Certificate) -> BinanceResult<UserDataStream> {
         self.client
             .post(
                 None::<String>,
@@ -32,7 +33,7 @@ where
             .await
     }
 
-    async fn put_listen_key(
+    pub async fn put_listen_key(
         &self,
         listen_key: UserDataStream,
         certificate: Certificate,
@@ -47,7 +48,7 @@ where
             .await
     }
 
-    async fn delete_listen_key(
+    pub async fn delete_listen_key(
         &self,
         listen_key: UserDataStream,
         certificate: Certificate,
@@ -0,0 +1,416 @@
+use crate::userdata::future_user_data_rest::FutureUserDataRestClient;
+use crate::userdata::types::event_type::UserDataEventPayload;
+use crate::userdata::types::future::event_type::UserDataEventPayload as FutureUserDataEventPayload;
+use crate::userdata::types::listen_key::UserDataStream;
+use crate::userdata::user_data_rest::UserDataRestClient;
+use async_trait::async_trait;
+use client::rest::client::{BinanceClient, BinanceClientAction};
+use client::rest::layer::authorization::types::Certificate;
+use client::stream::buffer::PayloadReceiver;
+use client::stream::client::WebsocketClient;
+use client::stream::config::SocketConfig;
+use client::stream::payload::SocketPayloadActor;
+use client::stream::stream::SocketPayloadProcess;
+use futures_util::StreamExt;
+use general::result::BinanceResult;
+use serde::de::DeserializeOwned;
+use std::fmt::Debug;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio::sync::mpsc::{channel, Sender};
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+use tokio::time::{Instant, Interval};
+
+// binance closes a listen key which is not kept alive for 60 minutes
+const KEEPALIVE_INTERVAL: Duration = Duration::from_secs(30 * 60);
+// the delay before a failed listen key renewal is tried again
+const RENEW_RETRY_DELAY: Duration = Duration::from_secs(5);
+
+// the listen key endpoints of a market, see
+// https://developers.binance.com/docs/binance-spot-api-docs/user-data-stream
+// https://developers.binance.com/docs/derivatives/usds-margined-futures/user-data-streams
+#[async_trait]
+pub trait ListenKeyApi: Send + Sync + 'static {
+    async fn create_key(&self, certificate: Certificate) -> BinanceResult<UserDataStream>;
+
+    async fn keep_alive_key(&self, listen_key: UserDataStream, certificate: Certificate) -> BinanceResult<()>;
+
+    async fn delete_key(&self, listen_key: UserDataStream, certificate: Certificate) -> BinanceResult<()>;
+}
+
+#[async_trait]
+impl<T> ListenKeyApi for UserDataRestClient<T>
+where
+    T: BinanceClient + BinanceClientAction + Send + Sync + 'static,
+{
+    async fn create_key(&self, certificate: Certificate) -> BinanceResult<UserDataStream> {
+        self.create_listen_key(certificate).await
+    }
+
+    async fn keep_alive_key(&self, listen_key: UserDataStream, certificate: Certificate) -> BinanceResult<()> {
+        self.put_listen_key(listen_key, certificate).await.map(|_| ())
+    }
+
+    async fn delete_key(&self, listen_key: UserDataStream, certificate: Certificate) -> BinanceResult<()> {
+        self.delete_listen_key(listen_key, certificate).await.map(|_| ())
+    }
+}
+
+#[async_trait]
+impl<T> ListenKeyApi for FutureUserDataRestClient<T>
+where
+    T: BinanceClient + BinanceClientAction + Send + Sync + 'static,
+{
+    async fn create_key(&self, certificate: Certificate) -> BinanceResult<UserDataStream> {
+        self.create_listen_key(certificate).await
+    }
+
+    async fn keep_alive_key(&self, _listen_key: UserDataStream, certificate: Certificate) -> BinanceResult<()> {
+        self.put_listen_key(certificate).await.map(|_| ())
+    }
+
+    async fn delete_key(&self, _listen_key: UserDataStream, certificate: Certificate) -> BinanceResult<()> {
+        self.delete_listen_key(certificate).await.map(|_| ())
+    }
+}
+
+// a user data event telling that the listen key can no longer be used
+pub trait ListenKeyEvent {
+    fn is_listen_key_invalidated(&self) -> bool;
+}
+
+impl ListenKeyEvent for UserDataEventPayload {
+    fn is_listen_key_invalidated(&self) -> bool {
+        matches!(
+            self,
+            UserDataEventPayload::ListenKeyExpired(_) | UserDataEventPayload::EventStreamTerminated
+        )
+    }
+}
+
+impl ListenKeyEvent for FutureUserDataEventPayload {
+    fn is_listen_key_invalidated(&self) -> bool {
+        matches!(self, FutureUserDataEventPayload::ListenKeyExpired(_))
+    }
+}
+
+// UserDataSession is a user data stream which manages its own listen key:
+// 1. a listen key is created with the certificate and subscribed,
+// 2. the listen key is kept alive every 30 minutes,
+// 3. when the listen key expires or the event stream is terminated, a new listen key is created and subscribed,
+// 4. when the session is closed, the listen key is deleted.
+// every payload, including the expiration events, is delivered to the processor.
+pub struct UserDataSession {
+    listen_key: Arc<RwLock<String>>,
+    shutdown_sender: oneshot::Sender<()>,
+    session_task: JoinHandle<()>,
+}
+
+impl UserDataSession {
+    pub async fn start<A, O, P>(
+        api: A,
+        certificate: Certificate,
+        uri: &str,
+        socket_config: SocketConfig,
+        mut process: P,
+    ) -> BinanceResult<Self>
+    where
+        A: ListenKeyApi,
+        O: ListenKeyEvent + DeserializeOwned + Send + Debug + 'static,
+        P: SocketPayloadProcess<O> + Send + 'static,
+    {
+        let listen_key = api.create_key(certificate.clone()).await?;
+        let socket = match Self::subscribe(uri, socket_config, listen_key.clone()).await {
+            Ok(socket) => socket,
+            Err(err) => {
+                if let Err(delete_err) = api.delete_key(listen_key, certificate).await {
+                    log::error!("Failed to delete listen key of a failed user data session: {}", delete_err);
+                }
+                return Err(err);
+            }
+        };
+        let (payload_sender, mut payload_receiver) = channel(1);
+        let payload_stream = Box::pin(futures_util::stream::poll_fn(move |cx| payload_receiver.poll_recv(cx)));
+        tokio::spawn(async move { process.process(payload_stream).await });
+
+        let shared_listen_key = Arc::new(RwLock::new(listen_key.listen_key.clone()));
+        let (shutdown_sender, shutdown_receiver) = oneshot::channel();
+        let session = SessionState {
+            api,
+            certificate,
+            listen_key,
+            shared_listen_key: shared_listen_key.clone(),
+            websocket_client: socket.0,
+            payload_sender,
+        };
+        let session_task = tokio::spawn(session.run(socket.1, shutdown_receiver));
+        Ok(UserDataSession {
+            listen_key: shared_listen_key,
+            shutdown_sender,
+            session_task,
+        })
+    }
+
+    async fn subscribe<O>(
+        uri: &str,
+        socket_config: SocketConfig,
+        listen_key: UserDataStream,
+    ) -> BinanceResult<(WebsocketClient<UserDataStream>, PayloadReceiver<O>)>
+    where
+        O: DeserializeOwned + Send + Debug + 'static,
+    {
+        let (mut websocket_client, payload_receiver) =
+            WebsocketClient::<UserDataStream>::new_with_config::<O>(uri, socket_config).await?;
+        if let Err(err) = websocket_client.subscribe_single(listen_key).await {
+            websocket_client.close().await;
+            return Err(err);
+        }
+        Ok((websocket_client, payload_receiver))
+    }
+
+    // the listen key in use, it changes whenever the listen key is renewed
+    pub fn get_listen_key(&self) -> String {
+        self.listen_key.read().unwrap().clone()
+    }
+
+    // delete the listen key and close the connection
+    pub async fn close(self) {
+        let _ = self.shutdown_sender.send(());
+        if let Err(err) = self.session_task.await {
+            log::error!("User data session task failed: {}", err);
+        }
+    }
+}
+
+struct SessionState<A, O> {
+    api: A,
+    certificate: Certificate,
+    listen_key: UserDataStream,
+    shared_listen_key: Arc<RwLock<String>>,
+    websocket_client: WebsocketClient<UserDataStream>,
+    payload_sender: Sender<BinanceResult<SocketPayloadActor<O>>>,
+}
+
+impl<A, O> SessionState<A, O>
+where
+    A: ListenKeyApi,
+    O: ListenKeyEvent + DeserializeOwned + Send + Debug + 'static,
+{
+    async fn run(mut self, mut payload_receiver: PayloadReceiver<O>, mut shutdown_receiver: oneshot::Receiver<()>) {
+        let mut keepalive = tokio::time::interval_at(Instant::now() + KEEPALIVE_INTERVAL, KEEPALIVE_INTERVAL);
+        let mut renew_pending = false;
+        loop {
+            tokio::select! {
+                _ = &mut shutdown_receiver => break,
+                _ = keepalive.tick() => {
+                    if !renew_pending {
+                        match self.api.keep_alive_key(self.listen_key.clone(), self.certificate.clone()).await {
+                            Ok(_) => continue,
+                            Err(err) => log::warn!("Failed to keep the listen key alive, renew it: {}", err),
+                        }
+                    }
+                    renew_pending = !self.renew(&mut keepalive).await;
+                }
+                payload = payload_receiver.next() => {
+                    let Some(payload) = payload else {
+                        log::info!("User data socket is closed, the session ends");
+                        break;
+                    };
+                    let invalidated = matches!(&payload, Ok(SocketPayloadActor::Payload(event)) if event.is_listen_key_invalidated());
+                    if self.payload_sender.send(payload).await.is_err() {
+                        log::info!("User data consumer is gone, the session ends");
+                        break;
+                    }
+                    if invalidated {
+                        log::warn!("Listen key is invalidated, renew it: {}", self.listen_key.listen_key);
+                        renew_pending = !self.renew(&mut keepalive).await;
+                    }
+                }
+            }
+        }
+        if let Err(err) = self.api.delete_key(self.listen_key.clone(), self.certificate.clone()).await {
+            log::error!("Failed to delete listen key: {}", err);
+        }
+        self.websocket_client.close().await;
+    }
+
+    // create a new listen key and move the subscription to it, return false when it must be tried again later
+    async fn renew(&mut self, keepalive: &mut Interval) -> bool {
+        let listen_key = match self.api.create_key(self.certificate.clone()).await {
+            Ok(listen_key) => listen_key,
+            Err(err) => {
+                log::error!("Failed to create a new listen key: {}", err);
+                keepalive.reset_after(RENEW_RETRY_DELAY);
+                return false;
+            }
+        };
+        // binance may return the same listen key, it is subscribed again as the event stream may be terminated
+        if let Err(err) = self.websocket_client.unsubscribe_single(self.listen_key.clone()).await {
+            log::warn!("Failed to unsubscribe the old listen key: {}", err);
+        }
+        if let Err(err) = self.websocket_client.subscribe_single(listen_key.clone()).await {
+            log::error!("Failed to subscribe the new listen key: {}", err);
+            keepalive.reset_after(RENEW_RETRY_DELAY);
+            return false;
+        }
+        *self.shared_listen_key.write().unwrap() = listen_key.listen_key.clone();
+        self.listen_key = listen_key;
+        keepalive.reset();
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::SinkExt;
+    use futures_util::stream::Stream;
+    use serde_json::Value;
+    use std::pin::Pin;
+    use std::sync::Mutex;
+    use tokio::net::TcpListener;
+    use tokio::sync::mpsc::{UnboundedSender, unbounded_channel};
+    use tokio_tungstenite::tungstenite::Message;
+
+    // a listen key api which hands out key1, key2, ... and records every call
+    #[derive(Clone, Default)]
+    struct FakeListenKeyApi {
+        calls: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl FakeListenKeyApi {
+        fn calls(&self) -> Vec<String> {
+            self.calls.lock().unwrap().clone()
+        }
+    }
+
+    #[async_trait]
+    impl ListenKeyApi for FakeListenKeyApi {
+        async fn create_key(&self, _certificate: Certificate) -> BinanceResult<UserDataStream> {
+            let mut calls = self.calls.lock().unwrap();
+            let listen_key = format!("key{}", calls.iter().filter(|call| call.starts_with("create")).count() + 1);
+            calls.push(format!("create:{}", listen_key));
+            Ok(UserDataStream::new(&listen_key))
+        }
+
+        async fn keep_alive_key(&self, listen_key: UserDataStream, _certificate: Certificate) -> BinanceResult<()> {
+            self.calls.lock().unwrap().push(format!("keepalive:{}", listen_key.listen_key));
+            Ok(())
+        }
+
+        async fn delete_key(&self, listen_key: UserDataStream, _certificate: Certificate) -> BinanceResult<()> {
+            self.calls.lock().unwrap().push(format!("delete:{}", listen_key.listen_key));
+            Ok(())
+        }
+    }
+
+    struct ForwardProcess(UnboundedSender<UserDataEventPayload>);
+
+    #[async_trait]
+    impl SocketPayloadProcess<UserDataEventPayload> for ForwardProcess {
+        async fn process(
+            &mut self,
+            mut stream: Pin<Box<dyn Stream<Item = BinanceResult<SocketPayloadActor<UserDataEventPayload>>> + Send>>,
+        ) {
+            while let Some(payload) = stream.next().await {
+                if let Ok(SocketPayloadActor::Payload(event)) = payload {
+                    let _ = self.0.send(event);
+                }
+            }
+        }
+    }
+
+    // a binance user data endpoint which accepts every request and sends the events it is given,
+    // it records the SUBSCRIBE and UNSUBSCRIBE requests as "SUBSCRIBE:key1"
+    async fn fake_binance() -> (String, UnboundedSender<String>, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let uri = format!("ws://{}/ws", listener.local_addr().unwrap());
+        let (event_sender, mut event_receiver) = unbounded_channel::<String>();
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let server_requests = requests.clone();
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut socket = tokio_tungstenite::accept_async(stream).await.unwrap();
+            loop {
+                tokio::select! {
+                    event = event_receiver.recv() => {
+                        let Some(event) = event else { break };
+                        socket.send(Message::Text(event)).await.unwrap();
+                    }
+                    message = socket.next() => {
+                        let Some(Ok(message)) = message else { break };
+                        let Message::Text(text) = message else { continue };
+                        let request = serde_json::from_str::<Value>(&text).unwrap();
+                        for param in request["params"].as_array().into_iter().flatten() {
+                            server_requests.lock().unwrap().push(format!("{}:{}", request["method"].as_str().unwrap(), param.as_str().unwrap()));
+                        }
+                        let response = format!(r#"{{"result":null,"id":{}}}"#, request["id"]);
+                        socket.send(Message::Text(response)).await.unwrap();
+                    }
+                }
+            }
+        });
+        (uri, event_sender, requests)
+    }
+
+    async fn wait_until(condition: impl Fn() -> bool) {
+        for _ in 0..100 {
+            if condition() {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        panic!("condition not met");
+    }
+
+    #[tokio::test]
+    async fn test_session_listen_key() {
+        let (uri, event_sender, requests) = fake_binance().await;
+        let api = FakeListenKeyApi::default();
+        let (process_sender, mut process_receiver) = unbounded_channel();
+        let session = UserDataSession::start(api.clone(), Certificate::new("api", "secret"), &uri, SocketConfig::new_default(), ForwardProcess(process_sender))
+            .await
+            .unwrap();
+        assert_eq!(requests.lock().unwrap().clone(), vec!["SUBSCRIBE:key1"]);
+        assert_eq!(session.get_listen_key(), "key1");
+
+        // the listen key is kept alive every 30 minutes
+        tokio::time::pause();
+        tokio::time::sleep(Duration::from_secs(29 * 60)).await;
+        assert_eq!(api.calls(), vec!["create:key1"]);
+        tokio::time::sleep(Duration::from_secs(60)).await;
+        wait_until(|| api.calls().len() == 2).await;
+        assert_eq!(api.calls(), vec!["create:key1", "keepalive:key1"]);
+        // the renewal waits for binance to answer, the clock runs while the socket is in use
+        tokio::time::resume();
+
+        // an expired listen key is replaced and the subscription moves to the new one
+        event_sender
+            .send(r#"{"e":"listenKeyExpired","E":1576653824250,"listenKey":"key1"}"#.to_string())
+            .unwrap();
+        let event = process_receiver.recv().await.unwrap();
+        assert!(matches!(event, UserDataEventPayload::ListenKeyExpired(event) if event.listen_key == "key1"));
+        wait_until(|| requests.lock().unwrap().len() == 3).await;
+        assert_eq!(requests.lock().unwrap().clone(), vec!["SUBSCRIBE:key1", "UNSUBSCRIBE:key1", "SUBSCRIBE:key2"]);
+        assert_eq!(session.get_listen_key(), "key2");
+
+        // so is a listen key whose event stream is terminated
+        event_sender.send(r#"{"e":"eventStreamTerminated","E":1728973001334}"#.to_string()).unwrap();
+        assert!(matches!(process_receiver.recv().await.unwrap(), UserDataEventPayload::EventStreamTerminated));
+        wait_until(|| requests.lock().unwrap().len() == 5).await;
+        assert_eq!(session.get_listen_key(), "key3");
+
+        // the keepalive restarts with the new listen key
+        tokio::time::pause();
+        tokio::time::sleep(Duration::from_secs(29 * 60)).await;
+        assert_eq!(api.calls().last().unwrap(), "create:key3");
+        tokio::time::sleep(Duration::from_secs(60)).await;
+        wait_until(|| api.calls().last().unwrap() == "keepalive:key3").await;
+        tokio::time::resume();
+
+        // closing the session deletes the listen key in use
+        session.close().await;
+        assert_eq!(api.calls(), vec!["create:key1", "keepalive:key1", "create:key2", "create:key3", "keepalive:key3", "delete:key3"]);
+    }
+}
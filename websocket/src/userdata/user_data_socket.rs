@@ -5,6 +5,7 @@ use client::stream::adaptor::BinanceWebsocketAdaptor;
 use client::stream::client::WebsocketClient;
 use client::stream::payload::SocketPayloadActor;
 use client::stream::stream::SocketPayloadProcess;
+use client::stream::config::SocketConfig;
 use futures_util::Stream;
 use general::result::BinanceResult;
 use std::pin::Pin;
@@ -21,33 +22,30 @@ impl BinanceWebsocketAdaptor for UserDataClient {
     type INPUT = String;
     type OUTPUT = UserDataEventPayload;
 
-    async fn create_client<P>(process: P, uri: &str) -> Self::CLIENT
+    async fn create_client<P>(process: P, uri: &str, socket_config: SocketConfig) -> BinanceResult<Self::CLIENT>
     where
         P: SocketPayloadProcess<Self::OUTPUT> + Send + 'static,
     {
         let (client, payload_receiver) =
-            WebsocketClient::<UserDataStream>::new_with_uri::<UserDataEventPayload>(uri).await;
-        let trade_stream = Box::pin(tokio_stream::wrappers::UnboundedReceiverStream::new(
-            payload_receiver,
-        ));
+            WebsocketClient::<UserDataStream>::new_with_config::<UserDataEventPayload>(uri, socket_config).await?;
+        let trade_stream = Box::pin(payload_receiver);
         tokio::spawn(user_data_payload_process(trade_stream, process));
-        UserDataClient {
+        Ok(UserDataClient {
             websocket_client: client,
-        }
+        })
     }
 
     async fn close(self) {
         self.websocket_client.close().await
     }
 
-    async fn subscribe_item(&mut self, input: Self::INPUT) {
+    async fn subscribe_item(&mut self, input: Self::INPUT) -> BinanceResult<()> {
         self.websocket_client
             .subscribe_single(UserDataStream::new(input.as_str()))
             .await
-            .unwrap();
     }
 
-    async fn subscribe_items(&mut self, input: Vec<Self::INPUT>) {
+    async fn subscribe_items(&mut self, input: Vec<Self::INPUT>) -> BinanceResult<()> {
         let items = input
             .iter()
             .map(|item| UserDataStream::new(item.as_str()))
@@ -55,17 +53,15 @@ impl BinanceWebsocketAdaptor for UserDataClient {
         self.websocket_client
             .subscribe_multiple(items)
             .await
-            .unwrap();
     }
 
-    async fn unsubscribe_item(&mut self, input: Self::INPUT) {
+    async fn unsubscribe_item(&mut self, input: Self::INPUT) -> BinanceResult<()> {
         self.websocket_client
             .unsubscribe_single(UserDataStream::new(input.as_str()))
             .await
-            .unwrap();
     }
 
-    async fn unsubscribe_items(&mut self, input: Vec<Self::INPUT>) {
+    async fn unsubscribe_items(&mut self, input: Vec<Self::INPUT>) -> BinanceResult<()> {
         let items = input
             .iter()
             .map(|item| UserDataStream::new(item.as_str()))
@@ -73,7 +69,6 @@ impl BinanceWebsocketAdaptor for UserDataClient {
         self.websocket_client
             .unsubscribe_multiple(items)
             .await
-            .unwrap();
     }
 
     fn get_subscribe_items(&self) -> Vec<Self::INPUT> {
@@ -108,7 +103,7 @@ mod tests {
     //         .filter(None, log::LevelFilter::Debug)
     //         .init();
     //
-    //     let mut client = BinanceUserdataWebsocketClient::userdata_client(DefaultStreamPayloadProcess::default()).await;
+    //     let mut client = BinanceUserdataWebsocketClient::new_default().userdata_client(DefaultStreamPayloadProcess::default()).await;
     //     client
     //         .subscribe_item("jxSl0enlQguoDW2J3AvP4HEBKVXe4zKkh4PxE5CCMYhLVOCqIJnDjIa8nOxh".to_string())
     //         .await;
@@ -0,0 +1,50 @@
+use bigdecimal::BigDecimal;
+use client::wsapi::message::{signature_payload, to_params, WsApiEventMessage};
+use general::enums::general::TimeInForce;
+use general::enums::order::{OrderSide, OrderType};
+use rest_api::types::market::order_book::CommonReq;
+use rest_api::types::market::price::SymbolReq;
+use rest_api::types::order::general::create::CreateOrderReqBuilder;
+use std::str::FromStr;
+use websocket::spot_websocket_api_ct::BinanceSpotWebsocketApiClient;
+use websocket::userdata::types::event_type::UserDataEventPayload;
+
+#[test]
+fn spot_api_order_params_test() {
+    let request = CreateOrderReqBuilder::new_builder()
+        .symbol("BTCUSDT")
+        .side(OrderSide::BUY)
+        .order_type(OrderType::LIMIT)
+        .time_in_force(TimeInForce::GTC)
+        .quantity(BigDecimal::from_str("0.01").unwrap())
+        .price(BigDecimal::from_str("52000.5").unwrap())
+        .build()
+        .unwrap();
+    let params = to_params(&request).unwrap();
+    assert_eq!(
+        signature_payload(&params),
+        "price=52000.5&quantity=0.01&side=BUY&symbol=BTCUSDT&timeInForce=GTC&type=LIMIT"
+    );
+}
+
+#[test]
+fn spot_api_user_data_event_test() {
+    let message = r#"{"subscriptionId":0,"event":{"e":"balanceUpdate","E":1573200697110,"a":"BTC","d":"100.00000000","T":1573200697068}}"#;
+    let message = serde_json::from_str::<WsApiEventMessage>(message).unwrap();
+    assert_eq!(message.subscription_id, Some(0));
+    let event = serde_json::from_value::<UserDataEventPayload>(message.event).unwrap();
+    assert!(matches!(event, UserDataEventPayload::BalanceUpdate(balance) if balance.asset == "BTC"));
+}
+
+#[tokio::test]
+async fn spot_api_market_data_test() {
+    let client = BinanceSpotWebsocketApiClient::new_default().api_client().await.unwrap();
+    client.ping().await.unwrap();
+    let time = client.get_server_time().await.unwrap();
+    println!("{:?}", time);
+    let order_book = client.get_order_book(CommonReq::new("BTCUSDT", 5)).await.unwrap();
+    assert!(order_book.bids.len() <= 5);
+    let ticker = client.get_symbol_price_ticker(SymbolReq::new_with_single("BTCUSDT")).await.unwrap();
+    println!("{:?}", ticker);
+    client.close().await;
+}
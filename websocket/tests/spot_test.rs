@@ -1,13 +1,20 @@
+use client::rest::client::BinanceClient;
+use client::rest::config::Config;
+use client::rest::rest_client::BinanceRestClient;
 use client::stream::adaptor::BinanceWebsocketAdaptor;
 use client::stream::stream::DefaultStreamPayloadProcess;
 use env_logger::Builder;
+use futures_util::StreamExt;
 use general::enums::interval::Interval;
 use general::enums::level::Level;
+use general::enums::speed::Speed;
 use general::enums::timezone::Timezone;
 use general::enums::window_size::WindowSize;
 use general::symbol::Symbol;
 use std::time::Duration;
 use tokio::time::sleep;
+use rest_api::spot_market_ct::SpotMarketClient;
+use websocket::market::order_book_ct::OrderBookChangeKind;
 use websocket::market::types::symbol_mini_ticker::TotalSymbolMiniTickerStream;
 use websocket::market::types::symbol_ticker::TotalSymbolTickerStream;
 use websocket::spot_market_socket_ct::BinanceSpotMarketWebsocketClient;
@@ -18,8 +25,8 @@ async fn spot_agg_trade_test() {
         .filter(None, log::LevelFilter::Info)
         .init();
     let mut client =
-        BinanceSpotMarketWebsocketClient::agg_trade(DefaultStreamPayloadProcess::new()).await;
-    client.subscribe_item(Symbol::new("ETHUSDT")).await;
+        BinanceSpotMarketWebsocketClient::new_default().agg_trade(DefaultStreamPayloadProcess::new()).await.unwrap();
+    client.subscribe_item(Symbol::new("ETHUSDT")).await.unwrap();
     sleep(Duration::from_secs(2)).await;
     client.close().await;
     sleep(Duration::from_secs(2)).await;
@@ -32,28 +39,41 @@ async fn spot_trade_test() {
         .filter(None, log::LevelFilter::Info)
         .init();
     let mut client =
-        BinanceSpotMarketWebsocketClient::trade(DefaultStreamPayloadProcess::new()).await;
-    client.subscribe_item(Symbol::new("ETHUSDT")).await;
+        BinanceSpotMarketWebsocketClient::new_default().trade(DefaultStreamPayloadProcess::new()).await.unwrap();
+    client.subscribe_item(Symbol::new("ETHUSDT")).await.unwrap();
     sleep(Duration::from_secs(2)).await;
     client.close().await;
     sleep(Duration::from_secs(2)).await;
     print!("over");
 }
 
+#[tokio::test]
+async fn spot_trade_stream_test() {
+    Builder::from_default_env()
+        .filter(None, log::LevelFilter::Info)
+        .init();
+    let (mut client, stream) = BinanceSpotMarketWebsocketClient::new_default().trade_stream().await.unwrap();
+    client.subscribe_item(Symbol::new("ETHUSDT")).await.unwrap();
+    let payloads = stream.take(3).collect::<Vec<_>>().await;
+    assert!(payloads.iter().all(|payload| payload.is_ok()));
+    client.close().await;
+    print!("over");
+}
+
 #[tokio::test]
 async fn spot_kline_test() {
     Builder::from_default_env()
         .filter(None, log::LevelFilter::Info)
         .init();
     let mut client =
-        BinanceSpotMarketWebsocketClient::kline(DefaultStreamPayloadProcess::new()).await;
+        BinanceSpotMarketWebsocketClient::new_default().kline(DefaultStreamPayloadProcess::new()).await.unwrap();
     client
         .subscribe_item((
             Symbol::new("ETHUSDT"),
             Interval::Second1,
             Some(Timezone::UTC8),
         ))
-        .await;
+        .await.unwrap();
     sleep(Duration::from_secs(2)).await;
     client.close().await;
     sleep(Duration::from_secs(2)).await;
@@ -67,9 +87,9 @@ async fn spot_individual_symbol_mini_ticker() {
         .init();
 
     let mut client =
-        BinanceSpotMarketWebsocketClient::symbol_mini_ticker(DefaultStreamPayloadProcess::new())
-            .await;
-    client.subscribe_item(Symbol::new("ETHUSDT")).await;
+        BinanceSpotMarketWebsocketClient::new_default().symbol_mini_ticker(DefaultStreamPayloadProcess::new())
+            .await.unwrap();
+    client.subscribe_item(Symbol::new("ETHUSDT")).await.unwrap();
     sleep(Duration::from_secs(2)).await;
     client.close().await;
     sleep(Duration::from_secs(2)).await;
@@ -81,13 +101,13 @@ async fn spot_total_symbol_mini_ticker() {
     Builder::from_default_env()
         .filter(None, log::LevelFilter::Info)
         .init();
-    let mut client = BinanceSpotMarketWebsocketClient::symbol_mini_ticker_total(
+    let mut client = BinanceSpotMarketWebsocketClient::new_default().symbol_mini_ticker_total(
         DefaultStreamPayloadProcess::new(),
     )
-    .await;
+    .await.unwrap();
     client
         .subscribe_item(TotalSymbolMiniTickerStream::default())
-        .await;
+        .await.unwrap();
     sleep(Duration::from_secs(2)).await;
     client.close().await;
     sleep(Duration::from_secs(2)).await;
@@ -100,8 +120,8 @@ async fn spot_individual_symbol_ticker() {
         .filter(None, log::LevelFilter::Info)
         .init();
     let mut client =
-        BinanceSpotMarketWebsocketClient::symbol_ticker(DefaultStreamPayloadProcess::new()).await;
-    client.subscribe_item(Symbol::new("ETHUSDT")).await;
+        BinanceSpotMarketWebsocketClient::new_default().symbol_ticker(DefaultStreamPayloadProcess::new()).await.unwrap();
+    client.subscribe_item(Symbol::new("ETHUSDT")).await.unwrap();
     sleep(Duration::from_secs(2)).await;
     client.close().await;
     sleep(Duration::from_secs(2)).await;
@@ -114,11 +134,11 @@ async fn spot_total_symbol_ticker() {
         .filter(None, log::LevelFilter::Info)
         .init();
     let mut client =
-        BinanceSpotMarketWebsocketClient::symbol_ticker_total(DefaultStreamPayloadProcess::new())
-            .await;
+        BinanceSpotMarketWebsocketClient::new_default().symbol_ticker_total(DefaultStreamPayloadProcess::new())
+            .await.unwrap();
     client
         .subscribe_item(TotalSymbolTickerStream::default())
-        .await;
+        .await.unwrap();
     sleep(Duration::from_secs(2)).await;
     client.close().await;
     sleep(Duration::from_secs(2)).await;
@@ -131,11 +151,11 @@ async fn spot_symbol_rolling_window_test() {
         .filter(None, log::LevelFilter::Info)
         .init();
     let mut client =
-        BinanceSpotMarketWebsocketClient::symbol_rolling_ticker(DefaultStreamPayloadProcess::new())
-            .await;
+        BinanceSpotMarketWebsocketClient::new_default().symbol_rolling_ticker(DefaultStreamPayloadProcess::new())
+            .await.unwrap();
     client
         .subscribe_item((Symbol::new("ETHUSDT"), WindowSize::OneHour))
-        .await;
+        .await.unwrap();
     sleep(Duration::from_secs(2)).await;
     client.close().await;
     sleep(Duration::from_secs(2)).await;
@@ -147,11 +167,11 @@ async fn spot_total_symbol_rolling_window() {
     Builder::from_default_env()
         .filter(None, log::LevelFilter::Info)
         .init();
-    let mut client = BinanceSpotMarketWebsocketClient::symbol_rolling_ticker_total(
+    let mut client = BinanceSpotMarketWebsocketClient::new_default().symbol_rolling_ticker_total(
         DefaultStreamPayloadProcess::new(),
     )
-    .await;
-    client.subscribe_item(WindowSize::OneHour).await;
+    .await.unwrap();
+    client.subscribe_item(WindowSize::OneHour).await.unwrap();
     sleep(Duration::from_secs(2)).await;
     client.close().await;
     sleep(Duration::from_secs(2)).await;
@@ -164,9 +184,9 @@ async fn spot_symbol_book_test() {
         .filter(None, log::LevelFilter::Info)
         .init();
     let mut client =
-        BinanceSpotMarketWebsocketClient::symbol_book_ticker(DefaultStreamPayloadProcess::new())
-            .await;
-    client.subscribe_item(Symbol::new("ETHUSDT")).await;
+        BinanceSpotMarketWebsocketClient::new_default().symbol_book_ticker(DefaultStreamPayloadProcess::new())
+            .await.unwrap();
+    client.subscribe_item(Symbol::new("ETHUSDT")).await.unwrap();
     sleep(Duration::from_secs(2)).await;
     client.close().await;
     sleep(Duration::from_secs(2)).await;
@@ -179,8 +199,8 @@ async fn spot_average_price_test() {
         .filter(None, log::LevelFilter::Info)
         .init();
     let mut client =
-        BinanceSpotMarketWebsocketClient::average_price(DefaultStreamPayloadProcess::new()).await;
-    client.subscribe_item(Symbol::new("ETHUSDT")).await;
+        BinanceSpotMarketWebsocketClient::new_default().average_price(DefaultStreamPayloadProcess::new()).await.unwrap();
+    client.subscribe_item(Symbol::new("ETHUSDT")).await.unwrap();
     sleep(Duration::from_secs(2)).await;
     client.close().await;
     sleep(Duration::from_secs(2)).await;
@@ -193,11 +213,11 @@ async fn spot_partial_book_depth_test() {
         .filter(None, log::LevelFilter::Info)
         .init();
     let mut client =
-        BinanceSpotMarketWebsocketClient::partial_book_depth(DefaultStreamPayloadProcess::new())
-            .await;
+        BinanceSpotMarketWebsocketClient::new_default().partial_book_depth(DefaultStreamPayloadProcess::new())
+            .await.unwrap();
     client
         .subscribe_item((Symbol::new("ETHUSDT"), Level::L1, None))
-        .await;
+        .await.unwrap();
     sleep(Duration::from_secs(2)).await;
     client.close().await;
     sleep(Duration::from_secs(2)).await;
@@ -210,10 +230,27 @@ async fn spot_diff_book_depth_test() {
         .filter(None, log::LevelFilter::Info)
         .init();
     let mut client =
-        BinanceSpotMarketWebsocketClient::diff_book_depth(DefaultStreamPayloadProcess::new()).await;
-    client.subscribe_item((Symbol::new("ETHUSDT"), None)).await;
+        BinanceSpotMarketWebsocketClient::new_default().diff_book_depth(DefaultStreamPayloadProcess::new()).await.unwrap();
+    client.subscribe_item((Symbol::new("ETHUSDT"), None)).await.unwrap();
     sleep(Duration::from_secs(2)).await;
     client.close().await;
     sleep(Duration::from_secs(2)).await;
     print!("over");
 }
+
+#[tokio::test]
+async fn spot_order_book_test() {
+    Builder::from_default_env()
+        .filter(None, log::LevelFilter::Info)
+        .init();
+    let market_client = SpotMarketClient::new(BinanceRestClient::build_client(Config::new_default()));
+    let (mut client, handle) = BinanceSpotMarketWebsocketClient::new_default().order_book(market_client, 1000).await.unwrap();
+    let mut changes = handle.subscribe_changes();
+    client.subscribe_item((Symbol::new("ETHUSDT"), Some(Speed::Ms100))).await.unwrap();
+    while changes.recv().await.unwrap().kind != OrderBookChangeKind::Synced {}
+    println!("best bid: {:?}", handle.get_best_bid("ETHUSDT"));
+    println!("best ask: {:?}", handle.get_best_ask("ETHUSDT"));
+    println!("depth: {:?}", handle.get_depth("ETHUSDT", 5));
+    client.close().await;
+    print!("over");
+}
@@ -23,9 +23,9 @@ async fn usd_future_test_trade() {
         .filter(None, log::LevelFilter::Info)
         .init();
     let mut client =
-        BinanceUsdFutureMarketWebsocketClient::trade(DefaultStreamPayloadProcess::new()).await;
-    client.subscribe_item(Symbol::new("BANUSDT")).await;
-    client.subscribe_item(Symbol::new("BBUSDT")).await;
+        BinanceUsdFutureMarketWebsocketClient::new_default().trade(DefaultStreamPayloadProcess::new()).await.unwrap();
+    client.subscribe_item(Symbol::new("BANUSDT")).await.unwrap();
+    client.subscribe_item(Symbol::new("BBUSDT")).await.unwrap();
     sleep(Duration::from_millis(8000)).await;
     client.close().await;
     sleep(Duration::from_millis(2000)).await;
@@ -38,9 +38,9 @@ async fn usd_future_test_agg_trade() {
         .filter(None, log::LevelFilter::Info)
         .init();
     let mut client =
-        BinanceUsdFutureMarketWebsocketClient::agg_trade(DefaultStreamPayloadProcess::new()).await;
-    client.subscribe_item(Symbol::new("BANUSDT")).await;
-    client.subscribe_item(Symbol::new("BBUSDT")).await;
+        BinanceUsdFutureMarketWebsocketClient::new_default().agg_trade(DefaultStreamPayloadProcess::new()).await.unwrap();
+    client.subscribe_item(Symbol::new("BANUSDT")).await.unwrap();
+    client.subscribe_item(Symbol::new("BBUSDT")).await.unwrap();
     sleep(Duration::from_millis(10000)).await;
     client.close().await;
     sleep(Duration::from_millis(2000)).await;
@@ -53,9 +53,9 @@ async fn usd_future_test_mark_price() {
         .filter(None, log::LevelFilter::Info)
         .init();
     let mut client =
-        BinanceUsdFutureMarketWebsocketClient::mark_price(DefaultStreamPayloadProcess::new()).await;
-    client.subscribe_item((Symbol::new("BANUSDT"), true)).await;
-    client.subscribe_item((Symbol::new("BBUSDT"), false)).await;
+        BinanceUsdFutureMarketWebsocketClient::new_default().mark_price(DefaultStreamPayloadProcess::new()).await.unwrap();
+    client.subscribe_item((Symbol::new("BANUSDT"), true)).await.unwrap();
+    client.subscribe_item((Symbol::new("BBUSDT"), false)).await.unwrap();
     sleep(Duration::from_millis(10000)).await;
     client.close().await;
     sleep(Duration::from_millis(2000)).await;
@@ -68,11 +68,11 @@ async fn usd_future_test_mark_all_price() {
         .filter(None, log::LevelFilter::Info)
         .init();
     let mut client =
-        BinanceUsdFutureMarketWebsocketClient::mark_price_total(DefaultStreamPayloadProcess::new())
-            .await;
+        BinanceUsdFutureMarketWebsocketClient::new_default().mark_price_total(DefaultStreamPayloadProcess::new())
+            .await.unwrap();
     client
         .subscribe_item(TotalMarkPriceStream::new(false))
-        .await;
+        .await.unwrap();
     sleep(Duration::from_millis(5000)).await;
     client.close().await;
     sleep(Duration::from_millis(2000)).await;
@@ -85,10 +85,10 @@ async fn usd_future_test_kline_candle() {
         .filter(None, log::LevelFilter::Info)
         .init();
     let mut client =
-        BinanceUsdFutureMarketWebsocketClient::kline(DefaultStreamPayloadProcess::new()).await;
+        BinanceUsdFutureMarketWebsocketClient::new_default().kline(DefaultStreamPayloadProcess::new()).await.unwrap();
     client
         .subscribe_item((Symbol::new("BANUSDT"), Interval::Minute1, None))
-        .await;
+        .await.unwrap();
     sleep(Duration::from_secs(10000)).await;
     client.close().await;
     sleep(Duration::from_millis(5000)).await;
@@ -101,15 +101,15 @@ async fn usd_future_test_continuous_contract_kline() {
         .filter(None, log::LevelFilter::Info)
         .init();
     let mut client =
-        BinanceUsdFutureMarketWebsocketClient::continuous_kline(DefaultStreamPayloadProcess::new())
-            .await;
+        BinanceUsdFutureMarketWebsocketClient::new_default().continuous_kline(DefaultStreamPayloadProcess::new())
+            .await.unwrap();
     client
         .subscribe_item((
             Symbol::new("BTCUSDT"),
             ContractType::Perpetual,
             Interval::Minute1,
         ))
-        .await;
+        .await.unwrap();
     sleep(Duration::from_secs(10)).await;
     client.close().await;
     sleep(Duration::from_millis(5000)).await;
@@ -121,11 +121,11 @@ async fn usd_future_test_individual_symbol_mini_ticker() {
     Builder::from_default_env()
         .filter(None, log::LevelFilter::Info)
         .init();
-    let mut client = BinanceUsdFutureMarketWebsocketClient::symbol_mini_ticker(
+    let mut client = BinanceUsdFutureMarketWebsocketClient::new_default().symbol_mini_ticker(
         DefaultStreamPayloadProcess::new(),
     )
-    .await;
-    client.subscribe_item(Symbol::new("BANUSDT")).await;
+    .await.unwrap();
+    client.subscribe_item(Symbol::new("BANUSDT")).await.unwrap();
     sleep(Duration::from_secs(2)).await;
     client.close().await;
     sleep(Duration::from_millis(5000)).await;
@@ -138,9 +138,9 @@ async fn usd_future_test_symbol_ticker() {
         .filter(None, log::LevelFilter::Info)
         .init();
     let mut client =
-        BinanceUsdFutureMarketWebsocketClient::symbol_ticker(DefaultStreamPayloadProcess::new())
-            .await;
-    client.subscribe_item(Symbol::new("BANUSDT")).await;
+        BinanceUsdFutureMarketWebsocketClient::new_default().symbol_ticker(DefaultStreamPayloadProcess::new())
+            .await.unwrap();
+    client.subscribe_item(Symbol::new("BANUSDT")).await.unwrap();
     sleep(Duration::from_secs(5)).await;
     client.close().await;
     sleep(Duration::from_millis(5000)).await;
@@ -152,13 +152,13 @@ async fn usd_future_test_total_symbol_ticker() {
     Builder::from_default_env()
         .filter(None, log::LevelFilter::Info)
         .init();
-    let mut client = BinanceUsdFutureMarketWebsocketClient::symbol_ticker_total(
+    let mut client = BinanceUsdFutureMarketWebsocketClient::new_default().symbol_ticker_total(
         DefaultStreamPayloadProcess::new(),
     )
-    .await;
+    .await.unwrap();
     client
         .subscribe_item(TotalSymbolTickerStream::default())
-        .await;
+        .await.unwrap();
     sleep(Duration::from_secs(3)).await;
     client.close().await;
     sleep(Duration::from_millis(5000)).await;
@@ -170,13 +170,13 @@ async fn usd_future_test_total_symbol_mini_ticker() {
     Builder::from_default_env()
         .filter(None, log::LevelFilter::Info)
         .init();
-    let mut client = BinanceUsdFutureMarketWebsocketClient::symbol_mini_ticker_total(
+    let mut client = BinanceUsdFutureMarketWebsocketClient::new_default().symbol_mini_ticker_total(
         DefaultStreamPayloadProcess::new(),
     )
-    .await;
+    .await.unwrap();
     client
         .subscribe_item(TotalSymbolMiniTickerStream::default())
-        .await;
+        .await.unwrap();
     sleep(Duration::from_secs(3)).await;
     client.close().await;
     sleep(Duration::from_millis(5000)).await;
@@ -188,11 +188,11 @@ async fn usd_future_test_symbol_book_ticker() {
     Builder::from_default_env()
         .filter(None, log::LevelFilter::Info)
         .init();
-    let mut client = BinanceUsdFutureMarketWebsocketClient::symbol_book_ticker(
+    let mut client = BinanceUsdFutureMarketWebsocketClient::new_default().symbol_book_ticker(
         DefaultStreamPayloadProcess::new(),
     )
-    .await;
-    client.subscribe_item(Symbol::new("BBUSDT")).await;
+    .await.unwrap();
+    client.subscribe_item(Symbol::new("BBUSDT")).await.unwrap();
     sleep(Duration::from_secs(3)).await;
     client.close().await;
     sleep(Duration::from_millis(5000)).await;
@@ -204,13 +204,13 @@ async fn usd_future_test_all_symbol_book_ticker() {
     Builder::from_default_env()
         .filter(None, log::LevelFilter::Info)
         .init();
-    let mut client = BinanceUsdFutureMarketWebsocketClient::symbol_book_ticker_total(
+    let mut client = BinanceUsdFutureMarketWebsocketClient::new_default().symbol_book_ticker_total(
         DefaultStreamPayloadProcess::new(),
     )
-    .await;
+    .await.unwrap();
     client
         .subscribe_item(TotalSymbolBookTickerStream::default())
-        .await;
+        .await.unwrap();
     sleep(Duration::from_secs(3)).await;
     client.close().await;
     sleep(Duration::from_millis(5000)).await;
@@ -222,11 +222,11 @@ async fn usd_future_test_liquidation_order() {
     Builder::from_default_env()
         .filter(None, log::LevelFilter::Info)
         .init();
-    let mut client = BinanceUsdFutureMarketWebsocketClient::liquidation_order(
+    let mut client = BinanceUsdFutureMarketWebsocketClient::new_default().liquidation_order(
         DefaultStreamPayloadProcess::new(),
     )
-    .await;
-    client.subscribe_item(Symbol::new("USUALUSDT")).await;
+    .await.unwrap();
+    client.subscribe_item(Symbol::new("USUALUSDT")).await.unwrap();
     sleep(Duration::from_secs(20)).await;
     client.close().await;
     sleep(Duration::from_millis(5000)).await;
@@ -238,13 +238,13 @@ async fn usd_future_test_total_liquidation_order() {
     Builder::from_default_env()
         .filter(None, log::LevelFilter::Info)
         .init();
-    let mut client = BinanceUsdFutureMarketWebsocketClient::liquidation_order_total(
+    let mut client = BinanceUsdFutureMarketWebsocketClient::new_default().liquidation_order_total(
         DefaultStreamPayloadProcess::new(),
     )
-    .await;
+    .await.unwrap();
     client
         .subscribe_item(TotalLiquidationOrderStream::default())
-        .await;
+        .await.unwrap();
     sleep(Duration::from_secs(20)).await;
     client.close().await;
     sleep(Duration::from_millis(5000)).await;
@@ -256,13 +256,13 @@ async fn usd_future_test_partial_book_depth() {
     Builder::from_default_env()
         .filter(None, log::LevelFilter::Info)
         .init();
-    let mut client = BinanceUsdFutureMarketWebsocketClient::partial_book_depth(
+    let mut client = BinanceUsdFutureMarketWebsocketClient::new_default().partial_book_depth(
         DefaultStreamPayloadProcess::new(),
     )
-    .await;
+    .await.unwrap();
     client
         .subscribe_item((Symbol::new("ETHUSDT"), Level::L1, Some(Speed::Ms100)))
-        .await;
+        .await.unwrap();
     sleep(Duration::from_secs(2)).await;
     client.close().await;
     sleep(Duration::from_millis(5000)).await;
@@ -275,9 +275,9 @@ async fn usd_future_test_diff_book_depth() {
         .filter(None, log::LevelFilter::Info)
         .init();
     let mut client =
-        BinanceUsdFutureMarketWebsocketClient::diff_book_depth(DefaultStreamPayloadProcess::new())
-            .await;
-    client.subscribe_item((Symbol::new("ETHUSDT"), None)).await;
+        BinanceUsdFutureMarketWebsocketClient::new_default().diff_book_depth(DefaultStreamPayloadProcess::new())
+            .await.unwrap();
+    client.subscribe_item((Symbol::new("ETHUSDT"), None)).await.unwrap();
     sleep(Duration::from_secs(2)).await;
     client.close().await;
     sleep(Duration::from_millis(5000)).await;
@@ -290,11 +290,11 @@ async fn usd_future_test_composite_index_symbol() {
     Builder::from_default_env()
         .filter(None, log::LevelFilter::Info)
         .init();
-    let mut client = BinanceUsdFutureMarketWebsocketClient::composite_index_symbol(
+    let mut client = BinanceUsdFutureMarketWebsocketClient::new_default().composite_index_symbol(
         DefaultStreamPayloadProcess::new(),
     )
-    .await;
-    client.subscribe_item(Symbol::new("BTCUSDT")).await;
+    .await.unwrap();
+    client.subscribe_item(Symbol::new("BTCUSDT")).await.unwrap();
     sleep(Duration::from_secs(30)).await;
     client.close().await;
     sleep(Duration::from_millis(5000)).await;
@@ -306,8 +306,8 @@ async fn usd_future_test_contract_info() {
     Builder::from_default_env()
     .filter(None, log::LevelFilter::Info)
     .init();
-    let mut client = BinanceUsdFutureMarketWebsocketClient::contract_info(DefaultStreamPayloadProcess::new()).await;
-    client.subscribe_item(ContractInfoStream::default()).await;
+    let mut client = BinanceUsdFutureMarketWebsocketClient::new_default().contract_info(DefaultStreamPayloadProcess::new()).await.unwrap();
+    client.subscribe_item(ContractInfoStream::default()).await.unwrap();
     sleep(Duration::from_secs(2)).await;
     client.close().await;
     sleep(Duration::from_millis(5000)).await;